@@ -0,0 +1,41 @@
+/// Base urls of the services taking part in the flow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoints {
+	/// Microsoft identity platform, serves `/devicecode` and `/token`
+	pub oauth: Box<str>,
+	/// Xbox Live user authentication, serves `/user/authenticate`
+	pub xbox_live: Box<str>,
+	/// Xbox secure token service, serves `/xsts/authorize`
+	pub xsts: Box<str>,
+	/// Minecraft services, serves login, entitlements and the profile
+	pub minecraft: Box<str>,
+}
+
+impl Default for Endpoints {
+	fn default() -> Self {
+		Self {
+			oauth: "https://login.microsoftonline.com/consumers/oauth2/v2.0".into(),
+			xbox_live: "https://user.auth.xboxlive.com".into(),
+			xsts: "https://xsts.auth.xboxlive.com".into(),
+			minecraft: "https://api.minecraftservices.com".into(),
+		}
+	}
+}
+
+impl Endpoints {
+	/// Every service under the same `base`, e.g. a local stand-in. Paths of
+	/// the services do not overlap, so they can share one server
+	pub fn at(base: &str) -> Self {
+		let base = base.trim_end_matches('/');
+		Self {
+			oauth: format!("{base}/oauth2/v2.0").into(),
+			xbox_live: base.into(),
+			xsts: base.into(),
+			minecraft: base.into(),
+		}
+	}
+
+	pub(crate) fn url(base: &str, path: &str) -> Result<url::Url, url::ParseError> {
+		url::Url::parse(&format!("{}/{path}", base.trim_end_matches('/')))
+	}
+}
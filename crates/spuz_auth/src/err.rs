@@ -0,0 +1,44 @@
+use thiserror::Error;
+use url::Url;
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+	#[error("Request failed: {0}")]
+	Request(
+		#[from]
+		#[source]
+		reqwest::Error,
+	),
+	#[error("{url} responded with status {status}: {body}")]
+	Status { url: Url, status: u16, body: Box<str> },
+	#[error("Invalid url: {0}")]
+	Url(
+		#[from]
+		#[source]
+		url::ParseError,
+	),
+	#[error("Authorization failed with {error}: {}", description.as_deref().unwrap_or_default())]
+	OAuth { error: Box<str>, description: Option<Box<str>> },
+	#[error("The user declined the authorization")]
+	Declined,
+	#[error("The device code expired before the user signed in")]
+	Expired,
+	#[error("The session has no refresh token, the user has to sign in again")]
+	NoRefreshToken,
+	#[error("The microsoft account has no xbox account, it has to be created first")]
+	NoXboxAccount,
+	#[error("The account belongs to a child and has to be added to a family by an adult")]
+	ChildAccount,
+	#[error("Xbox live is not available in the country of the account")]
+	XboxUnavailable,
+	#[error("The account needs adult verification on the xbox page")]
+	AdultVerification,
+	#[error("Xbox authorization failed with {code}: {message}")]
+	Xbox { code: u64, message: Box<str> },
+	#[error("The account does not own minecraft")]
+	NoGameOwnership,
+	#[error("The account has no minecraft profile yet")]
+	NoProfile,
+	#[error("Yggdrasil server responded with {error}: {message}")]
+	Yggdrasil { error: Box<str>, message: Box<str> },
+}
@@ -0,0 +1,73 @@
+//! Microsoft account authentication. The flow is
+//! [device code](Auth::device_code) → [msa token](Auth::poll) →
+//! [Xbox Live](Auth::xbox_live) → [XSTS](Auth::xsts) →
+//! [minecraft services](Auth::minecraft) → [profile](Auth::profile), or all
+//! of it after the device code with [login](Auth::login). Accounts of
+//! third party yggdrasil servers are signed in with [`Yggdrasil`]
+
+mod endpoints;
+mod err;
+mod minecraft;
+mod msa;
+#[cfg(test)]
+mod test;
+mod xbox;
+mod yggdrasil;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+
+pub use crate::{
+	endpoints::Endpoints,
+	err::AuthError,
+	minecraft::{Cape, MinecraftToken, Model, Profile, Session, Skin, TextureState},
+	msa::{DeviceCode, MsaToken, SCOPE},
+	xbox::XboxToken,
+	yggdrasil::{GameProfile, Yggdrasil, YggdrasilSession},
+};
+
+/// Client of the whole authentication flow. `client_id` is the id of the
+/// azure application of the launcher
+#[derive(Debug, Clone)]
+pub struct Auth {
+	pub client: reqwest::Client,
+	pub client_id: Box<str>,
+	pub endpoints: Endpoints,
+}
+
+impl Auth {
+	pub fn new(client_id: impl Into<Box<str>>) -> Self {
+		Self { client: reqwest::Client::new(), client_id: client_id.into(), endpoints: Endpoints::default() }
+	}
+
+	#[must_use]
+	pub fn with_client(mut self, client: reqwest::Client) -> Self {
+		self.client = client;
+		self
+	}
+
+	/// Talks to the `endpoints` instead of the official ones
+	#[must_use]
+	pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+		self.endpoints = endpoints;
+		self
+	}
+}
+
+pub(crate) fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|it| it.as_secs()).unwrap_or_default()
+}
+
+/// Parses the successful response, others are reported with their body
+pub(crate) async fn json<T: DeserializeOwned>(response: Response) -> Result<T, AuthError> {
+	let status = response.status();
+	if status.is_success() {
+		return Ok(response.json().await?);
+	}
+
+	let url = response.url().clone();
+	let body = response.text().await.unwrap_or_default().into();
+	Err(AuthError::Status { url, status: status.as_u16(), body })
+}
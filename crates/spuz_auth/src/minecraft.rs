@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use crate::{endpoints::Endpoints, json, now, Auth, AuthError, MsaToken, XboxToken};
+
+/// Entitlements granting the java edition
+const GAME: [&str; 2] = ["product_minecraft", "game_minecraft"];
+
+/// Token of the minecraft services, the one the game is launched with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinecraftToken {
+	pub access_token: Box<str>,
+	/// Unix timestamp in seconds
+	pub expires_at: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LoginResponse {
+	access_token: Box<str>,
+	expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Entitlements {
+	#[serde(default)]
+	items: Box<[Entitlement]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Entitlement {
+	name: Box<str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+	/// Uuid of the player without dashes
+	pub id: Box<str>,
+	pub name: Box<str>,
+	#[serde(default)]
+	pub skins: Box<[Skin]>,
+	#[serde(default)]
+	pub capes: Box<[Cape]>,
+}
+
+impl Profile {
+	pub fn active_skin(&self) -> Option<&Skin> {
+		self.skins.iter().find(|it| it.state == TextureState::Active)
+	}
+
+	pub fn active_cape(&self) -> Option<&Cape> {
+		self.capes.iter().find(|it| it.state == TextureState::Active)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TextureState {
+	Active,
+	Inactive,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Model {
+	/// Arms 4 pixels wide, also known as steve
+	#[default]
+	#[serde(alias = "CLASSIC")]
+	Classic,
+	/// Arms 3 pixels wide, also known as alex
+	#[serde(alias = "SLIM")]
+	Slim,
+}
+
+impl Model {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Classic => "classic",
+			Self::Slim => "slim",
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Skin {
+	pub id: Box<str>,
+	pub state: TextureState,
+	pub url: Box<str>,
+	pub variant: Model,
+	pub alias: Option<Box<str>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cape {
+	pub id: Box<str>,
+	pub state: TextureState,
+	pub url: Box<str>,
+	pub alias: Option<Box<str>>,
+}
+
+/// Signed in account, everything needed to launch the game and to refresh
+/// the session once it expires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+	pub profile: Profile,
+	pub access_token: Box<str>,
+	/// Unix timestamp in seconds
+	pub expires_at: u64,
+	pub xuid: Option<Box<str>>,
+	/// Refresh token of the microsoft account
+	pub refresh_token: Option<Box<str>>,
+	/// Azure application the session was issued to
+	pub client_id: Box<str>,
+}
+
+impl Session {
+	pub fn is_expired(&self) -> bool {
+		now() >= self.expires_at
+	}
+
+	/// Player to launch the game with
+	#[cfg(feature = "wrench")]
+	pub fn player(&self) -> spuz_wrench::Player {
+		spuz_wrench::Player {
+			username: self.profile.name.to_string(),
+			uuid: self.profile.id.to_string(),
+			access_token: Some(self.access_token.to_string()),
+			client_id: Some(self.client_id.to_string()),
+			xuid: self.xuid.as_deref().map(ToString::to_string),
+			user_type: spuz_wrench::UserType::Msa,
+		}
+	}
+}
+
+impl Auth {
+	/// Signs into the minecraft services with the XSTS token
+	pub async fn minecraft(&self, xsts: &XboxToken) -> Result<MinecraftToken, AuthError> {
+		let url = Endpoints::url(&self.endpoints.minecraft, "authentication/login_with_xbox")?;
+		let body = json!({ "identityToken": format!("XBL3.0 x={};{}", xsts.user_hash, xsts.token) });
+		let response = self.client.post(url).json(&body).send().await?;
+		let login: LoginResponse = json(response).await?;
+
+		Ok(MinecraftToken { access_token: login.access_token, expires_at: now() + login.expires_in })
+	}
+
+	/// Whether the account owns the java edition
+	pub async fn owns_game(&self, access_token: &str) -> Result<bool, AuthError> {
+		let url = Endpoints::url(&self.endpoints.minecraft, "entitlements/mcstore")?;
+		let response = self.client.get(url).bearer_auth(access_token).send().await?;
+		let entitlements: Entitlements = json(response).await?;
+
+		Ok(entitlements.items.iter().any(|it| GAME.contains(&&*it.name)))
+	}
+
+	/// Profile of the account, fails with [`AuthError::NoProfile`] if the
+	/// player has not picked a name yet
+	pub async fn profile(&self, access_token: &str) -> Result<Profile, AuthError> {
+		let url = Endpoints::url(&self.endpoints.minecraft, "minecraft/profile")?;
+		let response = self.client.get(url).bearer_auth(access_token).send().await?;
+
+		if response.status() == reqwest::StatusCode::NOT_FOUND {
+			return Err(AuthError::NoProfile);
+		}
+		json(response).await
+	}
+
+	/// Goes through the rest of the flow with the microsoft account token
+	pub async fn login(&self, msa: &MsaToken) -> Result<Session, AuthError> {
+		let xbox_live = self.xbox_live(&msa.access_token).await?;
+		let xsts = self.xsts(&xbox_live).await?;
+		let minecraft = self.minecraft(&xsts).await?;
+
+		if !self.owns_game(&minecraft.access_token).await? {
+			return Err(AuthError::NoGameOwnership);
+		}
+		let profile = self.profile(&minecraft.access_token).await?;
+
+		debug!("Signed in as {}", profile.name);
+
+		Ok(Session {
+			profile,
+			access_token: minecraft.access_token,
+			expires_at: minecraft.expires_at,
+			xuid: xsts.xuid,
+			refresh_token: msa.refresh_token.clone(),
+			client_id: self.client_id.clone(),
+		})
+	}
+
+	/// Signs in again with the refresh token of the `session`
+	pub async fn refresh_session(&self, session: &Session) -> Result<Session, AuthError> {
+		let refresh_token = session.refresh_token.as_deref().ok_or(AuthError::NoRefreshToken)?;
+		let mut msa = self.refresh(refresh_token).await?;
+		if msa.refresh_token.is_none() {
+			msa.refresh_token.clone_from(&session.refresh_token);
+		}
+		self.login(&msa).await
+	}
+}
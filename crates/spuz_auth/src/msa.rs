@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{endpoints::Endpoints, json, now, Auth, AuthError};
+
+/// Scopes requested from the microsoft account, `offline_access` is what
+/// gives out the refresh token
+pub const SCOPE: &str = "XboxLive.signin offline_access";
+
+/// Slowdown requested by `slow_down`, as the device code flow defines it
+const SLOW_DOWN: u64 = 5;
+
+/// Code the user enters at [`verification_uri`](Self::verification_uri) to
+/// sign in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceCode {
+	pub device_code: Box<str>,
+	pub user_code: Box<str>,
+	pub verification_uri: Box<str>,
+	/// Seconds the code is valid for
+	pub expires_in: u64,
+	/// Seconds to wait between polls
+	#[serde(default = "interval")]
+	pub interval: u64,
+	pub message: Option<Box<str>>,
+}
+
+fn interval() -> u64 {
+	5
+}
+
+impl DeviceCode {
+	/// Instructions for the user, as microsoft words them if present
+	pub fn message(&self) -> String {
+		match &self.message {
+			Some(message) => message.to_string(),
+			None => format!("To sign in, open {} and enter the code {}", self.verification_uri, self.user_code),
+		}
+	}
+}
+
+/// Microsoft account token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MsaToken {
+	pub access_token: Box<str>,
+	pub refresh_token: Option<Box<str>>,
+	/// Unix timestamp in seconds
+	pub expires_at: u64,
+}
+
+impl MsaToken {
+	pub fn is_expired(&self) -> bool {
+		now() >= self.expires_at
+	}
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+	access_token: Box<str>,
+	refresh_token: Option<Box<str>>,
+	expires_in: u64,
+}
+
+impl From<TokenResponse> for MsaToken {
+	fn from(value: TokenResponse) -> Self {
+		Self { access_token: value.access_token, refresh_token: value.refresh_token, expires_at: now() + value.expires_in }
+	}
+}
+
+#[derive(Debug, Deserialize)]
+struct OAuthError {
+	error: Box<str>,
+	error_description: Option<Box<str>>,
+}
+
+impl From<OAuthError> for AuthError {
+	fn from(value: OAuthError) -> Self {
+		Self::OAuth { error: value.error, description: value.error_description }
+	}
+}
+
+impl Auth {
+	/// Starts the device code flow. Show [`DeviceCode::message`] to the user
+	/// and [poll](Self::poll) for the token
+	pub async fn device_code(&self) -> Result<DeviceCode, AuthError> {
+		let url = Endpoints::url(&self.endpoints.oauth, "devicecode")?;
+		let response = self.client.post(url).form(&[("client_id", &*self.client_id), ("scope", SCOPE)]).send().await?;
+		json(response).await
+	}
+
+	/// Waits until the user signs in with the `code`
+	pub async fn poll(&self, code: &DeviceCode) -> Result<MsaToken, AuthError> {
+		let deadline = now() + code.expires_in;
+		let mut interval = code.interval;
+
+		loop {
+			tokio::time::sleep(Duration::from_secs(interval)).await;
+
+			let form = [
+				("client_id", &*self.client_id),
+				("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+				("device_code", &*code.device_code),
+			];
+			match self.token(&form).await? {
+				Ok(token) => return Ok(token.into()),
+				Err(err) => match &*err.error {
+					"authorization_pending" => {}
+					"slow_down" => interval += SLOW_DOWN,
+					"authorization_declined" => return Err(AuthError::Declined),
+					"expired_token" => return Err(AuthError::Expired),
+					_ => return Err(err.into()),
+				},
+			}
+
+			if now() >= deadline {
+				return Err(AuthError::Expired);
+			}
+			debug!("Authorization is pending, polling again in {interval}s");
+		}
+	}
+
+	/// Exchanges the refresh token of an earlier sign in for a new token
+	pub async fn refresh(&self, refresh_token: &str) -> Result<MsaToken, AuthError> {
+		let form = [
+			("client_id", &*self.client_id),
+			("grant_type", "refresh_token"),
+			("refresh_token", refresh_token),
+			("scope", SCOPE),
+		];
+		Ok(self.token(&form).await??.into())
+	}
+
+	/// Oauth errors come with a status of 400 and are up to the caller
+	async fn token(&self, form: &[(&str, &str)]) -> Result<Result<TokenResponse, OAuthError>, AuthError> {
+		let url = Endpoints::url(&self.endpoints.oauth, "token")?;
+		let response = self.client.post(url).form(form).send().await?;
+
+		if response.status().is_client_error() {
+			let url = response.url().clone();
+			let status = response.status().as_u16();
+			let body = response.text().await?;
+			return match serde_json::from_str(&body) {
+				Ok(err) => Ok(Err(err)),
+				Err(_) => Err(AuthError::Status { url, status, body: body.into() }),
+			};
+		}
+
+		json(response).await.map(Ok)
+	}
+}
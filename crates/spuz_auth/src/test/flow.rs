@@ -0,0 +1,256 @@
+use mockito::{Matcher, Mock, Server, ServerGuard};
+use serde_json::json;
+
+use crate::{Auth, AuthError, DeviceCode, Endpoints, MsaToken};
+
+fn auth(server: &ServerGuard) -> Auth {
+	Auth::new("client").with_endpoints(Endpoints::at(&server.url()))
+}
+
+fn msa() -> MsaToken {
+	MsaToken { access_token: "msa".into(), refresh_token: Some("refresh".into()), expires_at: u64::MAX }
+}
+
+async fn xbox(server: &mut ServerGuard) -> (Mock, Mock) {
+	let xbl = server
+		.mock("POST", "/user/authenticate")
+		.match_body(Matcher::PartialJson(json!({ "Properties": { "RpsTicket": "d=msa" } })))
+		.with_body(json!({ "Token": "xbl", "DisplayClaims": { "xui": [{ "uhs": "hash" }] } }).to_string())
+		.create_async()
+		.await;
+	let xsts = server
+		.mock("POST", "/xsts/authorize")
+		.match_body(Matcher::PartialJson(json!({
+			"Properties": { "UserTokens": ["xbl"] },
+			"RelyingParty": "rp://api.minecraftservices.com/"
+		})))
+		.with_body(json!({ "Token": "xsts", "DisplayClaims": { "xui": [{ "uhs": "hash", "xid": "2535" }] } }).to_string())
+		.create_async()
+		.await;
+	(xbl, xsts)
+}
+
+async fn minecraft(server: &mut ServerGuard, items: &[&str]) -> Mock {
+	let login = server
+		.mock("POST", "/authentication/login_with_xbox")
+		.match_body(Matcher::Json(json!({ "identityToken": "XBL3.0 x=hash;xsts" })))
+		.with_body(json!({ "access_token": "mc", "token_type": "Bearer", "expires_in": 86400 }).to_string())
+		.create_async()
+		.await;
+	let items: Vec<_> = items.iter().map(|it| json!({ "name": it, "signature": "sig" })).collect();
+	server
+		.mock("GET", "/entitlements/mcstore")
+		.match_header("authorization", "Bearer mc")
+		.with_body(json!({ "items": items, "signature": "sig" }).to_string())
+		.create_async()
+		.await;
+	login
+}
+
+async fn profile(server: &mut ServerGuard) -> Mock {
+	server
+		.mock("GET", "/minecraft/profile")
+		.match_header("authorization", "Bearer mc")
+		.with_body(
+			json!({
+				"id": "069a79f444e94726a5befca90e38aaf5",
+				"name": "Notch",
+				"skins": [{ "id": "s", "state": "ACTIVE", "url": "http://textures/s", "variant": "CLASSIC" }],
+				"capes": []
+			})
+			.to_string(),
+		)
+		.create_async()
+		.await
+}
+
+#[tokio::test]
+async fn device_code_polls_until_signed_in() {
+	let mut server = Server::new_async().await;
+	server
+		.mock("POST", "/oauth2/v2.0/devicecode")
+		.match_body(Matcher::UrlEncoded("client_id".into(), "client".into()))
+		.with_body(
+			json!({
+				"device_code": "device",
+				"user_code": "ABCD",
+				"verification_uri": "https://microsoft.com/link",
+				"expires_in": 900,
+				"interval": 0
+			})
+			.to_string(),
+		)
+		.create_async()
+		.await;
+	let pending = server
+		.mock("POST", "/oauth2/v2.0/token")
+		.match_body(Matcher::UrlEncoded("device_code".into(), "device".into()))
+		.with_status(400)
+		.with_body(json!({ "error": "authorization_pending" }).to_string())
+		.expect_at_least(1)
+		.create_async()
+		.await;
+
+	let auth = auth(&server);
+	let code = auth.device_code().await.unwrap();
+	assert_eq!(&*code.user_code, "ABCD");
+	assert!(code.message().contains("ABCD"));
+
+	let polling = tokio::spawn({
+		let auth = auth.clone();
+		let code = code.clone();
+		async move { auth.poll(&code).await }
+	});
+	while !pending.matched_async().await {
+		tokio::task::yield_now().await;
+	}
+	pending.remove_async().await;
+	server
+		.mock("POST", "/oauth2/v2.0/token")
+		.with_body(json!({ "access_token": "msa", "refresh_token": "refresh", "expires_in": 3600 }).to_string())
+		.create_async()
+		.await;
+
+	let token = polling.await.unwrap().unwrap();
+	assert_eq!(&*token.access_token, "msa");
+	assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+	assert!(!token.is_expired());
+}
+
+#[tokio::test]
+async fn declined_device_code() {
+	let mut server = Server::new_async().await;
+	server
+		.mock("POST", "/oauth2/v2.0/token")
+		.with_status(400)
+		.with_body(json!({ "error": "authorization_declined", "error_description": "no" }).to_string())
+		.create_async()
+		.await;
+	let code = DeviceCode {
+		device_code: "device".into(),
+		user_code: "ABCD".into(),
+		verification_uri: "https://microsoft.com/link".into(),
+		expires_in: 900,
+		interval: 0,
+		message: None,
+	};
+
+	let err = auth(&server).poll(&code).await.unwrap_err();
+	assert!(matches!(err, AuthError::Declined), "{err}");
+}
+
+#[tokio::test]
+async fn full_login() {
+	let mut server = Server::new_async().await;
+	let (xbl, xsts) = xbox(&mut server).await;
+	minecraft(&mut server, &["product_minecraft", "game_minecraft"]).await;
+	profile(&mut server).await;
+
+	let session = auth(&server).login(&msa()).await.unwrap();
+	xbl.assert_async().await;
+	xsts.assert_async().await;
+
+	assert_eq!(&*session.profile.name, "Notch");
+	assert_eq!(&*session.access_token, "mc");
+	assert_eq!(session.xuid.as_deref(), Some("2535"));
+	assert_eq!(session.refresh_token.as_deref(), Some("refresh"));
+	assert!(!session.is_expired());
+
+	#[cfg(feature = "wrench")]
+	{
+		let player = session.player();
+		assert_eq!(player.username, "Notch");
+		assert_eq!(player.uuid, "069a79f444e94726a5befca90e38aaf5");
+		assert_eq!(player.access_token.as_deref(), Some("mc"));
+		assert_eq!(player.client_id.as_deref(), Some("client"));
+	}
+}
+
+#[tokio::test]
+async fn refresh_session() {
+	let mut server = Server::new_async().await;
+	let refresh = server
+		.mock("POST", "/oauth2/v2.0/token")
+		.match_body(Matcher::AllOf(vec![
+			Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+			Matcher::UrlEncoded("refresh_token".into(), "refresh".into()),
+		]))
+		.with_body(json!({ "access_token": "msa", "refresh_token": "rotated", "expires_in": 3600 }).to_string())
+		.create_async()
+		.await;
+	xbox(&mut server).await;
+	minecraft(&mut server, &["game_minecraft"]).await;
+	profile(&mut server).await;
+
+	let auth = auth(&server);
+	let session = auth.login(&msa()).await.unwrap();
+	let refreshed = auth.refresh_session(&session).await.unwrap();
+
+	refresh.assert_async().await;
+	assert_eq!(refreshed.refresh_token.as_deref(), Some("rotated"));
+
+	let without = crate::Session { refresh_token: None, ..session };
+	assert!(matches!(auth.refresh_session(&without).await, Err(AuthError::NoRefreshToken)));
+}
+
+#[tokio::test]
+async fn child_account() {
+	let mut server = Server::new_async().await;
+	server
+		.mock("POST", "/user/authenticate")
+		.with_body(json!({ "Token": "xbl", "DisplayClaims": { "xui": [{ "uhs": "hash" }] } }).to_string())
+		.create_async()
+		.await;
+	server
+		.mock("POST", "/xsts/authorize")
+		.with_status(401)
+		.with_body(json!({ "Identity": "0", "XErr": 2_148_916_238_u64, "Message": "", "Redirect": "" }).to_string())
+		.create_async()
+		.await;
+
+	let err = auth(&server).login(&msa()).await.unwrap_err();
+	assert!(matches!(err, AuthError::ChildAccount), "{err}");
+}
+
+#[tokio::test]
+async fn no_xbox_account() {
+	let mut server = Server::new_async().await;
+	xbox(&mut server).await.1.remove_async().await;
+	server
+		.mock("POST", "/xsts/authorize")
+		.with_status(401)
+		.with_body(json!({ "XErr": 2_148_916_233_u64 }).to_string())
+		.create_async()
+		.await;
+
+	let err = auth(&server).login(&msa()).await.unwrap_err();
+	assert!(matches!(err, AuthError::NoXboxAccount), "{err}");
+}
+
+#[tokio::test]
+async fn no_game_ownership() {
+	let mut server = Server::new_async().await;
+	xbox(&mut server).await;
+	minecraft(&mut server, &[]).await;
+	let profile = profile(&mut server).await.expect(0);
+
+	let err = auth(&server).login(&msa()).await.unwrap_err();
+	assert!(matches!(err, AuthError::NoGameOwnership), "{err}");
+	profile.assert_async().await;
+}
+
+#[tokio::test]
+async fn no_profile() {
+	let mut server = Server::new_async().await;
+	xbox(&mut server).await;
+	minecraft(&mut server, &["game_minecraft"]).await;
+	server
+		.mock("GET", "/minecraft/profile")
+		.with_status(404)
+		.with_body(json!({ "path": "/minecraft/profile", "error": "NOT_FOUND" }).to_string())
+		.create_async()
+		.await;
+
+	let err = auth(&server).login(&msa()).await.unwrap_err();
+	assert!(matches!(err, AuthError::NoProfile), "{err}");
+}
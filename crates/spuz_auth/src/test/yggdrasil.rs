@@ -0,0 +1,135 @@
+use mockito::{Matcher, Server, ServerGuard};
+use serde_json::json;
+
+use crate::{AuthError, GameProfile, Yggdrasil, YggdrasilSession};
+
+fn yggdrasil(server: &ServerGuard) -> Yggdrasil {
+	Yggdrasil::new(format!("{}/api/yggdrasil", server.url()))
+}
+
+fn session() -> YggdrasilSession {
+	YggdrasilSession {
+		access_token: "access".into(),
+		client_token: "client".into(),
+		available_profiles: [profile("a"), profile("b")].into(),
+		selected_profile: None,
+	}
+}
+
+fn profile(name: &str) -> GameProfile {
+	GameProfile { id: format!("{name:0>32}").into(), name: name.into() }
+}
+
+#[tokio::test]
+async fn authenticate() {
+	let mut server = Server::new_async().await;
+	server
+		.mock("POST", "/api/yggdrasil/authserver/authenticate")
+		.match_body(Matcher::PartialJson(json!({
+			"agent": { "name": "Minecraft", "version": 1 },
+			"username": "player@example.com",
+			"password": "password",
+			"clientToken": "client"
+		})))
+		.with_body(
+			json!({
+				"accessToken": "access",
+				"clientToken": "client",
+				"availableProfiles": [profile("Steve")],
+				"selectedProfile": profile("Steve")
+			})
+			.to_string(),
+		)
+		.create_async()
+		.await;
+
+	let session =
+		yggdrasil(&server).with_client_token("client").authenticate("player@example.com", "password").await.unwrap();
+	assert_eq!(&*session.access_token, "access");
+	assert_eq!(session.selected_profile, Some(profile("Steve")));
+
+	#[cfg(feature = "wrench")]
+	{
+		let player = session.player().unwrap();
+		assert_eq!(player.username, "Steve");
+		assert_eq!(player.user_type, spuz_wrench::UserType::Mojang);
+	}
+}
+
+#[tokio::test]
+async fn invalid_credentials() {
+	let mut server = Server::new_async().await;
+	server
+		.mock("POST", "/api/yggdrasil/authserver/authenticate")
+		.with_status(403)
+		.with_body(
+			json!({ "error": "ForbiddenOperationException", "errorMessage": "Invalid credentials. Invalid username or password." })
+				.to_string(),
+		)
+		.create_async()
+		.await;
+
+	let err = yggdrasil(&server).authenticate("player", "wrong").await.unwrap_err();
+	assert!(matches!(err, AuthError::Yggdrasil { error, .. } if &*error == "ForbiddenOperationException"));
+}
+
+#[tokio::test]
+async fn refresh_selects_profile() {
+	let mut server = Server::new_async().await;
+	server
+		.mock("POST", "/api/yggdrasil/authserver/refresh")
+		.match_body(Matcher::Json(json!({
+			"accessToken": "access",
+			"clientToken": "client",
+			"requestUser": false,
+			"selectedProfile": profile("b")
+		})))
+		.with_body(json!({ "accessToken": "new", "clientToken": "client", "selectedProfile": profile("b") }).to_string())
+		.create_async()
+		.await;
+
+	let refreshed = yggdrasil(&server).refresh(&session(), Some(&profile("b"))).await.unwrap();
+	assert_eq!(&*refreshed.access_token, "new");
+	assert_eq!(refreshed.selected_profile, Some(profile("b")));
+	assert_eq!(refreshed.available_profiles.len(), 2);
+}
+
+#[tokio::test]
+async fn validate_and_invalidate() {
+	let mut server = Server::new_async().await;
+	let tokens = json!({ "accessToken": "access", "clientToken": "client" });
+	server
+		.mock("POST", "/api/yggdrasil/authserver/validate")
+		.match_body(Matcher::Json(tokens.clone()))
+		.with_status(204)
+		.create_async()
+		.await;
+	let invalidate = server
+		.mock("POST", "/api/yggdrasil/authserver/invalidate")
+		.match_body(Matcher::Json(tokens))
+		.with_status(204)
+		.create_async()
+		.await;
+
+	let yggdrasil = yggdrasil(&server);
+	assert!(yggdrasil.validate(&session()).await.unwrap());
+	yggdrasil.invalidate(&session()).await.unwrap();
+	invalidate.assert_async().await;
+
+	server.reset();
+	server
+		.mock("POST", "/api/yggdrasil/authserver/validate")
+		.with_status(403)
+		.with_body(json!({ "error": "ForbiddenOperationException", "errorMessage": "Invalid token." }).to_string())
+		.create_async()
+		.await;
+	assert!(!yggdrasil.validate(&session()).await.unwrap());
+}
+
+#[tokio::test]
+async fn prefetched_metadata() {
+	let mut server = Server::new_async().await;
+	server.mock("GET", "/api/yggdrasil").with_body("{}").create_async().await;
+
+	assert_eq!(yggdrasil(&server).prefetched().await.unwrap(), "e30=");
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{endpoints::Endpoints, json, Auth, AuthError};
+
+/// Relying party of the minecraft services
+const MINECRAFT_PARTY: &str = "rp://api.minecraftservices.com/";
+
+/// Token of Xbox Live or XSTS with the user hash it was issued for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XboxToken {
+	pub token: Box<str>,
+	/// `uhs` claim, the minecraft login needs it along with the token
+	pub user_hash: Box<str>,
+	/// `xid` claim, present only if the relying party shares it
+	pub xuid: Option<Box<str>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XboxResponse {
+	token: Box<str>,
+	display_claims: DisplayClaims,
+}
+
+#[derive(Debug, Deserialize)]
+struct DisplayClaims {
+	xui: Box<[Claims]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Claims {
+	uhs: Box<str>,
+	xid: Option<Box<str>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct XboxError {
+	#[serde(rename = "XErr")]
+	code: u64,
+	#[serde(rename = "Message", default)]
+	message: Box<str>,
+}
+
+impl From<XboxError> for AuthError {
+	fn from(value: XboxError) -> Self {
+		match value.code {
+			2_148_916_233 => Self::NoXboxAccount,
+			2_148_916_235 => Self::XboxUnavailable,
+			2_148_916_236 | 2_148_916_237 => Self::AdultVerification,
+			2_148_916_238 => Self::ChildAccount,
+			code => Self::Xbox { code, message: value.message },
+		}
+	}
+}
+
+impl TryFrom<XboxResponse> for XboxToken {
+	type Error = AuthError;
+
+	fn try_from(value: XboxResponse) -> Result<Self, Self::Error> {
+		let Some(claims) = value.display_claims.xui.into_vec().into_iter().next() else {
+			return Err(AuthError::Xbox { code: 0, message: "Response has no user claims".into() });
+		};
+		Ok(Self { token: value.token, user_hash: claims.uhs, xuid: claims.xid })
+	}
+}
+
+impl Auth {
+	/// Signs into Xbox Live with the access token of the microsoft account
+	pub async fn xbox_live(&self, msa_token: &str) -> Result<XboxToken, AuthError> {
+		let url = Endpoints::url(&self.endpoints.xbox_live, "user/authenticate")?;
+		let body = json!({
+			"Properties": {
+				"AuthMethod": "RPS",
+				"SiteName": "user.auth.xboxlive.com",
+				"RpsTicket": format!("d={msa_token}"),
+			},
+			"RelyingParty": "http://auth.xboxlive.com",
+			"TokenType": "JWT",
+		});
+		let response = self.client.post(url).json(&body).send().await?;
+		json::<XboxResponse>(response).await?.try_into()
+	}
+
+	/// Authorizes the Xbox Live token for the minecraft services. Accounts that
+	/// can not play are reported with their own errors, e.g.
+	/// [`AuthError::NoXboxAccount`] or [`AuthError::ChildAccount`]
+	pub async fn xsts(&self, xbox_live: &XboxToken) -> Result<XboxToken, AuthError> {
+		let url = Endpoints::url(&self.endpoints.xsts, "xsts/authorize")?;
+		let body = json!({
+			"Properties": {
+				"SandboxId": "RETAIL",
+				"UserTokens": [&*xbox_live.token],
+			},
+			"RelyingParty": MINECRAFT_PARTY,
+			"TokenType": "JWT",
+		});
+		let response = self.client.post(url).json(&body).send().await?;
+
+		if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+			let url = response.url().clone();
+			let body = response.text().await?;
+			return Err(match serde_json::from_str::<XboxError>(&body) {
+				Ok(err) => err.into(),
+				Err(_) => AuthError::Status { url, status: 401, body: body.into() },
+			});
+		}
+
+		let mut token: XboxToken = json::<XboxResponse>(response).await?.try_into()?;
+		if token.xuid.is_none() {
+			token.xuid.clone_from(&xbox_live.xuid);
+		}
+		Ok(token)
+	}
+}
@@ -0,0 +1,184 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::debug;
+
+use crate::{endpoints::Endpoints, AuthError};
+
+/// Client of a yggdrasil server located at [`api`](Self::api), the api root
+/// authlib-injector is pointed at as well
+///
+/// # Example
+/// ```no_run
+/// use spuz_auth::Yggdrasil;
+///
+/// # async fn run() -> Result<(), spuz_auth::AuthError> {
+/// let yggdrasil = Yggdrasil::new("https://skins.example.com/api/yggdrasil");
+/// let session = yggdrasil.authenticate("player@example.com", "password").await?;
+/// let prefetched = yggdrasil.prefetched().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Yggdrasil {
+	pub client: reqwest::Client,
+	pub api: Box<str>,
+	/// Token identifying the launcher, the server issues one if missing
+	pub client_token: Option<Box<str>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameProfile {
+	/// Uuid of the player without dashes
+	pub id: Box<str>,
+	pub name: Box<str>,
+}
+
+/// Signed in yggdrasil account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YggdrasilSession {
+	pub access_token: Box<str>,
+	pub client_token: Box<str>,
+	#[serde(default)]
+	pub available_profiles: Box<[GameProfile]>,
+	/// Profile the game is launched with, has to be
+	/// [selected](Yggdrasil::refresh) if the account has several
+	pub selected_profile: Option<GameProfile>,
+}
+
+impl YggdrasilSession {
+	/// Player to launch the game with, if a profile is selected
+	#[cfg(feature = "wrench")]
+	pub fn player(&self) -> Option<spuz_wrench::Player> {
+		let profile = self.selected_profile.as_ref()?;
+		Some(spuz_wrench::Player {
+			username: profile.name.to_string(),
+			uuid: profile.id.to_string(),
+			access_token: Some(self.access_token.to_string()),
+			user_type: spuz_wrench::UserType::Mojang,
+			..Default::default()
+		})
+	}
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YggdrasilError {
+	error: Box<str>,
+	#[serde(default)]
+	error_message: Box<str>,
+}
+
+impl Yggdrasil {
+	pub fn new(api: impl Into<Box<str>>) -> Self {
+		Self { client: reqwest::Client::new(), api: api.into(), client_token: None }
+	}
+
+	#[must_use]
+	pub fn with_client(mut self, client: reqwest::Client) -> Self {
+		self.client = client;
+		self
+	}
+
+	#[must_use]
+	pub fn with_client_token(mut self, client_token: impl Into<Box<str>>) -> Self {
+		self.client_token = Some(client_token.into());
+		self
+	}
+
+	/// Metadata of the server, the response of the api root
+	pub async fn metadata(&self) -> Result<Box<str>, AuthError> {
+		let response = self.client.get(&*self.api).send().await?;
+		Ok(check(response).await?.text().await?.into())
+	}
+
+	/// [Metadata](Self::metadata) in base64, as authlib-injector takes it
+	/// prefetched
+	pub async fn prefetched(&self) -> Result<String, AuthError> {
+		Ok(STANDARD.encode(&*self.metadata().await?))
+	}
+
+	/// Signs in with the credentials of the account
+	pub async fn authenticate(&self, username: &str, password: &str) -> Result<YggdrasilSession, AuthError> {
+		let mut body = json!({
+			"agent": { "name": "Minecraft", "version": 1 },
+			"username": username,
+			"password": password,
+			"requestUser": false,
+		});
+		if let Some(client_token) = &self.client_token {
+			body["clientToken"] = Value::from(&**client_token);
+		}
+
+		let session: YggdrasilSession = self.post("authserver/authenticate", &body).await?.json().await?;
+		debug!("Signed into {} with {} profiles", self.api, session.available_profiles.len());
+		Ok(session)
+	}
+
+	/// Issues a new access token for the session, invalidating the old one.
+	/// Selects the `profile` if given
+	pub async fn refresh(
+		&self,
+		session: &YggdrasilSession,
+		profile: Option<&GameProfile>,
+	) -> Result<YggdrasilSession, AuthError> {
+		let mut body = json!({
+			"accessToken": &*session.access_token,
+			"clientToken": &*session.client_token,
+			"requestUser": false,
+		});
+		if let Some(profile) = profile {
+			body["selectedProfile"] = json!(profile);
+		}
+
+		let mut refreshed: YggdrasilSession = self.post("authserver/refresh", &body).await?.json().await?;
+		if refreshed.available_profiles.is_empty() {
+			refreshed.available_profiles.clone_from(&session.available_profiles);
+		}
+		Ok(refreshed)
+	}
+
+	/// Whether the access token of the session can still be used
+	pub async fn validate(&self, session: &YggdrasilSession) -> Result<bool, AuthError> {
+		match self.post("authserver/validate", &tokens(session)).await {
+			Ok(_) => Ok(true),
+			Err(AuthError::Yggdrasil { .. }) => Ok(false),
+			Err(err) => Err(err),
+		}
+	}
+
+	/// Revokes the access token of the session
+	pub async fn invalidate(&self, session: &YggdrasilSession) -> Result<(), AuthError> {
+		self.post("authserver/invalidate", &tokens(session)).await?;
+		Ok(())
+	}
+
+	async fn post(&self, path: &str, body: &Value) -> Result<Response, AuthError> {
+		let url = Endpoints::url(&self.api, path)?;
+		check(self.client.post(url).json(body).send().await?).await
+	}
+}
+
+fn tokens(session: &YggdrasilSession) -> Value {
+	json!({ "accessToken": &*session.access_token, "clientToken": &*session.client_token })
+}
+
+/// Errors of the protocol come with a client error status and a json body
+async fn check(response: Response) -> Result<Response, AuthError> {
+	let status = response.status();
+	if status.is_success() {
+		return Ok(response);
+	}
+
+	let url = response.url().clone();
+	let body = response.text().await?;
+	match serde_json::from_str::<YggdrasilError>(&body) {
+		Ok(err) if status.is_client_error() => Err(AuthError::Yggdrasil { error: err.error, message: err.error_message }),
+		_ if status == StatusCode::FORBIDDEN => {
+			Err(AuthError::Yggdrasil { error: "ForbiddenOperationException".into(), message: body.into() })
+		}
+		_ => Err(AuthError::Status { url, status: status.as_u16(), body: body.into() }),
+	}
+}
@@ -0,0 +1,256 @@
+//! Accounts signed into the launcher. Everything secret is encrypted at rest
+//! with the key of the caller, access to the store is serialized across
+//! processes with a lock file
+
+use std::{
+	path::Path,
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+	aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+	ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use spuz_auth::{Auth, Session, Yggdrasil, YggdrasilSession};
+use spuz_wrench::{offline_uuid, validate_username, Player, UserType, UsernameError};
+use tokio::fs::{self, try_exists};
+use tracing::debug;
+
+use crate::{sync, Error, Result};
+
+/// Key the accounts are encrypted with
+pub type AccountsKey = [u8; 32];
+
+/// Sessions expiring sooner than this are refreshed before launch
+const REFRESH_MARGIN: u64 = 5 * 60;
+
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Credentials {
+	Microsoft(Session),
+	Offline,
+	Yggdrasil { api: Box<str>, session: YggdrasilSession },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+	/// Uuid of the player, identifies the account in the store
+	pub uuid: Box<str>,
+	pub name: Box<str>,
+	pub credentials: Credentials,
+}
+
+impl Account {
+	pub fn microsoft(session: Session) -> Self {
+		Self {
+			uuid: session.profile.id.clone(),
+			name: session.profile.name.clone(),
+			credentials: Credentials::Microsoft(session),
+		}
+	}
+
+	pub fn offline(name: &str) -> Result<Self, UsernameError> {
+		validate_username(name)?;
+		Ok(Self { uuid: offline_uuid(name).into(), name: name.into(), credentials: Credentials::Offline })
+	}
+
+	/// Account of the selected profile of the session, if there is one
+	pub fn yggdrasil(api: impl Into<Box<str>>, session: YggdrasilSession) -> Option<Self> {
+		let profile = session.selected_profile.clone()?;
+		Some(Self {
+			uuid: profile.id,
+			name: profile.name,
+			credentials: Credentials::Yggdrasil { api: api.into(), session },
+		})
+	}
+
+	/// Unix timestamp in seconds the access token expires at, if it is known
+	pub fn expires_at(&self) -> Option<u64> {
+		match &self.credentials {
+			Credentials::Microsoft(session) => Some(session.expires_at),
+			Credentials::Offline | Credentials::Yggdrasil { .. } => None,
+		}
+	}
+
+	pub fn player(&self) -> Player {
+		match &self.credentials {
+			Credentials::Microsoft(session) => session.player(),
+			Credentials::Yggdrasil { session, .. } => Player {
+				username: self.name.to_string(),
+				uuid: self.uuid.to_string(),
+				access_token: Some(session.access_token.to_string()),
+				user_type: UserType::Mojang,
+				..Player::default()
+			},
+			Credentials::Offline => {
+				Player { user_type: UserType::Legacy, ..Player::new(self.name.to_string(), self.uuid.to_string()) }
+			}
+		}
+	}
+
+	/// Refreshes the credentials if they are about to expire, returns whether
+	/// they changed
+	async fn refresh(&mut self, auth: &Auth) -> Result<bool> {
+		match &mut self.credentials {
+			Credentials::Microsoft(session) => {
+				if now() + REFRESH_MARGIN < session.expires_at {
+					return Ok(false);
+				}
+				*session = auth.refresh_session(session).await?;
+				self.name.clone_from(&session.profile.name);
+			}
+			Credentials::Yggdrasil { api, session } => {
+				let yggdrasil = Yggdrasil::new(&**api).with_client(auth.client.clone());
+				if yggdrasil.validate(session).await? {
+					return Ok(false);
+				}
+				*session = yggdrasil.refresh(session, None).await?;
+			}
+			Credentials::Offline => return Ok(false),
+		}
+
+		debug!("Account {} refreshed", self.name);
+		Ok(true)
+	}
+}
+
+/// Account as it is written to the disk, with its credentials encrypted
+#[derive(Debug, Serialize, Deserialize)]
+struct Stored {
+	uuid: Box<str>,
+	name: Box<str>,
+	/// Base64 of the nonce followed by the encrypted credentials
+	secret: Box<str>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredAccounts {
+	accounts: Vec<Stored>,
+}
+
+pub struct Accounts {
+	pub path: Arc<Path>,
+	lock_path: Arc<Path>,
+	cipher: ChaCha20Poly1305,
+}
+
+impl std::fmt::Debug for Accounts {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Accounts").field("path", &self.path).finish_non_exhaustive()
+	}
+}
+
+impl Accounts {
+	pub(crate) fn from_folder_root(root: &Path, key: &AccountsKey) -> Self {
+		Self {
+			path: root.join("accounts.json").into(),
+			lock_path: root.join("accounts.lock").into(),
+			cipher: ChaCha20Poly1305::new(Key::from_slice(key)),
+		}
+	}
+
+	pub async fn list(&self) -> Result<Vec<Account>> {
+		let _lock = self.lock().await?;
+		self.read().await
+	}
+
+	pub async fn get(&self, uuid: &str) -> Result<Option<Account>> {
+		Ok(self.list().await?.into_iter().find(|it| &*it.uuid == uuid))
+	}
+
+	/// Adds the account, replacing the one with the same uuid
+	pub async fn insert(&self, account: Account) -> Result<()> {
+		let _lock = self.lock().await?;
+		let mut accounts = self.read().await?;
+		match accounts.iter_mut().find(|it| it.uuid == account.uuid) {
+			Some(existing) => *existing = account,
+			None => accounts.push(account),
+		}
+		self.write(&accounts).await
+	}
+
+	/// Removes the account, returns whether it was there
+	pub async fn remove(&self, uuid: &str) -> Result<bool> {
+		let _lock = self.lock().await?;
+		let mut accounts = self.read().await?;
+		let len = accounts.len();
+		accounts.retain(|it| &*it.uuid != uuid);
+		if accounts.len() == len {
+			return Ok(false);
+		}
+		self.write(&accounts).await?;
+		Ok(true)
+	}
+
+	/// Player of the account ready to launch with, refreshing its credentials
+	/// first if needed. The store stays locked during the refresh, so other
+	/// processes do not refresh the same account at once
+	pub async fn player(&self, uuid: &str, auth: &Auth) -> Result<Player> {
+		let _lock = self.lock().await?;
+		let mut accounts = self.read().await?;
+		let account =
+			accounts.iter_mut().find(|it| &*it.uuid == uuid).ok_or_else(|| Error::AccountNotFound(uuid.into()))?;
+
+		let player = if account.refresh(auth).await? {
+			let player = account.player();
+			self.write(&accounts).await?;
+			player
+		} else {
+			account.player()
+		};
+
+		Ok(player)
+	}
+
+	async fn lock(&self) -> Result<std::fs::File> {
+		sync::lock(self.lock_path.clone()).await
+	}
+
+	async fn read(&self) -> Result<Vec<Account>> {
+		if !try_exists(&self.path).await? {
+			return Ok(Vec::new());
+		}
+
+		let stored: StoredAccounts = serde_json::from_slice(&fs::read(&self.path).await?)?;
+		stored.accounts.into_iter().map(|it| self.decrypt(it)).collect()
+	}
+
+	async fn write(&self, accounts: &[Account]) -> Result<()> {
+		let stored = StoredAccounts { accounts: accounts.iter().map(|it| self.encrypt(it)).collect::<Result<_>>()? };
+		sync::write_atomic(&self.path, &serde_json::to_vec_pretty(&stored)?).await
+	}
+
+	fn encrypt(&self, account: &Account) -> Result<Stored> {
+		let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+		let msg = serde_json::to_vec(&account.credentials)?;
+		let encrypted =
+			self.cipher.encrypt(&nonce, Payload { msg: &msg, aad: account.uuid.as_bytes() }).map_err(|_| Error::Crypto)?;
+
+		let mut secret = nonce.to_vec();
+		secret.extend(encrypted);
+		Ok(Stored { uuid: account.uuid.clone(), name: account.name.clone(), secret: STANDARD.encode(secret).into() })
+	}
+
+	fn decrypt(&self, stored: Stored) -> Result<Account> {
+		let secret = STANDARD.decode(&*stored.secret).map_err(|_| Error::Crypto)?;
+		if secret.len() < NONCE_LEN {
+			return Err(Error::Crypto);
+		}
+		let (nonce, msg) = secret.split_at(NONCE_LEN);
+		let credentials = self
+			.cipher
+			.decrypt(Nonce::from_slice(nonce), Payload { msg, aad: stored.uuid.as_bytes() })
+			.map_err(|_| Error::Crypto)?;
+
+		Ok(Account { uuid: stored.uuid, name: stored.name, credentials: serde_json::from_slice(&credentials)? })
+	}
+}
+
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|it| it.as_secs()).unwrap_or_default()
+}
@@ -0,0 +1,47 @@
+use std::{path::Path, sync::Arc};
+
+use tokio::fs::try_exists;
+
+use crate::Result;
+
+#[derive(Debug)]
+pub struct Assets {
+	pub path: Arc<Path>,
+}
+
+impl Assets {
+	pub(crate) fn from_folder_root(root: &Path) -> Self {
+		let path = root.join("assets").into();
+		Self { path }
+	}
+
+	pub fn indexes_dir(&self) -> Arc<Path> {
+		self.path.join("indexes").into()
+	}
+
+	pub fn objects_dir(&self) -> Arc<Path> {
+		self.path.join("objects").into()
+	}
+
+	/// Asset index with the `id`, e.g. `assets/indexes/16.json`
+	pub fn index_path(&self, id: &str) -> Arc<Path> {
+		self.indexes_dir().join(format!("{id}.json")).into()
+	}
+
+	/// Object stored by its sha1 `hash`, under the directory of its first two
+	/// characters
+	pub fn object_path(&self, hash: &str) -> Arc<Path> {
+		let prefix = hash.get(..2).unwrap_or(hash);
+		self.objects_dir().join(prefix).join(hash).into()
+	}
+
+	/// Logging config of the client with the `id`, e.g.
+	/// `assets/log_configs/client-1.12.xml`
+	pub fn log_config_path(&self, id: &str) -> Arc<Path> {
+		self.path.join("log_configs").join(id).into()
+	}
+
+	pub async fn index_exists(&self, id: &str) -> Result<bool> {
+		try_exists(self.index_path(id)).await.map_err(Into::into)
+	}
+}
@@ -1,5 +1,6 @@
-use std::io::Error as IoError;
+use std::{io::Error as IoError, path::PathBuf};
 
+use spuz_piston::maven::ParseCoordinateError;
 use thiserror::Error;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -11,4 +12,35 @@ pub enum Error {
 
 	#[error(transparent)]
 	Json(#[from] serde_json::Error),
+
+	#[error(transparent)]
+	Coordinate(#[from] ParseCoordinateError),
+
+	#[cfg(feature = "accounts")]
+	#[error(transparent)]
+	Auth(#[from] spuz_auth::AuthError),
+
+	#[cfg(feature = "accounts")]
+	#[error("Accounts can not be decrypted, the key is wrong or the store is damaged")]
+	Crypto,
+
+	#[cfg(feature = "accounts")]
+	#[error("No account with uuid {0}")]
+	AccountNotFound(Box<str>),
+
+	#[error("Instance {0} already exists")]
+	InstanceExists(Box<str>),
+
+	#[error("No instance named {0}")]
+	InstanceNotFound(Box<str>),
+
+	#[error("`{0}` can not be an instance name")]
+	InvalidInstanceName(Box<str>),
+
+	#[cfg(feature = "launch")]
+	#[error("Native library {} can not be extracted: {source}", .path.display())]
+	Natives { path: PathBuf, source: zip::result::ZipError },
+
+	#[error("Files required to launch are missing: {}", .0.iter().map(|it| it.display().to_string()).collect::<Vec<_>>().join(", "))]
+	MissingFiles(Box<[PathBuf]>),
 }
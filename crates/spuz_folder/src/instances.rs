@@ -1,8 +1,166 @@
-use std::{path::Path, sync::Arc};
+//! Instances are separate game directories with their own configuration.
+//! Every instance lives in `instances/<name>` with its config in
+//! `instance.json` and the game directory in `minecraft`
+
+use std::{
+	collections::BTreeMap,
+	fs::File,
+	io::ErrorKind,
+	path::{Component, Path, PathBuf},
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, try_exists};
+use tracing::{debug, warn};
+
+use crate::{sync, Error, Result};
+
+const CONFIG: &str = "instance.json";
+const LOCK: &str = "instance.lock";
+const GAME_DIR: &str = "minecraft";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoaderKind {
+	Fabric,
+	Quilt,
+	Forge,
+	NeoForge,
+}
+
+impl LoaderKind {
+	/// Id of the version manifest the installer of the loader names it
+	pub fn version_id(self, minecraft: &str, loader: &str) -> String {
+		match self {
+			Self::Fabric => format!("fabric-loader-{loader}-{minecraft}"),
+			Self::Quilt => format!("quilt-loader-{loader}-{minecraft}"),
+			Self::Forge => format!("{minecraft}-forge-{loader}"),
+			Self::NeoForge => format!("neoforge-{loader}"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Loader {
+	pub kind: LoaderKind,
+	pub version: Box<str>,
+}
+
+/// Java the instance is launched with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Java {
+	/// Component of the [runtimes](crate::JavaRuntimes), e.g.
+	/// `java-runtime-delta`
+	Runtime(Box<str>),
+	/// Java binary installed elsewhere
+	Path(PathBuf),
+}
+
+/// Heap size range in megabytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Memory {
+	pub min: u32,
+	pub max: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Window {
+	pub width: u32,
+	pub height: u32,
+	#[serde(default)]
+	pub fullscreen: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstanceConfig {
+	/// Id of the minecraft version
+	pub version: Box<str>,
+	pub loader: Option<Loader>,
+	/// Java of the version manifest is used if missing
+	pub java: Option<Java>,
+	pub memory: Option<Memory>,
+	pub jvm_args: Vec<String>,
+	pub game_args: Vec<String>,
+	pub window: Option<Window>,
+	pub env: BTreeMap<String, String>,
+	/// File name of the icon in the instance directory
+	pub icon: Option<Box<str>>,
+	/// Seconds played in total
+	pub play_time: u64,
+	/// Unix timestamp in seconds the game was last closed at
+	pub last_played: Option<u64>,
+}
+
+impl InstanceConfig {
+	pub fn new(version: impl Into<Box<str>>) -> Self {
+		Self { version: version.into(), ..Self::default() }
+	}
+
+	/// Id of the version manifest to launch, as the loader installers name it
+	pub fn version_id(&self) -> String {
+		match &self.loader {
+			None => self.version.to_string(),
+			Some(Loader { kind, version }) => kind.version_id(&self.version, version),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instance {
+	pub name: Box<str>,
+	pub dir: Arc<Path>,
+	pub config: InstanceConfig,
+}
+
+impl Instance {
+	pub fn config_path(&self) -> PathBuf {
+		self.dir.join(CONFIG)
+	}
+
+	pub fn game_dir(&self) -> PathBuf {
+		self.dir.join(GAME_DIR)
+	}
+
+	pub fn icon_path(&self) -> Option<PathBuf> {
+		self.config.icon.as_deref().map(|it| self.dir.join(it))
+	}
+
+	/// Applies the `update` to the config on the disk and saves it. Changes
+	/// made by other processes since the config was read are kept
+	pub async fn update(&mut self, update: impl FnOnce(&mut InstanceConfig)) -> Result<()> {
+		let _lock = lock(&self.dir, &self.name).await?;
+		let mut config = read_config(&self.dir).await?;
+		update(&mut config);
+		write_config(&self.dir, &config).await?;
+		self.config = config;
+		Ok(())
+	}
+
+	/// Reads the config again, e.g. after another process changed it
+	pub async fn reload(&mut self) -> Result<()> {
+		self.config = read_config(&self.dir).await?;
+		Ok(())
+	}
+
+	/// Adds a finished session of the game to the play time
+	pub async fn record_session(&mut self, seconds: u64) -> Result<()> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|it| it.as_secs()).unwrap_or_default();
+		self
+			.update(|config| {
+				config.play_time += seconds;
+				config.last_played = Some(now);
+			})
+			.await
+	}
+}
 
 #[derive(Debug)]
 pub struct Instances {
-	path: Arc<Path>,
+	pub path: Arc<Path>,
 }
 
 impl Instances {
@@ -10,4 +168,198 @@ impl Instances {
 		let path = root.join("instances").into();
 		Self { path }
 	}
+
+	/// Directory of the instance named `name`
+	pub fn dir(&self, name: &str) -> Arc<Path> {
+		self.path.join(name).into()
+	}
+
+	pub async fn create(&self, name: &str, config: InstanceConfig) -> Result<Instance> {
+		let dir = self.vacant_dir(name).await?;
+		let created = match fs::create_dir(dir.join(GAME_DIR)).await {
+			Ok(()) => write_config(&dir, &config).await,
+			Err(err) => Err(err.into()),
+		};
+		if let Err(err) = created {
+			// A directory without a config would block the name without being an instance
+			fs::remove_dir_all(&dir).await.ok();
+			return Err(err);
+		}
+
+		debug!("Instance {name} created");
+		Ok(Instance { name: name.into(), dir, config })
+	}
+
+	pub async fn get(&self, name: &str) -> Result<Option<Instance>> {
+		let dir = self.dir(validate_name(name)?);
+		if !try_exists(dir.join(CONFIG)).await? {
+			return Ok(None);
+		}
+		let config = read_config(&dir).await?;
+		Ok(Some(Instance { name: name.into(), dir, config }))
+	}
+
+	/// Instances sorted by name. Directories without a config or with a name
+	/// that is not valid are skipped, instances with a config that can not be
+	/// read are logged and skipped
+	pub async fn list(&self) -> Result<Vec<Instance>> {
+		if !try_exists(&self.path).await? {
+			return Ok(Vec::new());
+		}
+
+		let mut instances = Vec::new();
+		let mut entries = fs::read_dir(&self.path).await?;
+		while let Some(entry) = entries.next_entry().await? {
+			let Some(name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+				continue;
+			};
+			match self.get(&name).await {
+				Ok(Some(instance)) => instances.push(instance),
+				Ok(None) | Err(Error::InvalidInstanceName(_)) => {}
+				Err(err) => warn!("Instance {name} skipped: {err}"),
+			}
+		}
+
+		instances.sort_by(|a, b| a.name.cmp(&b.name));
+		Ok(instances)
+	}
+
+	/// Moves the instance under the new name. The instance stays locked, so
+	/// no other process changes it meanwhile
+	pub async fn rename(&self, from: &str, to: &str) -> Result<Instance> {
+		let (source, lock) = self.locked_dir(from).await?;
+		let target = self.vacant_dir(to).await?;
+		move_entries(&source, &target).await?;
+		fs::remove_dir_all(&source).await?;
+		drop(lock);
+
+		debug!("Instance {from} renamed to {to}");
+		let config = read_config(&target).await?;
+		Ok(Instance { name: to.into(), dir: target, config })
+	}
+
+	/// Copies the instance with its game directory
+	pub async fn duplicate(&self, from: &str, to: &str) -> Result<Instance> {
+		let (source, _lock) = self.locked_dir(from).await?;
+		let target = self.vacant_dir(to).await?;
+		copy_dir(&source, &target).await?;
+
+		debug!("Instance {from} duplicated into {to}");
+		let config = read_config(&target).await?;
+		Ok(Instance { name: to.into(), dir: target, config })
+	}
+
+	/// Deletes the instance with everything in its game directory
+	pub async fn delete(&self, name: &str) -> Result<()> {
+		let (dir, lock) = self.locked_dir(name).await?;
+		fs::remove_dir_all(dir).await?;
+		drop(lock);
+
+		debug!("Instance {name} deleted");
+		Ok(())
+	}
+
+	/// Directory of the existing instance, locked until the file is dropped
+	async fn locked_dir(&self, name: &str) -> Result<(Arc<Path>, File)> {
+		let dir = self.dir(validate_name(name)?);
+		let lock = lock(&dir, name).await?;
+		// Another process may have moved the instance while it was waited for
+		if !try_exists(dir.join(CONFIG)).await? {
+			return Err(Error::InstanceNotFound(name.into()));
+		}
+		Ok((dir, lock))
+	}
+
+	/// Creates the directory of the new instance, failing if it is taken
+	async fn vacant_dir(&self, name: &str) -> Result<Arc<Path>> {
+		let dir = self.dir(validate_name(name)?);
+		fs::create_dir_all(&self.path).await?;
+		match fs::create_dir(&dir).await {
+			Ok(()) => Ok(dir),
+			Err(err) if err.kind() == ErrorKind::AlreadyExists => Err(Error::InstanceExists(name.into())),
+			Err(err) => Err(err.into()),
+		}
+	}
+}
+
+/// Waits for the lock of the instance in `dir`. The directory is never
+/// created by the lock, so a moved or deleted instance is reported as missing
+async fn lock(dir: &Path, name: &str) -> Result<File> {
+	match sync::lock(dir.join(LOCK).into()).await {
+		Err(Error::Io(err)) if err.kind() == ErrorKind::NotFound => Err(Error::InstanceNotFound(name.into())),
+		result => result,
+	}
+}
+
+/// Names are directory names, so they must be a single path component valid
+/// on every platform, which excludes the device names of windows
+fn validate_name(name: &str) -> Result<&str> {
+	const RESERVED: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+	let mut components = Path::new(name).components();
+	let single = matches!((components.next(), components.next()), (Some(Component::Normal(_)), None));
+	let valid = single
+		&& !name.starts_with('.')
+		&& !name.ends_with([' ', '.'])
+		&& !name.contains(RESERVED)
+		&& !name.contains(char::is_control)
+		&& !is_device(name.split('.').next().unwrap_or(name).trim_end());
+
+	if valid {
+		Ok(name)
+	} else {
+		Err(Error::InvalidInstanceName(name.into()))
+	}
+}
+
+/// Windows opens the device instead of the directory for these names, with
+/// any extension and in any case
+fn is_device(stem: &str) -> bool {
+	const DEVICES: &[&str] = &["CON", "PRN", "AUX", "NUL"];
+
+	let numbered = |prefix: &str| {
+		stem.get(..3).is_some_and(|it| it.eq_ignore_ascii_case(prefix))
+			&& matches!(stem.as_bytes().get(3..), Some([b'1'..=b'9']))
+	};
+	DEVICES.iter().any(|it| stem.eq_ignore_ascii_case(it)) || numbered("COM") || numbered("LPT")
+}
+
+async fn read_config(dir: &Path) -> Result<InstanceConfig> {
+	Ok(serde_json::from_slice(&fs::read(dir.join(CONFIG)).await?)?)
+}
+
+async fn write_config(dir: &Path, config: &InstanceConfig) -> Result<()> {
+	sync::write_atomic(&dir.join(CONFIG), &serde_json::to_vec_pretty(config)?).await
+}
+
+/// Moves everything but the lock into the `target` directory
+async fn move_entries(source: &Path, target: &Path) -> Result<()> {
+	let mut entries = fs::read_dir(source).await?;
+	while let Some(entry) = entries.next_entry().await? {
+		if entry.file_name() != LOCK {
+			fs::rename(entry.path(), target.join(entry.file_name())).await?;
+		}
+	}
+	Ok(())
+}
+
+async fn copy_dir(source: &Path, target: &Path) -> Result<()> {
+	let mut pending = vec![(source.to_path_buf(), target.to_path_buf())];
+	while let Some((source, target)) = pending.pop() {
+		fs::create_dir_all(&target).await?;
+
+		let mut entries = fs::read_dir(&source).await?;
+		while let Some(entry) = entries.next_entry().await? {
+			if entry.file_name() == LOCK {
+				continue;
+			}
+			let target = target.join(entry.file_name());
+			if entry.file_type().await?.is_dir() {
+				pending.push((entry.path(), target));
+			} else {
+				fs::copy(entry.path(), target).await?;
+			}
+		}
+	}
+	Ok(())
 }
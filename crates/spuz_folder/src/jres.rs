@@ -21,7 +21,7 @@ impl JavaRuntimes {
 	}
 }
 
-type LazyPath = std::cell::OnceCell<Arc<Path>>;
+type LazyPath = std::sync::OnceLock<Arc<Path>>;
 
 #[derive(Debug)]
 pub struct JavaRuntime {
@@ -0,0 +1,198 @@
+//! Launch command of an [`Instance`], configured from the folder and the
+//! config of the instance
+
+use std::{
+	collections::HashSet,
+	fs::File,
+	io,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+use spuz_piston::{
+	platform::{NativeClassifier, TARGET_OS},
+	rule::{Feature, RuleCompilance},
+	shared::{Arr, Str},
+	InheritedManifest, Manifest,
+};
+use spuz_spawner::{
+	useful::{AllocRange, AppArg, JavaArg},
+	CommandBuilder,
+};
+use spuz_wrench::{Fullscreen, LauncherInfo, LauncherWrench, Player, WindowSize};
+use tokio::{
+	fs::{self, try_exists},
+	task::spawn_blocking,
+};
+use tracing::debug;
+use zip::ZipArchive;
+
+use crate::{Error, Folder, Instance, Java, Result};
+
+/// Java looked up on the `PATH` when neither the instance nor the manifest
+/// name one
+const SYSTEM_JAVA: &str = "java";
+
+/// Manifest to launch with, inheritance resolved
+struct Resolved {
+	manifest: Manifest,
+	client_jar: PathBuf,
+}
+
+impl Folder {
+	/// Command that launches the `instance` as the `player`. Fails with
+	/// [`Error::MissingFiles`] listing everything that has to be installed
+	/// first. Native classifiers of the libraries are extracted into the
+	/// natives directory of the launched version, not of the one it inherits
+	/// from, as a loader may replace the libraries carrying them
+	pub async fn command(&self, instance: &Instance, player: Player, launcher: LauncherInfo) -> Result<CommandBuilder> {
+		let config = &instance.config;
+		let Resolved { manifest, client_jar } = self.resolve(&config.version_id()).await?;
+
+		let java = match &config.java {
+			Some(Java::Path(path)) => path.clone(),
+			Some(Java::Runtime(component)) => self.java_runtimes.component(&**component).bin().to_path_buf(),
+			None => match &manifest.java_version {
+				Some(java) => self.java_runtimes.component(&*java.component).bin().to_path_buf(),
+				None => PathBuf::from(SYSTEM_JAVA),
+			},
+		};
+
+		let features = match config.window {
+			Some(window) if !window.fullscreen => HashSet::from([Feature::CustomResolution]),
+			_ => HashSet::new(),
+		};
+		let game_dir = instance.game_dir();
+		let natives_dir = self.versions.get(&config.version_id()).natives_path().clone();
+		let natives = natives(&manifest, &RuleCompilance::new(features.clone()), &self.libraries.path);
+		let wrench = LauncherWrench::builder()
+			.manifest(&manifest)
+			.current_dir(&game_dir)
+			.libraries_dir(&*self.libraries.path)
+			.assets_dir(&*self.assets.path)
+			.natives_dir(&*natives_dir)
+			.game_dir(&game_dir)
+			.client_jar(&*client_jar)
+			.features(features)
+			.build();
+
+		let mut required = wrench.libraries();
+		required.push(client_jar.clone());
+		required.push(self.assets.index_path(&manifest.assets).to_path_buf());
+		if java != Path::new(SYSTEM_JAVA) {
+			required.push(java.clone());
+		}
+		let mut missing = Vec::new();
+		for path in required {
+			if !try_exists(&path).await? {
+				missing.push(path);
+			}
+		}
+		if !missing.is_empty() {
+			return Err(Error::MissingFiles(missing.into()));
+		}
+
+		fs::create_dir_all(&game_dir).await?;
+		if !natives.is_empty() {
+			let dir = natives_dir.to_path_buf();
+			spawn_blocking(move || extract_natives(&natives, &dir)).await.map_err(io::Error::from)??;
+		}
+
+		let mut builder = CommandBuilder::new(java);
+		if let Some(memory) = config.memory {
+			builder.apply(AllocRange(memory.min..memory.max));
+		}
+		for arg in &config.jvm_args {
+			builder.apply(JavaArg(arg));
+		}
+		builder.apply(wrench);
+		builder.apply(launcher);
+		builder.apply(player);
+		match config.window {
+			Some(window) if window.fullscreen => builder.apply(Fullscreen),
+			Some(window) => builder.apply(WindowSize::new(window.width, window.height)),
+			None => {}
+		}
+		for arg in &config.game_args {
+			builder.apply(AppArg(arg));
+		}
+		for (key, value) in &config.env {
+			builder.env(key, value);
+		}
+
+		debug!("Launch command of {} is ready", instance.name);
+		Ok(builder)
+	}
+
+	/// Reads the manifest of the version, merging it into the one it inherits
+	/// from. Loaders are launched with the client jar of their parent
+	async fn resolve(&self, id: &str) -> Result<Resolved> {
+		let version = self.versions.get(id);
+		let content = read_manifest(version.manifest_path()).await?;
+
+		let value: serde_json::Value = serde_json::from_str(&content)?;
+		if value.get("inheritsFrom").is_none() {
+			let manifest = Manifest::from_str(&content)?;
+			return Ok(Resolved { manifest, client_jar: version.client_path().to_path_buf() });
+		}
+
+		let child = InheritedManifest::from_str(&content)?;
+		let parent = self.versions.get(&child.inherits_from);
+		let manifest = Manifest::from_str(&read_manifest(parent.manifest_path()).await?)?;
+
+		Ok(Resolved { manifest: child.inherit(manifest), client_jar: parent.client_path().to_path_buf() })
+	}
+}
+
+/// Native classifiers of the libraries that apply, with the prefixes of the
+/// entries left out of them
+fn natives(manifest: &Manifest, rules: &RuleCompilance, libraries_dir: &Path) -> Vec<(PathBuf, Arr<Str>)> {
+	let classifier = NativeClassifier::from(TARGET_OS);
+	manifest
+		.libraries
+		.iter()
+		.filter(|library| library.rules.iter().flatten().all(|rule| rules.is_met(rule)))
+		.filter_map(|library| {
+			let native = library.downloads.classifiers.as_ref()?.get(&classifier)?;
+			let exclude = library.extract.as_ref().map(|it| it.exclude.clone()).unwrap_or_default();
+			Some((libraries_dir.join(&native.path), exclude))
+		})
+		.collect()
+}
+
+/// Unpacks the native jars into `dir`. Files already there with the same size
+/// are kept, another running instance of the version may have them loaded
+fn extract_natives(natives: &[(PathBuf, Arr<Str>)], dir: &Path) -> Result<()> {
+	for (path, exclude) in natives {
+		let zip_err = |source| Error::Natives { path: path.clone(), source };
+		let mut archive = ZipArchive::new(File::open(path)?).map_err(zip_err)?;
+		for index in 0..archive.len() {
+			let mut entry = archive.by_index(index).map_err(zip_err)?;
+			let Some(name) = entry.enclosed_name() else {
+				continue;
+			};
+			let excluded = exclude.iter().any(|prefix| entry.name().starts_with(&**prefix));
+			if entry.is_dir() || excluded {
+				continue;
+			}
+
+			let target = dir.join(name);
+			if std::fs::metadata(&target).is_ok_and(|it| it.len() == entry.size()) {
+				continue;
+			}
+			if let Some(parent) = target.parent() {
+				std::fs::create_dir_all(parent)?;
+			}
+			io::copy(&mut entry, &mut File::create(&target)?)?;
+		}
+		debug!("Natives of {path:?} extracted");
+	}
+	Ok(())
+}
+
+async fn read_manifest(path: &Path) -> Result<String> {
+	if !try_exists(path).await? {
+		return Err(Error::MissingFiles([path.to_path_buf()].into()));
+	}
+	Ok(fs::read_to_string(path).await?)
+}
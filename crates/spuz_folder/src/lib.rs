@@ -1,31 +1,68 @@
+#[cfg(feature = "accounts")]
+mod accounts;
+mod assets;
 mod err;
+mod instances;
 mod jres;
+#[cfg(feature = "launch")]
+mod launch;
+mod libraries;
+mod sync;
+#[cfg(test)]
+mod test;
+mod verify;
+mod versions;
 
 use std::{path::Path, sync::Arc};
 
 use tokio::fs::{canonicalize, create_dir_all};
 use tracing::info;
 
+#[cfg(feature = "accounts")]
+pub use crate::accounts::{Account, Accounts, AccountsKey, Credentials};
 pub use crate::{
+	assets::Assets,
 	err::{Error, Result},
-	jres::JavaRuntimes,
+	instances::{Instance, InstanceConfig, Instances, Java, Loader, LoaderKind, Memory, Window},
+	jres::{JavaRuntime, JavaRuntimes},
+	libraries::{Lib, Libraries},
+	verify::{FileKind, Report, RequiredFile},
+	versions::{Version, Versions},
 };
 
 #[derive(Debug)]
 pub struct Folder {
 	pub root: Arc<Path>,
 	pub java_runtimes: JavaRuntimes,
+	pub libraries: Libraries,
+	pub versions: Versions,
+	pub assets: Assets,
+	pub instances: Instances,
 }
 
 impl Folder {
 	pub async fn settle(root: impl AsRef<Path>) -> Result<Arc<Self>> {
-		let root = Arc::from(canonicalize(root.as_ref()).await?);
-		create_dir_all(&root).await?;
+		create_dir_all(root.as_ref()).await?;
+		let root: Arc<Path> = Arc::from(canonicalize(root.as_ref()).await?);
 
 		info!("Spuz folder settled into {root:?}");
 
-		let java_runtimes = JavaRuntimes::from_folder_root(&root);
+		Ok(
+			Self {
+				java_runtimes: JavaRuntimes::from_folder_root(&root),
+				libraries: Libraries::from_folder_root(&root),
+				versions: Versions::from_folder_root(&root),
+				assets: Assets::from_folder_root(&root),
+				instances: Instances::from_folder_root(&root),
+				root,
+			}
+			.into(),
+		)
+	}
 
-		Ok(Self { root, java_runtimes }.into())
+	/// Accounts of the folder, encrypted with the `key`
+	#[cfg(feature = "accounts")]
+	pub fn accounts(&self, key: &AccountsKey) -> Accounts {
+		Accounts::from_folder_root(&self.root, key)
 	}
 }
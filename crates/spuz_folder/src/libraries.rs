@@ -1,8 +1,6 @@
-use std::{
-	path::{Path, PathBuf},
-	sync::Arc,
-};
+use std::{path::Path, sync::Arc};
 
+use spuz_piston::maven::Coordinate;
 use tokio::fs::try_exists;
 
 use crate::Result;
@@ -18,44 +16,28 @@ impl Libraries {
 		Self { path }
 	}
 
-	pub fn get(&self, name: &str) -> Lib {
-		Lib::new(self.path.clone(), name)
+	/// Library by its maven coordinate, e.g. `org.ow2.asm:asm:9.6`
+	pub fn get(&self, name: &str) -> Result<Lib> {
+		Ok(Lib::new(self.path.clone(), name.parse()?))
 	}
 }
 
-type LazyPath = std::cell::OnceCell<Arc<Path>>;
+type LazyPath = std::sync::OnceLock<Arc<Path>>;
 
 #[derive(Debug)]
 pub struct Lib {
-	pub name: String,
+	pub coordinate: Coordinate,
 	libraries_dir: Arc<Path>,
 	path: LazyPath,
 }
 
 impl Lib {
-	pub(crate) fn new(libraries_dir: Arc<Path>, name: &str) -> Self {
-		Self { name: name.into(), libraries_dir, path: LazyPath::new() }
+	pub(crate) fn new(libraries_dir: Arc<Path>, coordinate: Coordinate) -> Self {
+		Self { coordinate, libraries_dir, path: LazyPath::new() }
 	}
 
 	pub fn path(&self) -> &Arc<Path> {
-		self.path.get_or_init(|| {
-			let parts: Vec<&str> = self.name.split(':').collect();
-			let mut path_buf = PathBuf::new();
-
-			let groups = parts[0].split('.');
-			let name = parts[1];
-			let version = parts[2];
-
-			for group in groups {
-				path_buf.push(group);
-			}
-
-			path_buf.push(name);
-			path_buf.push(version);
-			path_buf.push(format!("{name}-{version}.jar"));
-
-			self.libraries_dir.join(path_buf).into()
-		})
+		self.path.get_or_init(|| self.libraries_dir.join(self.coordinate.path()).into())
 	}
 
 	pub async fn exists(&self) -> Result<bool> {
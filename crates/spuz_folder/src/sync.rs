@@ -0,0 +1,35 @@
+//! Access to files shared by several processes of the launcher
+
+use std::{
+	fs::{File, OpenOptions},
+	path::Path,
+	sync::Arc,
+};
+
+use tokio::{fs, task::spawn_blocking};
+
+use crate::Result;
+
+/// Waits for the exclusive lock of the file at `path`, creating it if
+/// needed but not its directory. The lock is released once the returned file
+/// is dropped
+pub(crate) async fn lock(path: Arc<Path>) -> Result<File> {
+	let file = spawn_blocking(move || {
+		let file = OpenOptions::new().create(true).truncate(false).write(true).open(&path)?;
+		file.lock()?;
+		std::io::Result::Ok(file)
+	})
+	.await
+	.map_err(std::io::Error::other)??;
+
+	Ok(file)
+}
+
+/// Writes next to the file and renames over it, so it is never half written
+pub(crate) async fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+	let mut temp = path.as_os_str().to_owned();
+	temp.push(".tmp");
+	fs::write(&temp, content).await?;
+	fs::rename(&temp, path).await?;
+	Ok(())
+}
@@ -0,0 +1,129 @@
+use mockito::Server;
+use serde_json::json;
+use spuz_auth::{Auth, Endpoints, Profile, Session};
+use spuz_wrench::UserType;
+
+use super::folder;
+use crate::{Account, Credentials, Error};
+
+const KEY: [u8; 32] = [7; 32];
+
+fn session(expires_at: u64) -> Session {
+	Session {
+		profile: Profile {
+			id: "069a79f444e94726a5befca90e38aaf5".into(),
+			name: "Notch".into(),
+			skins: [].into(),
+			capes: [].into(),
+		},
+		access_token: "secret-access".into(),
+		expires_at,
+		xuid: Some("2535".into()),
+		refresh_token: Some("secret-refresh".into()),
+		client_id: "client".into(),
+	}
+}
+
+#[tokio::test]
+async fn encrypted_at_rest() {
+	let (_dir, folder) = folder().await;
+	let accounts = folder.accounts(&KEY);
+
+	accounts.insert(Account::microsoft(session(u64::MAX))).await.unwrap();
+	accounts.insert(Account::offline("Steve").unwrap()).await.unwrap();
+
+	let content = std::fs::read_to_string(&accounts.path).unwrap();
+	assert!(!content.contains("secret-access"));
+	assert!(!content.contains("secret-refresh"));
+
+	let listed = accounts.list().await.unwrap();
+	assert_eq!(listed.len(), 2);
+	assert_eq!(listed[0].credentials, Credentials::Microsoft(session(u64::MAX)));
+	assert_eq!(listed[1].player().user_type, UserType::Legacy);
+
+	let err = folder.accounts(&[0; 32]).list().await.unwrap_err();
+	assert!(matches!(err, Error::Crypto));
+}
+
+#[tokio::test]
+async fn replace_and_remove() {
+	let (_dir, folder) = folder().await;
+	let accounts = folder.accounts(&KEY);
+
+	accounts.insert(Account::microsoft(session(1))).await.unwrap();
+	accounts.insert(Account::microsoft(session(2))).await.unwrap();
+	let account = accounts.get("069a79f444e94726a5befca90e38aaf5").await.unwrap().unwrap();
+	assert_eq!(account.expires_at(), Some(2));
+
+	assert!(accounts.remove("069a79f444e94726a5befca90e38aaf5").await.unwrap());
+	assert!(!accounts.remove("069a79f444e94726a5befca90e38aaf5").await.unwrap());
+	assert!(accounts.list().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn refresh_before_launch() {
+	let mut server = Server::new_async().await;
+	server
+		.mock("POST", "/oauth2/v2.0/token")
+		.with_body(json!({ "access_token": "msa", "refresh_token": "rotated", "expires_in": 3600 }).to_string())
+		.create_async()
+		.await;
+	server
+		.mock("POST", "/user/authenticate")
+		.with_body(json!({ "Token": "xbl", "DisplayClaims": { "xui": [{ "uhs": "hash" }] } }).to_string())
+		.create_async()
+		.await;
+	server
+		.mock("POST", "/xsts/authorize")
+		.with_body(json!({ "Token": "xsts", "DisplayClaims": { "xui": [{ "uhs": "hash" }] } }).to_string())
+		.create_async()
+		.await;
+	server
+		.mock("POST", "/authentication/login_with_xbox")
+		.with_body(json!({ "access_token": "fresh", "expires_in": 86400 }).to_string())
+		.create_async()
+		.await;
+	server
+		.mock("GET", "/entitlements/mcstore")
+		.with_body(json!({ "items": [{ "name": "game_minecraft" }] }).to_string())
+		.create_async()
+		.await;
+	server
+		.mock("GET", "/minecraft/profile")
+		.with_body(json!({ "id": "069a79f444e94726a5befca90e38aaf5", "name": "Notch" }).to_string())
+		.create_async()
+		.await;
+	let auth = Auth::new("client").with_endpoints(Endpoints::at(&server.url()));
+
+	let (_dir, folder) = folder().await;
+	let accounts = folder.accounts(&KEY);
+	accounts.insert(Account::microsoft(session(0))).await.unwrap();
+
+	let player = accounts.player("069a79f444e94726a5befca90e38aaf5", &auth).await.unwrap();
+	assert_eq!(player.access_token.as_deref(), Some("fresh"));
+
+	let Credentials::Microsoft(stored) = accounts.list().await.unwrap().remove(0).credentials else {
+		panic!("Account is not a microsoft one");
+	};
+	assert_eq!(&*stored.access_token, "fresh");
+	assert_eq!(stored.refresh_token.as_deref(), Some("rotated"));
+
+	let err = accounts.player("missing", &auth).await.unwrap_err();
+	assert!(matches!(err, Error::AccountNotFound(_)));
+}
+
+#[tokio::test]
+async fn concurrent_writers() {
+	let (_dir, folder) = folder().await;
+
+	let writers = (0..8).map(|i| {
+		// Every writer has its own store, as separate processes would
+		let accounts = folder.accounts(&KEY);
+		tokio::spawn(async move { accounts.insert(Account::offline(&format!("Player{i}")).unwrap()).await })
+	});
+	for writer in writers.collect::<Vec<_>>() {
+		writer.await.unwrap().unwrap();
+	}
+
+	assert_eq!(folder.accounts(&KEY).list().await.unwrap().len(), 8);
+}
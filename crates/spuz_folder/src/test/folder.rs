@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use crate::{Error, Folder};
+
+const MANIFEST: &str = include_str!("../../../../versions/1.20.6.json");
+
+#[tokio::test]
+async fn settles_missing_root() {
+	let dir = TempDir::new().unwrap();
+	let folder = Folder::settle(dir.path().join("nested/root")).await.unwrap();
+
+	assert!(folder.root.is_dir());
+	assert!(folder.root.is_absolute());
+	assert_eq!(&*folder.libraries.path, folder.root.join("libraries"));
+	assert_eq!(&*folder.versions.path, folder.root.join("versions"));
+	assert_eq!(&*folder.assets.path, folder.root.join("assets"));
+	assert_eq!(&*folder.instances.path, folder.root.join("instances"));
+}
+
+#[tokio::test]
+async fn version_paths() {
+	let dir = TempDir::new().unwrap();
+	let folder = Folder::settle(dir.path()).await.unwrap();
+	let version = folder.versions.get("1.20.6");
+
+	let dir = folder.root.join("versions/1.20.6");
+	assert_eq!(&**version.path(), dir);
+	assert_eq!(&**version.manifest_path(), dir.join("1.20.6.json"));
+	assert_eq!(&**version.client_path(), dir.join("1.20.6.jar"));
+	assert_eq!(&**version.natives_path(), dir.join("natives"));
+
+	assert!(!version.exists().await.unwrap());
+	assert!(version.manifest().await.unwrap().is_none());
+
+	std::fs::create_dir_all(&dir).unwrap();
+	std::fs::write(version.manifest_path(), MANIFEST).unwrap();
+	assert!(version.exists().await.unwrap());
+	let manifest = version.manifest().await.unwrap().unwrap();
+	assert_eq!(&*manifest.id, "1.20.6");
+}
+
+#[tokio::test]
+async fn library_paths() {
+	let dir = TempDir::new().unwrap();
+	let folder = Folder::settle(dir.path()).await.unwrap();
+
+	let lib = folder.libraries.get("org.lwjgl:lwjgl:3.3.3:natives-linux").unwrap();
+	let path = folder.root.join(Path::new("libraries/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-linux.jar"));
+	assert_eq!(&**lib.path(), path);
+	assert!(!lib.exists().await.unwrap());
+
+	std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+	std::fs::write(&path, b"").unwrap();
+	assert!(lib.exists().await.unwrap());
+
+	assert!(matches!(folder.libraries.get("not a coordinate"), Err(Error::Coordinate(_))));
+}
+
+#[tokio::test]
+async fn asset_and_instance_paths() {
+	let dir = TempDir::new().unwrap();
+	let folder = Folder::settle(dir.path()).await.unwrap();
+
+	assert_eq!(&*folder.assets.index_path("16"), folder.root.join("assets/indexes/16.json"));
+	assert_eq!(
+		&*folder.assets.object_path("bdf48ef6b5d0d23bbb02e17d04865216179f510a"),
+		folder.root.join("assets/objects/bd/bdf48ef6b5d0d23bbb02e17d04865216179f510a")
+	);
+	assert!(!folder.assets.index_exists("16").await.unwrap());
+	assert_eq!(&*folder.instances.dir("survival"), folder.root.join("instances/survival"));
+}
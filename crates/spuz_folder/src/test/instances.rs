@@ -0,0 +1,171 @@
+use super::folder;
+use crate::{Error, InstanceConfig, Java, Loader, LoaderKind, Memory};
+
+fn config() -> InstanceConfig {
+	InstanceConfig {
+		loader: Some(Loader { kind: LoaderKind::Fabric, version: "0.15.11".into() }),
+		java: Some(Java::Runtime("java-runtime-delta".into())),
+		memory: Some(Memory { min: 1024, max: 4096 }),
+		jvm_args: vec!["-XX:+UseG1GC".into()],
+		..InstanceConfig::new("1.20.6")
+	}
+}
+
+#[tokio::test]
+async fn create_and_list() {
+	let (_dir, folder) = folder().await;
+	let instances = &folder.instances;
+
+	let created = instances.create("Survival", config()).await.unwrap();
+	assert!(created.game_dir().is_dir());
+	instances.create("Creative", InstanceConfig::new("1.21")).await.unwrap();
+	std::fs::create_dir_all(instances.path.join("not an instance")).unwrap();
+	std::fs::create_dir_all(instances.path.join(".hidden")).unwrap();
+	std::fs::create_dir_all(instances.path.join("Corrupt")).unwrap();
+	std::fs::write(instances.path.join("Corrupt/instance.json"), "{").unwrap();
+
+	let listed = instances.list().await.unwrap();
+	let names: Vec<_> = listed.iter().map(|it| &*it.name).collect();
+	assert_eq!(names, ["Creative", "Survival"]);
+	assert_eq!(listed[1], created);
+
+	let err = instances.create("Survival", config()).await.unwrap_err();
+	assert!(matches!(err, Error::InstanceExists(_)));
+	assert!(instances.get("Missing").await.unwrap().is_none());
+	assert!(matches!(instances.get("Corrupt").await, Err(Error::Json(_))));
+}
+
+#[tokio::test]
+async fn invalid_names() {
+	let (_dir, folder) = folder().await;
+
+	for name in ["", "..", "../escape", "a/b", ".hidden", "what?", "trailing.", "CON", "nul.txt", "Com1", "lpt9 .a"] {
+		let err = folder.instances.create(name, config()).await.unwrap_err();
+		assert!(matches!(err, Error::InvalidInstanceName(_)), "{name}");
+	}
+	for name in ["console", "COM0", "LPT10", "aux-world"] {
+		folder.instances.create(name, config()).await.unwrap();
+	}
+}
+
+#[test]
+fn version_ids() {
+	let mut config = InstanceConfig::new("1.20.6");
+	assert_eq!(config.version_id(), "1.20.6");
+
+	let cases = [
+		(LoaderKind::Fabric, "0.15.11", "fabric-loader-0.15.11-1.20.6"),
+		(LoaderKind::Quilt, "0.26.0", "quilt-loader-0.26.0-1.20.6"),
+		(LoaderKind::Forge, "50.1.0", "1.20.6-forge-50.1.0"),
+		(LoaderKind::NeoForge, "20.6.119", "neoforge-20.6.119"),
+	];
+	for (kind, version, id) in cases {
+		config.loader = Some(Loader { kind, version: version.into() });
+		assert_eq!(config.version_id(), id);
+	}
+}
+
+#[tokio::test]
+async fn rename_duplicate_delete() {
+	let (_dir, folder) = folder().await;
+	let instances = &folder.instances;
+	let created = instances.create("Survival", config()).await.unwrap();
+	std::fs::create_dir_all(created.game_dir().join("saves/World")).unwrap();
+	std::fs::write(created.game_dir().join("saves/World/level.dat"), b"level").unwrap();
+
+	let renamed = instances.rename("Survival", "Hardcore").await.unwrap();
+	assert_eq!(renamed.config, config());
+	assert!(instances.get("Survival").await.unwrap().is_none());
+
+	let duplicate = instances.duplicate("Hardcore", "Hardcore copy").await.unwrap();
+	assert_eq!(std::fs::read(duplicate.game_dir().join("saves/World/level.dat")).unwrap(), b"level");
+
+	instances.delete("Hardcore").await.unwrap();
+	assert!(!renamed.dir.exists());
+	assert!(matches!(instances.delete("Hardcore").await, Err(Error::InstanceNotFound(_))));
+	assert!(matches!(instances.rename("Hardcore", "Other").await, Err(Error::InstanceNotFound(_))));
+	assert!(matches!(instances.duplicate("Hardcore copy", "Hardcore copy").await, Err(Error::InstanceExists(_))));
+	assert!(matches!(instances.rename("Hardcore copy", "Hardcore copy").await, Err(Error::InstanceExists(_))));
+	assert!(instances.get("Hardcore copy").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn updates_keep_other_changes() {
+	let (_dir, folder) = folder().await;
+	let mut first = folder.instances.create("Survival", config()).await.unwrap();
+	let mut second = folder.instances.get("Survival").await.unwrap().unwrap();
+
+	first.update(|it| it.memory = Some(Memory { min: 2048, max: 8192 })).await.unwrap();
+	second
+		.update(|it| {
+			it.env.insert("MESA_GL_VERSION_OVERRIDE".into(), "4.5".into());
+		})
+		.await
+		.unwrap();
+	second.record_session(90).await.unwrap();
+
+	first.reload().await.unwrap();
+	assert_eq!(first.config.memory, Some(Memory { min: 2048, max: 8192 }));
+	assert_eq!(first.config.env.len(), 1);
+	assert_eq!(first.config.play_time, 90);
+	assert!(first.config.last_played.is_some());
+}
+
+#[tokio::test]
+async fn concurrent_updates() {
+	let (_dir, folder) = folder().await;
+	folder.instances.create("Survival", config()).await.unwrap();
+
+	let updates = (0..8).map(|_| {
+		let folder = folder.clone();
+		tokio::spawn(async move {
+			let mut instance = folder.instances.get("Survival").await.unwrap().unwrap();
+			instance.record_session(10).await.unwrap();
+		})
+	});
+	for update in updates.collect::<Vec<_>>() {
+		update.await.unwrap();
+	}
+
+	let instance = folder.instances.get("Survival").await.unwrap().unwrap();
+	assert_eq!(instance.config.play_time, 80);
+}
+
+#[tokio::test]
+async fn concurrent_creates() {
+	let (_dir, folder) = folder().await;
+
+	let creates = (0..8).map(|_| {
+		let folder = folder.clone();
+		tokio::spawn(async move { folder.instances.create("Survival", config()).await })
+	});
+	let mut succeeded = 0;
+	for create in creates.collect::<Vec<_>>() {
+		match create.await.unwrap() {
+			Ok(_) => succeeded += 1,
+			Err(err) => assert!(matches!(err, Error::InstanceExists(_))),
+		}
+	}
+	assert_eq!(succeeded, 1);
+}
+
+#[tokio::test]
+async fn delete_waits_for_updates() {
+	let (_dir, folder) = folder().await;
+	let mut instance = folder.instances.create("Survival", config()).await.unwrap();
+
+	let lock = crate::sync::lock(instance.dir.join("instance.lock").into()).await.unwrap();
+	let delete = tokio::spawn({
+		let folder = folder.clone();
+		async move { folder.instances.delete("Survival").await }
+	});
+	tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+	assert!(instance.dir.join("instance.json").exists());
+
+	drop(lock);
+	delete.await.unwrap().unwrap();
+	assert!(!instance.dir.exists());
+
+	assert!(matches!(instance.update(|it| it.play_time += 1).await, Err(Error::InstanceNotFound(_))));
+	assert!(!instance.dir.exists());
+}
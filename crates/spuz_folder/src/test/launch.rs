@@ -0,0 +1,186 @@
+use std::{io::Write, path::Path};
+
+use serde_json::{json, Value};
+use spuz_piston::platform::{NativeClassifier, TARGET_OS};
+use spuz_wrench::{LauncherInfo, Player};
+use tempfile::TempDir;
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use super::folder;
+use crate::{Error, Folder, InstanceConfig, Java, Loader, LoaderKind, Memory, Window};
+
+const MANIFEST: &str = include_str!("../../../../versions/1.20.6.json");
+const FABRIC: &str = r#"{
+	"id": "fabric-loader-0.15.11-1.20.6",
+	"inheritsFrom": "1.20.6",
+	"mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+	"libraries": [
+		{
+			"name": "net.fabricmc:fabric-loader:0.15.11",
+			"downloads": {
+				"artifact": {
+					"path": "net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar",
+					"sha1": "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+					"size": 0,
+					"url": "https://maven.fabricmc.net/net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar"
+				}
+			}
+		}
+	]
+}"#;
+
+fn write(path: &Path, content: &str) {
+	std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+	std::fs::write(path, content).unwrap();
+}
+
+fn install(folder: &Folder, id: &str, manifest: &str) {
+	write(&folder.versions.get(id).manifest_path().clone(), manifest);
+}
+
+fn config(dir: &TempDir) -> InstanceConfig {
+	InstanceConfig {
+		java: Some(Java::Path(dir.path().join("jdk/bin/java"))),
+		memory: Some(Memory { min: 1024, max: 4096 }),
+		jvm_args: vec!["-XX:+UseG1GC".into()],
+		game_args: vec!["--demo".into()],
+		window: Some(Window { width: 1280, height: 720, fullscreen: false }),
+		env: [("MESA_GL_VERSION_OVERRIDE".into(), "4.6".into())].into(),
+		..InstanceConfig::new("1.20.6")
+	}
+}
+
+fn player() -> Player {
+	Player::offline("Notch").unwrap()
+}
+
+fn launcher() -> LauncherInfo {
+	LauncherInfo::new("spuz", "0.1.0")
+}
+
+#[tokio::test]
+async fn reports_missing_files() {
+	let (dir, folder) = folder().await;
+	let instance = folder.instances.create("Survival", config(&dir)).await.unwrap();
+
+	let err = folder.command(&instance, player(), launcher()).await.unwrap_err();
+	let Error::MissingFiles(missing) = err else { panic!("{err}") };
+	assert_eq!(&*missing, [folder.versions.get("1.20.6").manifest_path().to_path_buf()]);
+
+	install(&folder, "1.20.6", MANIFEST);
+	let err = folder.command(&instance, player(), launcher()).await.unwrap_err();
+	let Error::MissingFiles(missing) = err else { panic!("{err}") };
+	assert!(missing.contains(&dir.path().join("jdk/bin/java")));
+	assert!(missing.contains(&folder.versions.get("1.20.6").client_path().to_path_buf()));
+	assert!(missing.contains(&folder.assets.index_path("16").to_path_buf()));
+	assert!(missing.iter().any(|it| it.starts_with(&*folder.libraries.path)));
+}
+
+#[tokio::test]
+async fn builds_command() {
+	let (dir, folder) = folder().await;
+	let instance = folder.instances.create("Survival", config(&dir)).await.unwrap();
+	install(&folder, "1.20.6", MANIFEST);
+
+	let Error::MissingFiles(missing) = folder.command(&instance, player(), launcher()).await.unwrap_err() else {
+		panic!()
+	};
+	for path in &*missing {
+		write(path, "");
+	}
+
+	let builder = folder.command(&instance, player(), launcher()).await.unwrap();
+	let command = builder.build().into_command();
+	let command = command.as_std();
+	let args: Vec<_> = command.get_args().map(|it| it.to_str().unwrap()).collect();
+
+	assert_eq!(command.get_program(), dir.path().join("jdk/bin/java"));
+	assert_eq!(command.get_current_dir(), Some(&*instance.game_dir()));
+	assert!(command.get_envs().any(|(key, value)| key == "MESA_GL_VERSION_OVERRIDE" && value.unwrap() == "4.6"));
+	for arg in
+		["-Xms1024m", "-Xmx4096m", "-XX:+UseG1GC", "net.minecraft.client.main.Main", "--demo", "Notch", "1280", "720"]
+	{
+		assert!(args.contains(&arg), "{arg} in {args:?}");
+	}
+	assert!(args.contains(&&*instance.game_dir().to_string_lossy()));
+	assert!(args.contains(&&*folder.assets.path.to_string_lossy()));
+}
+
+#[tokio::test]
+async fn inherits_loader_manifest() {
+	let (dir, folder) = folder().await;
+	let config =
+		InstanceConfig { loader: Some(Loader { kind: LoaderKind::Fabric, version: "0.15.11".into() }), ..config(&dir) };
+	let instance = folder.instances.create("Fabric", config).await.unwrap();
+	install(&folder, "fabric-loader-0.15.11-1.20.6", FABRIC);
+
+	let err = folder.command(&instance, player(), launcher()).await.unwrap_err();
+	let Error::MissingFiles(missing) = err else { panic!("{err}") };
+	assert_eq!(&*missing, [folder.versions.get("1.20.6").manifest_path().to_path_buf()]);
+
+	install(&folder, "1.20.6", MANIFEST);
+	let Error::MissingFiles(missing) = folder.command(&instance, player(), launcher()).await.unwrap_err() else {
+		panic!()
+	};
+	let loader = folder.libraries.path.join("net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar");
+	assert!(missing.contains(&folder.versions.get("1.20.6").client_path().to_path_buf()));
+	assert!(missing.contains(&loader));
+	for path in &*missing {
+		write(path, "");
+	}
+
+	let builder = folder.command(&instance, player(), launcher()).await.unwrap();
+	let command = builder.build().into_command();
+	let args: Vec<_> = command.as_std().get_args().map(|it| it.to_str().unwrap().to_owned()).collect();
+	assert!(args.iter().any(|it| it.contains(&*loader.to_string_lossy())), "{loader:?} in {args:?}");
+	assert!(args.iter().any(|it| it == "net.fabricmc.loader.impl.launch.knot.KnotClient"));
+	assert!(args.iter().any(|it| it == "--version") && args.iter().any(|it| it == "fabric-loader-0.15.11-1.20.6"));
+	let natives = folder.versions.get("fabric-loader-0.15.11-1.20.6").natives_path().to_string_lossy().into_owned();
+	assert!(args.iter().any(|it| it.contains(&natives)), "{natives} in {args:?}");
+}
+
+#[tokio::test]
+async fn extracts_natives() {
+	const NATIVE: &str = "org/lwjgl/lwjgl/lwjgl-platform/2.9.4/lwjgl-platform-2.9.4-natives.jar";
+
+	let (dir, folder) = folder().await;
+	let instance = folder.instances.create("Legacy", config(&dir)).await.unwrap();
+	let classifier = serde_json::to_value(NativeClassifier::from(TARGET_OS)).unwrap();
+	let mut manifest: Value = serde_json::from_str(MANIFEST).unwrap();
+	manifest["libraries"].as_array_mut().unwrap().push(json!({
+		"name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4",
+		"downloads": {
+			"classifiers": {
+				classifier.as_str().unwrap(): { "path": NATIVE, "sha1": "", "size": 0, "url": "https://libraries.minecraft.net" }
+			}
+		},
+		"extract": { "exclude": ["META-INF/"] }
+	}));
+	install(&folder, "1.20.6", &manifest.to_string());
+
+	let Error::MissingFiles(missing) = folder.command(&instance, player(), launcher()).await.unwrap_err() else {
+		panic!()
+	};
+	let native = folder.libraries.path.join(NATIVE);
+	assert!(missing.contains(&native));
+	for path in &*missing {
+		write(path, "");
+	}
+
+	// Broken jars fail the launch instead of the game
+	let err = folder.command(&instance, player(), launcher()).await.unwrap_err();
+	assert!(matches!(&err, Error::Natives { path, .. } if *path == native), "{err}");
+
+	let mut writer = ZipWriter::new(std::fs::File::create(&native).unwrap());
+	for (name, content) in [("liblwjgl.so", "lwjgl"), ("META-INF/MANIFEST.MF", "manifest"), ("../escape.so", "escape")] {
+		writer.start_file(name, SimpleFileOptions::default()).unwrap();
+		writer.write_all(content.as_bytes()).unwrap();
+	}
+	writer.finish().unwrap();
+
+	folder.command(&instance, player(), launcher()).await.unwrap();
+	let natives = folder.versions.get("1.20.6").natives_path().clone();
+	assert_eq!(std::fs::read_to_string(natives.join("liblwjgl.so")).unwrap(), "lwjgl");
+	assert!(!natives.join("META-INF").exists());
+	assert!(!natives.join("../escape.so").exists());
+}
@@ -0,0 +1,22 @@
+#![allow(clippy::unwrap_used)]
+
+use std::sync::Arc;
+
+use tempfile::TempDir;
+
+use crate::Folder;
+
+#[cfg(feature = "accounts")]
+mod accounts;
+mod folder;
+mod instances;
+#[cfg(feature = "launch")]
+mod launch;
+mod verify;
+
+/// Folder settled in a fresh temporary directory
+async fn folder() -> (TempDir, Arc<Folder>) {
+	let dir = TempDir::new().unwrap();
+	let folder = Folder::settle(dir.path()).await.unwrap();
+	(dir, folder)
+}
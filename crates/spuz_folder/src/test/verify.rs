@@ -0,0 +1,147 @@
+use std::{collections::HashSet, path::Path};
+
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use spuz_piston::{
+	assets::RESOURCES,
+	platform::{NativeClassifier, Os, TARGET_OS},
+	rule::RuleCompilance,
+	Manifest,
+};
+use tempfile::TempDir;
+
+use crate::{FileKind, Folder};
+
+const MANIFEST: &str = include_str!("../../../../versions/1.20.6.json");
+const CLIENT: &[u8] = b"client";
+const LIBRARY: &[u8] = b"library";
+const NATIVE: &[u8] = b"native";
+const FOREIGN: &[u8] = b"foreign";
+const LOG_CONFIG: &[u8] = b"<Configuration/>";
+const SOUND: &[u8] = b"sound";
+const ICON: &[u8] = b"icon";
+
+fn sha1(content: &[u8]) -> String {
+	format!("{:x}", Sha1::digest(content))
+}
+
+fn artifact(path: &str, content: &[u8]) -> serde_json::Value {
+	json!({ "path": path, "sha1": sha1(content), "size": content.len(), "url": format!("https://libraries.minecraft.net/{path}") })
+}
+
+fn index() -> String {
+	json!({
+		"objects": {
+			"minecraft/sounds/ambient.ogg": { "hash": sha1(SOUND), "size": SOUND.len() },
+			"minecraft/sounds/ambient_copy.ogg": { "hash": sha1(SOUND), "size": SOUND.len() },
+			"icons/icon_16x16.png": { "hash": sha1(ICON), "size": ICON.len() }
+		}
+	})
+	.to_string()
+}
+
+fn manifest() -> Manifest {
+	let foreign = if TARGET_OS == Os::Windows { "linux" } else { "windows" };
+	let classifier = serde_json::to_value(NativeClassifier::from(TARGET_OS)).unwrap();
+	let index = index();
+
+	let mut manifest: serde_json::Value = serde_json::from_str(MANIFEST).unwrap();
+	manifest["downloads"]["client"] =
+		json!({ "sha1": sha1(CLIENT), "size": CLIENT.len(), "url": "https://piston-data.mojang.com/client.jar" });
+	manifest["logging"]["client"]["file"] = json!({ "id": "client-1.12.xml", "sha1": sha1(LOG_CONFIG), "size": LOG_CONFIG.len(), "url": "https://piston-data.mojang.com/client-1.12.xml" });
+	manifest["assetIndex"]["sha1"] = json!(sha1(index.as_bytes()));
+	manifest["assetIndex"]["size"] = json!(index.len());
+	manifest["libraries"] = json!([
+		{
+			"name": "com.example:library:1.0",
+			"downloads": {
+				"artifact": artifact("com/example/library/1.0/library-1.0.jar", LIBRARY),
+				"classifiers": { classifier.as_str().unwrap(): artifact("com/example/library/1.0/library-1.0-natives.jar", NATIVE) }
+			}
+		},
+		{
+			"name": "com.example:foreign:1.0",
+			"downloads": { "artifact": artifact("com/example/foreign/1.0/foreign-1.0.jar", FOREIGN) },
+			"rules": [{ "action": "allow", "os": { "name": foreign } }]
+		}
+	]);
+	serde_json::from_value(manifest).unwrap()
+}
+
+fn write(path: &Path, content: &[u8]) {
+	std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+	std::fs::write(path, content).unwrap();
+}
+
+#[tokio::test]
+async fn reports_damaged_files() {
+	let dir = TempDir::new().unwrap();
+	let folder = Folder::settle(dir.path()).await.unwrap();
+	let manifest = manifest();
+	let client_jar = folder.versions.get("1.20.6").client_path().clone();
+	let target = RuleCompilance::new(HashSet::new());
+
+	let report = folder.verify(&manifest, &client_jar, &target).await.unwrap();
+	let kinds: Vec<_> = report.missing.iter().map(|it| it.kind).collect();
+	assert_eq!(kinds, [FileKind::Client, FileKind::Library, FileKind::Native, FileKind::LogConfig, FileKind::AssetIndex]);
+	assert!(report.corrupt.is_empty());
+	assert!(!report.is_complete());
+
+	let index = index();
+	for file in report.damaged() {
+		let content = match file.kind {
+			FileKind::Client => b"tneilc".as_slice(),
+			FileKind::AssetIndex => index.as_bytes(),
+			FileKind::Library => LIBRARY,
+			FileKind::Native => NATIVE,
+			_ => LOG_CONFIG,
+		};
+		write(&file.path, content);
+	}
+	write(&folder.assets.object_path(&sha1(ICON)), b"ico");
+
+	let report = folder.verify(&manifest, &client_jar, &target).await.unwrap();
+	assert_eq!(report.missing.len(), 1);
+	let sound = &report.missing[0];
+	assert_eq!(sound.kind, FileKind::Asset);
+	assert_eq!(sound.path, &*folder.assets.object_path(&sha1(SOUND)));
+	assert_eq!(&*sound.url, format!("{RESOURCES}/{}/{}", &sound.sha1[..2], sound.sha1));
+	let corrupt: Vec<_> = report.corrupt.iter().map(|it| (it.kind, it.path.clone())).collect();
+	assert_eq!(
+		corrupt,
+		[
+			(FileKind::Client, client_jar.to_path_buf()),
+			(FileKind::Asset, folder.assets.object_path(&sha1(ICON)).to_path_buf())
+		]
+	);
+
+	write(&client_jar, CLIENT);
+	write(&folder.assets.object_path(&sha1(SOUND)), SOUND);
+	write(&folder.assets.object_path(&sha1(ICON)), ICON);
+	assert!(folder.verify(&manifest, &client_jar, &target).await.unwrap().is_complete());
+}
+
+#[tokio::test]
+async fn checks_every_object() {
+	let dir = TempDir::new().unwrap();
+	let folder = Folder::settle(dir.path()).await.unwrap();
+	let mut manifest = manifest();
+	let client_jar = folder.versions.get("1.20.6").client_path().clone();
+
+	let objects: Vec<_> = (0..40).map(|i| format!("object {i}")).collect();
+	let index = json!({
+		"objects": objects.iter().map(|it| (it.clone(), json!({ "hash": sha1(it.as_bytes()), "size": it.len() }))).collect::<serde_json::Map<_, _>>()
+	})
+	.to_string();
+	manifest.asset_index.sha1 = sha1(index.as_bytes()).into();
+	manifest.asset_index.size = index.len() as u64;
+	write(&folder.assets.index_path(&manifest.asset_index.id), index.as_bytes());
+	for object in &objects[..10] {
+		write(&folder.assets.object_path(&sha1(object.as_bytes())), object.as_bytes());
+	}
+
+	let report = folder.verify(&manifest, &client_jar, &RuleCompilance::new(HashSet::new())).await.unwrap();
+	let assets: HashSet<_> = report.missing.iter().filter(|it| it.kind == FileKind::Asset).map(|it| &*it.sha1).collect();
+	let expected: Vec<_> = objects[10..].iter().map(|it| sha1(it.as_bytes())).collect();
+	assert_eq!(assets, expected.iter().map(|it| &**it).collect());
+}
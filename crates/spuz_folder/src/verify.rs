@@ -0,0 +1,220 @@
+//! Integrity of an installed version. Every file the launch depends on is
+//! checked against the size and sha1 from its manifest
+
+use std::{
+	collections::HashSet,
+	io::{self, Read},
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+use futures_util::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use spuz_piston::{
+	assets::RESOURCES,
+	manifest::Artifact,
+	platform::{NativeClassifier, TARGET_OS},
+	rule::RuleCompilance,
+	shared::{Size, Str},
+	AssetIndex, Manifest,
+};
+use tokio::{fs, task::spawn_blocking};
+use tracing::debug;
+
+use crate::{Folder, Result};
+
+/// Asset objects hashed at once
+const PARALLEL_CHECKS: usize = 16;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+	Client,
+	Library,
+	Native,
+	AssetIndex,
+	Asset,
+	LogConfig,
+}
+
+/// File the launch depends on, with everything needed to download it again
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequiredFile {
+	pub kind: FileKind,
+	pub path: PathBuf,
+	pub url: Str,
+	pub sha1: Str,
+	pub size: Size,
+}
+
+/// Files of the version that are not in place
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Report {
+	/// Files that do not exist
+	pub missing: Vec<RequiredFile>,
+	/// Files with a size or sha1 other than expected
+	pub corrupt: Vec<RequiredFile>,
+}
+
+impl Report {
+	pub fn is_complete(&self) -> bool {
+		self.missing.is_empty() && self.corrupt.is_empty()
+	}
+
+	/// Files to download to repair the install, missing ones first
+	pub fn damaged(&self) -> impl Iterator<Item = &RequiredFile> {
+		self.missing.iter().chain(&self.corrupt)
+	}
+
+	async fn check(&mut self, file: RequiredFile) -> Result<bool> {
+		let state = file_state(&file).await?;
+		Ok(self.record(file, state))
+	}
+
+	/// Adds the `file` to the report by its `state`, returns whether it is valid
+	fn record(&mut self, file: RequiredFile, state: State) -> bool {
+		match state {
+			State::Valid => return true,
+			State::Missing => self.missing.push(file),
+			State::Corrupt => {
+				debug!("{:?} is corrupt", file.path);
+				self.corrupt.push(file);
+			}
+		}
+		false
+	}
+}
+
+async fn file_state(file: &RequiredFile) -> Result<State> {
+	let path = file.path.clone();
+	let size = file.size;
+	let sha1 = file.sha1.clone();
+	Ok(spawn_blocking(move || state(&path, size, &sha1)).await.map_err(io::Error::from)??)
+}
+
+#[derive(Clone, Copy)]
+enum State {
+	Valid,
+	Missing,
+	Corrupt,
+}
+
+/// Compares the size first, so only files of the right size are hashed
+fn state(path: &Path, size: Size, sha1: &str) -> io::Result<State> {
+	let mut file = match std::fs::File::open(path) {
+		Ok(file) => file,
+		Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(State::Missing),
+		Err(err) => return Err(err),
+	};
+	if file.metadata()?.len() != size {
+		return Ok(State::Corrupt);
+	}
+
+	let mut hasher = Sha1::new();
+	let mut buf = vec![0u8; 0x4000];
+	loop {
+		let read = file.read(&mut buf)?;
+		if read == 0 {
+			break;
+		}
+		hasher.update(&buf[..read]);
+	}
+
+	if format!("{:x}", hasher.finalize()).eq_ignore_ascii_case(sha1) {
+		Ok(State::Valid)
+	} else {
+		Ok(State::Corrupt)
+	}
+}
+
+impl Folder {
+	/// Checks the client jar, libraries and natives selected by the rules of
+	/// the `target`, the asset index with all its objects and the logging
+	/// config. Objects are only checked once the index itself is intact
+	pub async fn verify(&self, manifest: &Manifest, client_jar: &Path, target: &RuleCompilance) -> Result<Report> {
+		let mut report = Report::default();
+		let client = &manifest.downloads.client;
+
+		let mut files = vec![RequiredFile {
+			kind: FileKind::Client,
+			path: client_jar.to_path_buf(),
+			url: client.url.clone(),
+			sha1: client.sha1.clone(),
+			size: client.size,
+		}];
+
+		let classifier = NativeClassifier::from(TARGET_OS);
+		for library in &manifest.libraries {
+			if !library.rules.iter().flatten().all(|rule| target.is_met(rule)) {
+				continue;
+			}
+			let downloads = &library.downloads;
+			if let Some(artifact) = &downloads.artifact {
+				files.push(self.library(FileKind::Library, artifact));
+			}
+			if let Some(native) = downloads.classifiers.as_ref().and_then(|it| it.get(&classifier)) {
+				files.push(self.library(FileKind::Native, native));
+			}
+		}
+
+		let logging = &manifest.logging.file;
+		files.push(RequiredFile {
+			kind: FileKind::LogConfig,
+			path: self.assets.log_config_path(&logging.id).to_path_buf(),
+			url: logging.url.clone(),
+			sha1: logging.sha1.clone(),
+			size: logging.size,
+		});
+
+		for file in files {
+			report.check(file).await?;
+		}
+
+		let index = &manifest.asset_index;
+		let index_path = self.assets.index_path(&index.id);
+		let index_file = RequiredFile {
+			kind: FileKind::AssetIndex,
+			path: index_path.to_path_buf(),
+			url: index.url.clone(),
+			sha1: index.sha1.clone(),
+			size: index.size,
+		};
+		if report.check(index_file).await? {
+			let index = AssetIndex::from_str(&fs::read_to_string(&index_path).await?)?;
+			let mut seen = HashSet::new();
+			let objects = index.objects.values().filter(|it| seen.insert(&it.hash)).map(|object| {
+				let hash = &object.hash;
+				let prefix = hash.get(..2).unwrap_or(hash);
+				RequiredFile {
+					kind: FileKind::Asset,
+					path: self.assets.object_path(hash).to_path_buf(),
+					url: format!("{RESOURCES}/{prefix}/{hash}").into(),
+					sha1: hash.clone(),
+					size: object.size,
+				}
+			});
+
+			let mut checks = stream::iter(objects)
+				.map(|file| async move { file_state(&file).await.map(|state| (file, state)) })
+				.buffer_unordered(PARALLEL_CHECKS);
+			while let Some((file, state)) = checks.try_next().await? {
+				report.record(file, state);
+			}
+		}
+
+		debug!("{} is missing {} files, {} are corrupt", manifest.id, report.missing.len(), report.corrupt.len());
+
+		Ok(report)
+	}
+
+	fn library(&self, kind: FileKind, artifact: &Artifact) -> RequiredFile {
+		RequiredFile {
+			kind,
+			path: self.libraries.path.join(&artifact.path),
+			url: artifact.url.clone(),
+			sha1: artifact.sha1.clone(),
+			size: artifact.size,
+		}
+	}
+}
@@ -10,7 +10,7 @@ use crate::Result;
 
 #[derive(Debug)]
 pub struct Versions {
-	path: Arc<Path>,
+	pub path: Arc<Path>,
 }
 
 impl Versions {
@@ -25,7 +25,7 @@ impl Versions {
 }
 
 type LazyManifest = tokio::sync::OnceCell<Arc<Manifest>>;
-type LazyPath = std::cell::OnceCell<Arc<Path>>;
+type LazyPath = std::sync::OnceLock<Arc<Path>>;
 
 #[derive(Debug)]
 pub struct Version {
@@ -60,7 +60,7 @@ impl Version {
 	}
 
 	pub fn client_path(&self) -> &Arc<Path> {
-		self.client_path.get_or_init(|| self.path().join(format!("{}.json", self.id)).into())
+		self.client_path.get_or_init(|| self.path().join(format!("{}.jar", self.id)).into())
 	}
 
 	pub fn natives_path(&self) -> &Arc<Path> {
@@ -68,7 +68,7 @@ impl Version {
 	}
 
 	pub async fn manifest(&self) -> Result<Option<Arc<Manifest>>> {
-		if try_exists(self.path()).await? {
+		if try_exists(self.manifest_path()).await? {
 			let initializer = || async move {
 				// Prevent redundant allocations
 				// Most manifestos are ~36Kb in size
@@ -0,0 +1,127 @@
+//! Blocking counterpart of the crate for synchronous programs. Nothing here
+//! needs an async runtime, so it can be used with the `tokio` feature
+//! disabled
+
+#[cfg(feature = "vanilla")]
+pub mod vanilla;
+
+use std::{io::Read, path::Path};
+
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+	json_resource::{read_json, save_blocking, JsonResourceParseError, JsonResourceSaveError},
+	mirror::Mirrored,
+	JsonResource,
+};
+
+pub type BoxedRead = Box<dyn Read + Send + 'static>;
+
+pub trait Client {
+	type Error: std::error::Error + 'static;
+
+	/// Sends a GET request and deserializes the result as json
+	fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned;
+
+	/// Sends a GET request and returns the body as [`Read`]
+	fn get_reader(&self, url: Url) -> Result<BoxedRead, Self::Error>;
+}
+
+#[cfg(feature = "ureq")]
+impl Client for ureq::Agent {
+	type Error = ureq::Error;
+
+	#[inline]
+	fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		self.get(url.as_str()).call()?.body_mut().read_json()
+	}
+
+	#[inline]
+	fn get_reader(&self, url: Url) -> Result<BoxedRead, Self::Error> {
+		Ok(Box::new(self.get(url.as_str()).call()?.into_body().into_reader()))
+	}
+}
+
+impl<C: Client> Client for Mirrored<C> {
+	type Error = C::Error;
+
+	fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		let mut candidates = self.candidates(&url);
+		let last = candidates.pop().unwrap_or(url);
+		for candidate in candidates {
+			match self.client.get_json(candidate.clone()) {
+				Ok(json) => return Ok(json),
+				Err(err) => debug!("{candidate} failed: {err}, trying next mirror"),
+			}
+		}
+		self.client.get_json(last)
+	}
+
+	fn get_reader(&self, url: Url) -> Result<BoxedRead, Self::Error> {
+		let mut candidates = self.candidates(&url);
+		let last = candidates.pop().unwrap_or(url);
+		for candidate in candidates {
+			match self.client.get_reader(candidate.clone()) {
+				Ok(reader) => return Ok(reader),
+				Err(err) => debug!("{candidate} failed: {err}, trying next mirror"),
+			}
+		}
+		self.client.get_reader(last)
+	}
+}
+
+/// Sends a GET request and deserializes the result as json, the error carries
+/// the url
+pub fn fetch_json<C: Client, T: DeserializeOwned>(client: &C, url: Url) -> Result<T, FetchError<C>> {
+	client.get_json(url.clone()).map_err(|source| FetchError::Client { url, source })
+}
+
+/// Sends a GET request and returns the body, the error carries the url
+pub fn fetch_reader<C: Client>(client: &C, url: Url) -> Result<BoxedRead, FetchError<C>> {
+	client.get_reader(url.clone()).map_err(|source| FetchError::Client { url, source })
+}
+
+#[derive(Debug, Error)]
+pub enum FetchError<C: Client> {
+	#[error("Fetch of {url} failed: {source}")]
+	Client {
+		url: Url,
+		#[source]
+		source: C::Error,
+	},
+	#[error("Invalid url: {0}")]
+	ParseUrl(
+		#[from]
+		#[source]
+		url::ParseError,
+	),
+}
+
+/// Stream of a [`JsonResource`] that is read without an async runtime
+#[derive(Debug)]
+pub struct Blocking<R>(pub R);
+
+impl<R: Read, D: DeserializeOwned> JsonResource<Blocking<R>, D> {
+	/// Parses the reader as [D], honoring [verify](JsonResource::verify) and
+	/// [tee](JsonResource::tee)
+	pub fn json(self) -> Result<D, JsonResourceParseError> {
+		read_json(self.stream.0, self.sha1.as_deref(), self.tee.as_deref())
+	}
+
+	/// Copies the underlying reader to the file, checking the sha1 if
+	/// [verified](JsonResource::verify)
+	pub fn save(self, path: impl AsRef<Path>) -> Result<(), JsonResourceSaveError> {
+		save_blocking(self.stream.0, path.as_ref(), self.sha1.as_deref())
+	}
+}
@@ -0,0 +1,36 @@
+//! Blocking version of [`crate::vanilla`]
+
+use spuz_piston::list::Versions;
+
+use super::{fetch_json, fetch_reader, Blocking, BoxedRead, Client, FetchError};
+use crate::{
+	vanilla::{list_url, object_url, package_url, resource_url},
+	JsonResource,
+};
+
+/// Lists all versions of minecraft over time
+pub fn list<C: Client>(client: &C) -> Result<Versions, FetchError<C>> {
+	fetch_json(client, list_url()?)
+}
+
+/// Requests json package from `https://piston-meta.mojang.com/v1/packages` by `hash` and `id`,
+/// the content is [verified](JsonResource::verify) against `hash`
+pub fn package<P, C: Client>(
+	client: &C,
+	hash: &str,
+	id: &str,
+) -> Result<JsonResource<Blocking<BoxedRead>, P>, FetchError<C>> {
+	let reader = fetch_reader(client, package_url(hash, id)?)?;
+
+	Ok(JsonResource::new(Blocking(reader)).verify(hash))
+}
+
+/// Requests binary object from `https://piston-meta.mojang.com/v1/objects` by `hash` and `id`
+pub fn object<C: Client>(client: &C, hash: &str, id: &str) -> Result<BoxedRead, FetchError<C>> {
+	fetch_reader(client, object_url(hash, id)?)
+}
+
+/// Requests game resource (aka assets) from `https://resources.download.minecraft.net/`
+pub fn resource<C: Client>(client: &C, hash: &str) -> Result<BoxedRead, FetchError<C>> {
+	fetch_reader(client, resource_url(hash)?)
+}
@@ -0,0 +1,194 @@
+use std::{
+	fmt::Debug,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use futures_lite::{
+	io::{Cursor, Take},
+	AsyncReadExt,
+};
+use serde::de::DeserializeOwned;
+use tracing::{debug, warn};
+use url::Url;
+
+use super::{Cache, CacheEntry, CacheError};
+use crate::{
+	client::{Body, CacheHeaders, Conditional, Validators},
+	err::status,
+	Client,
+};
+
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map(|it| it.as_secs()).unwrap_or_default()
+}
+
+/// Responses larger than this are not cached by default
+const STREAM_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// Packages and objects of piston-meta are addressed by their sha1, e.g.
+/// `/v1/packages/<sha1>/1.20.6.json`, so their content never changes
+fn is_immutable(url: &Url) -> bool {
+	let mut segments = url.path_segments().into_iter().flatten();
+	segments.next() == Some("v1")
+		&& matches!(segments.next(), Some("packages" | "objects"))
+		&& segments.next().is_some_and(|it| it.len() == 40 && it.bytes().all(|it| it.is_ascii_hexdigit()))
+}
+
+/// Caching client on top of any other [`Client`]. Fresh responses and
+/// content-addressed packages are served from the [`cache`](Self::cache)
+/// without any requests, stale ones are revalidated with
+/// [`Client::get_conditional`]
+#[derive(Debug, Clone)]
+pub struct Cached<C> {
+	pub client: C,
+	pub cache: Cache,
+	/// Serve stale responses when the server is unreachable or fails
+	pub stale_if_error: bool,
+	/// Larger responses are streamed through instead of being cached
+	pub stream_limit: u64,
+}
+
+enum Fetched {
+	Cached(Vec<u8>),
+	Stream(Body),
+}
+
+impl<C: Client + Sync> Cached<C> {
+	pub fn new(client: C, cache: Cache) -> Self {
+		Self { client, cache, stale_if_error: true, stream_limit: STREAM_LIMIT }
+	}
+
+	async fn fetch(&self, url: Url) -> Result<Fetched, CacheError<C>> {
+		let cached = self.cache.load(&url).await?;
+
+		let mut validators = Validators::default();
+		if let Some((entry, body)) = &cached {
+			if entry.is_fresh(now()) || is_immutable(&url) {
+				debug!("{url} is fresh, serving from cache");
+				return Ok(Fetched::Cached(body.clone()));
+			}
+			validators = Validators { etag: entry.etag.clone(), last_modified: entry.last_modified.clone() };
+		}
+
+		let response = match self.client.get_conditional(url.clone(), &validators).await {
+			Ok(response) => response,
+			Err(err) => {
+				let recoverable = status(&err).is_none_or(|status| status >= 500);
+				return match cached {
+					Some((_, body)) if self.stale_if_error && recoverable => {
+						warn!("{url} failed: {err}, serving stale response");
+						Ok(Fetched::Cached(body))
+					}
+					_ => Err(CacheError::Client(err)),
+				};
+			}
+		};
+
+		match (response, cached) {
+			(Conditional::NotModified(headers), Some((mut entry, body))) => {
+				debug!("{url} is not modified");
+				let policy = Policy::from_headers(&headers);
+				entry.stored_at = now();
+				entry.max_age = policy.max_age;
+				entry.no_cache = policy.no_cache;
+				self.cache.update(&url, &entry).await?;
+				Ok(Fetched::Cached(body))
+			}
+			(Conditional::NotModified(_), None) => Err(CacheError::UnexpectedNotModified(url)),
+			(Conditional::Modified(body, headers), _) => self.store(url, body, headers).await,
+		}
+	}
+
+	/// Reads the body into the cache, unless it turns out to be larger than the
+	/// [`stream_limit`](Self::stream_limit). Then the read part and the rest
+	/// are streamed through
+	async fn store(&self, url: Url, body: Body, headers: CacheHeaders) -> Result<Fetched, CacheError<C>> {
+		let policy = Policy::from_headers(&headers);
+		let content_length = body.content_length();
+		if policy.no_store || content_length.is_some_and(|len| len > self.stream_limit) {
+			return Ok(Fetched::Stream(body));
+		}
+
+		let mut content = Vec::new();
+		let mut limited: Take<Body> = body.take(self.stream_limit + 1);
+		limited.read_to_end(&mut content).await?;
+		let body = limited.into_inner();
+		if content.len() as u64 > self.stream_limit {
+			debug!("{url} is larger than {} bytes, streaming it through", self.stream_limit);
+			return Ok(Fetched::Stream(Body::new(Cursor::new(content).chain(body), content_length)));
+		}
+
+		let entry = CacheEntry {
+			url: url.as_str().into(),
+			etag: headers.etag,
+			last_modified: headers.last_modified,
+			stored_at: now(),
+			max_age: policy.max_age,
+			no_cache: policy.no_cache,
+		};
+		self.cache.store(&url, &entry, &content).await?;
+
+		Ok(Fetched::Cached(content))
+	}
+}
+
+/// Parts of `Cache-Control` that matter for a private cache
+#[derive(Debug, Default)]
+struct Policy {
+	max_age: Option<u64>,
+	no_cache: bool,
+	no_store: bool,
+}
+
+impl Policy {
+	fn from_headers(headers: &CacheHeaders) -> Self {
+		let mut policy = Self::default();
+		let directives = headers.cache_control.as_deref().unwrap_or_default();
+
+		for directive in directives.split(',').map(str::trim) {
+			let (name, value) = directive.split_once('=').unwrap_or((directive, ""));
+			match &*name.to_ascii_lowercase() {
+				"max-age" => policy.max_age = value.trim_matches('"').parse().ok(),
+				"no-cache" => policy.no_cache = true,
+				"no-store" => policy.no_store = true,
+				_ => {}
+			}
+		}
+
+		policy
+	}
+}
+
+#[async_trait]
+impl<C> Client for Cached<C>
+where
+	C: Client + Debug + Send + Sync + 'static,
+	C::Error: Send,
+{
+	type Error = CacheError<C>;
+
+	async fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		match self.fetch(url).await? {
+			Fetched::Cached(body) => Ok(serde_json::from_slice(&body)?),
+			Fetched::Stream(mut body) => {
+				let mut content = Vec::new();
+				body.read_to_end(&mut content).await?;
+				Ok(serde_json::from_slice(&content)?)
+			}
+		}
+	}
+
+	async fn get_stream(&self, url: Url) -> Result<Body, Self::Error> {
+		match self.fetch(url).await? {
+			Fetched::Cached(body) => {
+				let len = body.len() as u64;
+				Ok(Body::new(Cursor::new(body), Some(len)))
+			}
+			Fetched::Stream(body) => Ok(body),
+		}
+	}
+}
@@ -0,0 +1,140 @@
+//! On-disk http cache. [`Cache`] stores response bodies with their validators,
+//! and [`Cached`] is a client that revalidates them with conditional requests,
+//! so unchanged metadata, such as the version list, is not downloaded twice
+
+mod cached;
+
+use std::{
+	io,
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use spuz_folder::Folder;
+use spuz_piston::shared::Str;
+use thiserror::Error;
+use tokio::fs;
+use url::Url;
+
+pub use self::cached::Cached;
+use crate::Client;
+
+/// Metadata of the cached response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CacheEntry {
+	pub url: Str,
+	pub etag: Option<Str>,
+	pub last_modified: Option<Str>,
+	/// Unix time in seconds the response was received or revalidated at
+	pub stored_at: u64,
+	/// `max-age` of the `Cache-Control` header
+	pub max_age: Option<u64>,
+	/// Whether `Cache-Control` requires revalidation before every use
+	#[serde(default)]
+	pub no_cache: bool,
+}
+
+impl CacheEntry {
+	/// Whether the entry can be used without asking the server at unix time
+	/// `now`
+	pub fn is_fresh(&self, now: u64) -> bool {
+		!self.no_cache && self.max_age.is_some_and(|age| now < self.stored_at.saturating_add(age))
+	}
+}
+
+/// Directory of cached responses. Every url is stored as a pair of files named
+/// by the sha1 of the url: the body and its [`CacheEntry`] next to it as json
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cache {
+	pub dir: PathBuf,
+}
+
+impl Cache {
+	pub fn new(dir: impl Into<PathBuf>) -> Self {
+		Self { dir: dir.into() }
+	}
+
+	/// Cache located in the `cache` directory of the folder, where
+	/// [`Offline`](crate::offline::Offline) looks for responses
+	pub fn from_folder(folder: &Folder) -> Self {
+		Self::new(folder.root.join("cache"))
+	}
+
+	/// Hex encoded sha1 of the url the files are named by
+	pub fn key(url: &Url) -> String {
+		format!("{:x}", Sha1::digest(url.as_str()))
+	}
+
+	/// Path of the cached body of the `url`
+	pub fn body_path(&self, url: &Url) -> PathBuf {
+		let key = Self::key(url);
+		self.dir.join(&key[..2]).join(key)
+	}
+
+	fn entry_path(&self, url: &Url) -> PathBuf {
+		self.body_path(url).with_extension("json")
+	}
+
+	/// Reads the entry and the body of the `url`, if both are present and
+	/// readable
+	pub async fn load(&self, url: &Url) -> io::Result<Option<(CacheEntry, Vec<u8>)>> {
+		let entry = match fs::read(self.entry_path(url)).await {
+			Ok(entry) => entry,
+			Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+			Err(err) => return Err(err),
+		};
+		let Ok(entry) = serde_json::from_slice::<CacheEntry>(&entry) else {
+			return Ok(None);
+		};
+
+		match fs::read(self.body_path(url)).await {
+			Ok(body) => Ok(Some((entry, body))),
+			Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+			Err(err) => Err(err),
+		}
+	}
+
+	/// Stores the response body with its entry
+	pub async fn store(&self, url: &Url, entry: &CacheEntry, body: &[u8]) -> io::Result<()> {
+		write_atomic(&self.body_path(url), body).await?;
+		self.update(url, entry).await
+	}
+
+	/// Replaces the entry of the already stored response
+	pub async fn update(&self, url: &Url, entry: &CacheEntry) -> io::Result<()> {
+		write_atomic(&self.entry_path(url), &serde_json::to_vec(entry)?).await
+	}
+}
+
+/// Writes into a temporary file first, so readers never see a partial write
+async fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent).await?;
+	}
+
+	let mut part = path.as_os_str().to_owned();
+	part.push(format!(".{}.part", std::process::id()));
+	fs::write(&part, content).await?;
+	fs::rename(&part, path).await
+}
+
+#[derive(Debug, Error)]
+pub enum CacheError<C: Client> {
+	#[error("{0}")]
+	Client(#[source] C::Error),
+	#[error("{0} responded with 304 to a request without validators")]
+	UnexpectedNotModified(Url),
+	#[error("Cached response is not valid json: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
@@ -1,6 +1,13 @@
+use std::{
+	io,
+	pin::Pin,
+	task::{ready, Context, Poll},
+};
+
 use async_trait::async_trait;
 use futures_lite::AsyncRead;
 use serde::de::DeserializeOwned;
+use spuz_piston::shared::Str;
 use thiserror::Error;
 use url::Url;
 
@@ -12,47 +19,165 @@ pub trait Client {
 
 	/// Sends a GET request and deserializes the result as json
 	/// # Example
-	/// ```no_run
+	#[cfg_attr(feature = "testing", doc = "```")]
+	#[cfg_attr(not(feature = "testing"), doc = "```ignore")]
 	/// # use std::error::Error;
-	/// use spuz_get::Client;
-	/// use spuz_piston::Manifest;
+	/// use serde_json::Value;
+	/// use spuz_get::{testing::MockClient, Client};
 	/// # type Result<T> = std::result::Result<T, Box<dyn Error>>;
 	///
-	/// async fn get_manifest<C: Client>(client: &C) -> Result<Manifest>
+	/// const URL: &str = "https://piston-meta.mojang.com/v1/packages/d585c8e981e58326237746ca1253dea15c9e4aaa/24w21b.json";
+	///
+	/// async fn get_id<C: Client>(client: &C) -> Result<Value>
 	/// where
 	/// 	<C as Client>::Error: Error + 'static
 	/// {
-	///   let json = client.get_json("https://piston-meta.mojang.com/v1/packages/d585c8e981e58326237746ca1253dea15c9e4aaa/24w21b.json".parse()?).await?;
-	///   Ok(json)
+	///   let json: Value = client.get_json(URL.parse()?).await?;
+	///   Ok(json["id"].clone())
 	/// }
+	///
+	/// let client = MockClient::new().with(URL, r#"{ "id": "24w21b" }"#);
+	/// assert_eq!(pollster::block_on(get_id(&client)).unwrap(), "24w21b");
 	/// ```
 	async fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
 	where
 		T: DeserializeOwned;
 
-	/// Sends a GET request and returns [AsyncRead] stream
+	/// Sends a GET request and returns the body as [`AsyncRead`] stream, along
+	/// with its length if known
 	///
 	/// # Example
-	/// ```no_run
-	/// # use std::error::Error;
+	#[cfg_attr(feature = "testing", doc = "```")]
+	#[cfg_attr(not(feature = "testing"), doc = "```ignore")]
+	/// # use std::{error::Error, path::Path};
 	/// use async_compat::CompatExt;
 	/// use futures_lite::io;
 	/// use tokio::fs::File;
-	/// use spuz_get::Client;
-	/// use spuz_piston::Manifest;
+	/// use spuz_get::{testing::MockClient, Client};
 	/// # type Result<T> = std::result::Result<T, Box<dyn Error>>;
 	///
-	/// async fn download_jar<C: Client>(client: &C) -> Result<()>
+	/// const URL: &str = "https://piston-data.mojang.com/v1/objects/9d5b45173a0123720bae94afc8a35d742e559d5a/client.jar";
+	///
+	/// async fn download_jar<C: Client>(client: &C, path: &Path) -> Result<()>
 	/// where
 	/// 	<C as Client>::Error: Error + 'static
 	/// {
-	///   let mut stream = client.get_stream("https://piston-data.mojang.com/v1/objects/9d5b45173a0123720bae94afc8a35d742e559d5a/client.jar".parse()?).await?;
-	///   let mut file = File::open("./client.jar").await?;
+	///   let mut stream = client.get_stream(URL.parse()?).await?;
+	///   let mut file = File::create(path).await?;
 	///   io::copy(&mut stream, &mut file.compat_mut()).await?;
 	///   Ok(())
 	/// }
+	///
+	/// # fn main() -> Result<()> {
+	/// let client = MockClient::new().with(URL, "jar");
+	/// let dir = tempfile::tempdir()?;
+	/// let path = dir.path().join("client.jar");
+	///
+	/// tokio::runtime::Runtime::new()?.block_on(download_jar(&client, &path))?;
+	/// assert_eq!(std::fs::read(path)?, b"jar");
+	/// # Ok(())
+	/// # }
 	/// ```
-	async fn get_stream(&self, url: Url) -> Result<BoxedAsyncRead, Self::Error>;
+	async fn get_stream(&self, url: Url) -> Result<Body, Self::Error>;
+
+	/// Sends a GET request with the validators of a cached copy of the
+	/// response. Clients that can not send them ignore the validators and
+	/// always return the whole body without caching headers
+	async fn get_conditional(&self, url: Url, validators: &Validators) -> Result<Conditional, Self::Error> {
+		let _ = validators;
+		Ok(Conditional::Modified(self.get_stream(url).await?, CacheHeaders::default()))
+	}
+}
+
+/// Validators of a cached response, sent as `If-None-Match` and
+/// `If-Modified-Since`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+	pub etag: Option<Str>,
+	pub last_modified: Option<Str>,
+}
+
+/// Headers of a response that decide how it is cached
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheHeaders {
+	pub etag: Option<Str>,
+	pub last_modified: Option<Str>,
+	pub cache_control: Option<Str>,
+}
+
+/// Response to [`Client::get_conditional`]
+#[derive(Debug)]
+pub enum Conditional {
+	/// The cached copy is still valid
+	NotModified(CacheHeaders),
+	Modified(Body, CacheHeaders),
+}
+
+/// Response stream with the `Content-Length` of the response. Reading fails
+/// if the stream ends before the declared length or goes past it, and if it
+/// exceeds the [limit](Body::limit)
+pub struct Body {
+	stream: BoxedAsyncRead,
+	content_length: Option<u64>,
+	limit: Option<u64>,
+	read: u64,
+}
+
+impl Body {
+	pub fn new(stream: impl AsyncRead + Unpin + Send + Sync + 'static, content_length: Option<u64>) -> Self {
+		Self { stream: Box::new(stream), content_length, limit: None, read: 0 }
+	}
+
+	/// Fails reading once more than `limit` bytes are read
+	#[must_use]
+	pub fn limit(mut self, limit: u64) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	/// Declared length of the whole body
+	pub fn content_length(&self) -> Option<u64> {
+		self.content_length
+	}
+
+	/// Number of bytes read so far, to report progress
+	pub fn bytes_read(&self) -> u64 {
+		self.read
+	}
+}
+
+impl std::fmt::Debug for Body {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Body")
+			.field("content_length", &self.content_length)
+			.field("limit", &self.limit)
+			.field("read", &self.read)
+			.finish_non_exhaustive()
+	}
+}
+
+impl AsyncRead for Body {
+	fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+		let read = ready!(Pin::new(&mut self.stream).poll_read(cx, buf))?;
+		self.read += read as u64;
+
+		if let Some(limit) = self.limit.filter(|it| self.read > *it) {
+			return Poll::Ready(Err(io::Error::new(
+				io::ErrorKind::FileTooLarge,
+				format!("body is larger than the limit of {limit} bytes"),
+			)));
+		}
+		match self.content_length {
+			Some(len) if self.read > len => {
+				Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, format!("body is longer than {len} bytes"))))
+			}
+			Some(len) if read == 0 && !buf.is_empty() && self.read < len => Poll::Ready(Err(io::Error::new(
+				io::ErrorKind::UnexpectedEof,
+				format!("body ended after {} of {len} bytes", self.read),
+			))),
+			_ => Poll::Ready(Ok(read)),
+		}
+	}
 }
 
 #[cfg(feature = "reqwest")]
@@ -65,28 +190,85 @@ impl Client for reqwest::Client {
 	where
 		T: DeserializeOwned,
 	{
-		self.get(url).send().await?.json().await
+		self.get(url).send().await?.error_for_status()?.json().await
 	}
 
 	#[inline]
-	async fn get_stream(&self, url: Url) -> Result<BoxedAsyncRead, Self::Error> {
-		use futures_util::TryStreamExt;
+	async fn get_stream(&self, url: Url) -> Result<Body, Self::Error> {
+		let response = self.get(url).send().await?.error_for_status()?;
+		Ok(response_body(response))
+	}
 
-		#[inline]
-		fn map_err(err: reqwest::Error) -> std::io::Error {
-			std::io::Error::new(std::io::ErrorKind::Other, err)
+	async fn get_conditional(&self, url: Url, validators: &Validators) -> Result<Conditional, Self::Error> {
+		let response = conditional_request(self.get(url), validators).send().await?;
+		if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+			return Ok(Conditional::NotModified(cache_headers(response.headers())));
 		}
+		let response = response.error_for_status()?;
+		let headers = cache_headers(response.headers());
+		Ok(Conditional::Modified(response_body(response), headers))
+	}
+}
 
-		let bytes_stream = self.get(url).send().await?.bytes_stream().map_err(map_err);
+#[cfg(feature = "reqwest")]
+pub(crate) fn conditional_request(
+	request: reqwest::RequestBuilder,
+	validators: &Validators,
+) -> reqwest::RequestBuilder {
+	use reqwest::header::{IF_MODIFIED_SINCE, IF_NONE_MATCH};
 
-		Ok(Box::new(bytes_stream.into_async_read()))
+	let mut request = request;
+	if let Some(etag) = &validators.etag {
+		request = request.header(IF_NONE_MATCH, &**etag);
+	}
+	if let Some(last_modified) = &validators.last_modified {
+		request = request.header(IF_MODIFIED_SINCE, &**last_modified);
 	}
+	request
+}
+
+#[cfg(feature = "reqwest")]
+pub(crate) fn cache_headers(headers: &reqwest::header::HeaderMap) -> CacheHeaders {
+	use reqwest::header::{HeaderName, CACHE_CONTROL, ETAG, LAST_MODIFIED};
+
+	let header = |name: HeaderName| -> Option<Str> {
+		let values: Vec<_> = headers.get_all(name).iter().filter_map(|it| it.to_str().ok()).collect();
+		(!values.is_empty()).then(|| values.join(", ").into())
+	};
+	CacheHeaders { etag: header(ETAG), last_modified: header(LAST_MODIFIED), cache_control: header(CACHE_CONTROL) }
+}
+
+#[cfg(feature = "reqwest")]
+pub(crate) fn response_body(response: reqwest::Response) -> Body {
+	use futures_util::TryStreamExt;
+
+	let content_length = response.content_length();
+	let stream = response.bytes_stream().map_err(io::Error::other).into_async_read();
+
+	Body::new(stream, content_length)
+}
+
+/// Sends a GET request and deserializes the result as json, the error carries
+/// the url
+#[cfg(feature = "tokio")]
+pub async fn fetch_json<C: Client, T: DeserializeOwned>(client: &C, url: Url) -> Result<T, FetchError<C>> {
+	client.get_json(url.clone()).await.map_err(|source| FetchError::Client { url, source })
+}
+
+/// Sends a GET request and returns the body, the error carries the url
+#[cfg(feature = "tokio")]
+pub async fn fetch_stream<C: Client>(client: &C, url: Url) -> Result<Body, FetchError<C>> {
+	client.get_stream(url.clone()).await.map_err(|source| FetchError::Client { url, source })
 }
 
 #[derive(Debug, Error)]
 pub enum FetchError<C: Client> {
-	#[error("Fetch failed: {0}")]
-	Client(#[source] C::Error),
+	#[error("Fetch of {url} failed: {source}")]
+	Client {
+		url: Url,
+		#[source]
+		source: C::Error,
+	},
 	#[error("Invalid url: {0}")]
 	ParseUrl(
 		#[from]
@@ -0,0 +1,416 @@
+//! Client of the [curseforge](https://curseforge.com) api. Every request has
+//! to carry the `x-api-key` header, so the [`Client`] must be configured with
+//! the key of the caller, e.g. with [`CurseForge::http`]
+
+pub mod pack;
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use spuz_piston::shared::{Arr, Str};
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+	client::{fetch_json, fetch_stream},
+	download::{self, Checksum, DownloadError},
+	Client, FetchError,
+};
+
+/// Official curseforge api
+pub const API: &str = "https://api.curseforge.com/v1";
+
+/// Id of minecraft among curseforge games
+pub const MINECRAFT: u32 = 432;
+
+/// Hash algorithm ids used in [`FileHash::algo`]
+const SHA1: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModLoader {
+	Forge,
+	Fabric,
+	Quilt,
+	NeoForge,
+}
+
+impl ModLoader {
+	/// `modLoaderType` of the api
+	pub fn id(self) -> u8 {
+		match self {
+			Self::Forge => 1,
+			Self::Fabric => 4,
+			Self::Quilt => 5,
+			Self::NeoForge => 6,
+		}
+	}
+}
+
+/// Class of a project, determines the directory its files go to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+	Mods,
+	ResourcePacks,
+	ShaderPacks,
+	Worlds,
+	Modpacks,
+}
+
+impl Class {
+	pub fn id(self) -> u32 {
+		match self {
+			Self::Mods => 6,
+			Self::ResourcePacks => 12,
+			Self::ShaderPacks => 6552,
+			Self::Worlds => 17,
+			Self::Modpacks => 4471,
+		}
+	}
+
+	pub fn from_id(id: u32) -> Option<Self> {
+		[Self::Mods, Self::ResourcePacks, Self::ShaderPacks, Self::Worlds, Self::Modpacks]
+			.into_iter()
+			.find(|it| it.id() == id)
+	}
+
+	/// Directory of the game directory the files of the class belong to
+	pub fn dir(self) -> &'static str {
+		match self {
+			Self::Mods | Self::Modpacks => "mods",
+			Self::ResourcePacks => "resourcepacks",
+			Self::ShaderPacks => "shaderpacks",
+			Self::Worlds => "saves",
+		}
+	}
+}
+
+/// Search parameters, minecraft is always implied
+///
+/// # Example
+/// ```
+/// use spuz_get::curseforge::{Class, ModLoader, ModSearch};
+///
+/// let search = ModSearch::new("jei").class(Class::Mods).game_version("1.20.6").loader(ModLoader::NeoForge);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModSearch {
+	pub filter: Option<Str>,
+	pub class: Option<Class>,
+	pub category: Option<u32>,
+	pub game_version: Option<Str>,
+	pub loader: Option<ModLoader>,
+	pub index: Option<u32>,
+	pub page_size: Option<u32>,
+}
+
+impl ModSearch {
+	pub fn new(filter: impl Into<Str>) -> Self {
+		Self { filter: Some(filter.into()), ..Self::default() }
+	}
+
+	#[must_use]
+	pub fn class(mut self, class: Class) -> Self {
+		self.class = Some(class);
+		self
+	}
+
+	#[must_use]
+	pub fn category(mut self, category: u32) -> Self {
+		self.category = Some(category);
+		self
+	}
+
+	#[must_use]
+	pub fn game_version(mut self, version: impl Into<Str>) -> Self {
+		self.game_version = Some(version.into());
+		self
+	}
+
+	#[must_use]
+	pub fn loader(mut self, loader: ModLoader) -> Self {
+		self.loader = Some(loader);
+		self
+	}
+
+	#[must_use]
+	pub fn index(mut self, index: u32) -> Self {
+		self.index = Some(index);
+		self
+	}
+
+	#[must_use]
+	pub fn page_size(mut self, page_size: u32) -> Self {
+		self.page_size = Some(page_size);
+		self
+	}
+
+	fn apply(&self, url: &mut Url) {
+		let mut pairs = url.query_pairs_mut();
+		pairs.append_pair("gameId", &MINECRAFT.to_string());
+		if let Some(filter) = &self.filter {
+			pairs.append_pair("searchFilter", filter);
+		}
+		if let Some(class) = self.class {
+			pairs.append_pair("classId", &class.id().to_string());
+		}
+		if let Some(category) = self.category {
+			pairs.append_pair("categoryId", &category.to_string());
+		}
+		if let Some(version) = &self.game_version {
+			pairs.append_pair("gameVersion", version);
+		}
+		if let Some(loader) = self.loader {
+			pairs.append_pair("modLoaderType", &loader.id().to_string());
+		}
+		if let Some(index) = self.index {
+			pairs.append_pair("index", &index.to_string());
+		}
+		if let Some(page_size) = self.page_size {
+			pairs.append_pair("pageSize", &page_size.to_string());
+		}
+	}
+}
+
+/// Every response of the api is wrapped into `data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Data<T> {
+	data: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page<T> {
+	pub data: Arr<T>,
+	pub pagination: Pagination,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+	pub index: u32,
+	pub page_size: u32,
+	pub result_count: u32,
+	pub total_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mod {
+	pub id: u32,
+	pub name: Str,
+	pub slug: Str,
+	#[serde(default)]
+	pub summary: Str,
+	pub class_id: Option<u32>,
+	#[serde(default)]
+	pub download_count: u64,
+	#[serde(default)]
+	pub categories: Arr<Category>,
+	#[serde(default)]
+	pub latest_files: Arr<File>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Category {
+	pub id: u32,
+	pub name: Str,
+	pub slug: Str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct File {
+	pub id: u32,
+	pub mod_id: u32,
+	pub display_name: Str,
+	pub file_name: Str,
+	/// Missing when the author does not allow third party downloads
+	pub download_url: Option<Str>,
+	#[serde(default)]
+	pub hashes: Arr<FileHash>,
+	pub file_length: u64,
+	#[serde(default)]
+	pub game_versions: Arr<Str>,
+	#[serde(default)]
+	pub dependencies: Arr<FileDependency>,
+}
+
+impl File {
+	pub fn sha1(&self) -> Option<&str> {
+		self.hashes.iter().find(|it| it.algo == SHA1).map(|it| &*it.value)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHash {
+	pub value: Str,
+	pub algo: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDependency {
+	pub mod_id: u32,
+	/// `3` is a required dependency
+	pub relation_type: u8,
+}
+
+/// Client of the curseforge api located at [`api`](Self::api). Use
+/// [`CurseForge::default`] to talk to the official one or [`CurseForge::new`]
+/// to point it anywhere else
+///
+/// # Example
+/// ```no_run
+/// # use std::error::Error;
+/// # use pollster::FutureExt;
+/// use spuz_get::curseforge::{CurseForge, ModSearch};
+///
+/// # async move {
+/// let curseforge = CurseForge::default();
+/// let client = curseforge.http("api key")?;
+///
+/// let found = curseforge.search(&client, &ModSearch::new("jei")).await?;
+/// let files = curseforge.files(&client, found.data[0].id, Some("1.20.6"), None).await?;
+/// # Result::<(), Box<dyn Error>>::Ok(())
+/// # }.block_on();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CurseForge {
+	pub api: Box<str>,
+}
+
+impl Default for CurseForge {
+	fn default() -> Self {
+		Self::new(API)
+	}
+}
+
+impl CurseForge {
+	pub fn new(api: impl Into<Box<str>>) -> Self {
+		Self { api: api.into() }
+	}
+
+	/// [`Http`](crate::http::Http) client that sends the `api_key` with the
+	/// requests to the [`api`](Self::api) only
+	#[cfg(feature = "reqwest")]
+	pub fn http(&self, api_key: &str) -> Result<crate::http::Http, ApiKeyError> {
+		use reqwest::header::{HeaderName, HeaderValue};
+
+		let mut key = HeaderValue::from_str(api_key)?;
+		key.set_sensitive(true);
+
+		let api = Url::parse(&self.api)?;
+		Ok(crate::http::Http::builder().scoped_header(api, HeaderName::from_static("x-api-key"), key).build()?)
+	}
+
+	/// Url of the endpoint under the api, the segments are percent-encoded
+	fn url(&self, segments: &[&str]) -> Result<Url, url::ParseError> {
+		let mut url = Url::parse(&self.api)?;
+		url
+			.path_segments_mut()
+			.map_err(|()| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+			.pop_if_empty()
+			.extend(segments);
+		Ok(url)
+	}
+
+	/// Searches minecraft projects matching the `search`
+	pub async fn search<C: Client>(&self, client: &C, search: &ModSearch) -> Result<Page<Mod>, FetchError<C>> {
+		let mut url = self.url(&["mods", "search"])?;
+		search.apply(&mut url);
+		fetch_json(client, url).await
+	}
+
+	/// Requests the project by its id
+	pub async fn project<C: Client>(&self, client: &C, id: u32) -> Result<Mod, FetchError<C>> {
+		let data: Data<Mod> = fetch_json(client, self.url(&["mods", &id.to_string()])?).await?;
+		Ok(data.data)
+	}
+
+	/// Lists files of the project, optionally only ones for the `game_version`
+	/// and the `loader`
+	pub async fn files<C: Client>(
+		&self,
+		client: &C,
+		project: u32,
+		game_version: Option<&str>,
+		loader: Option<ModLoader>,
+	) -> Result<Page<File>, FetchError<C>> {
+		let mut url = self.url(&["mods", &project.to_string(), "files"])?;
+		{
+			let mut pairs = url.query_pairs_mut();
+			if let Some(version) = game_version {
+				pairs.append_pair("gameVersion", version);
+			}
+			if let Some(loader) = loader {
+				pairs.append_pair("modLoaderType", &loader.id().to_string());
+			}
+		}
+		fetch_json(client, url).await
+	}
+
+	/// Requests the file of the project
+	pub async fn file<C: Client>(&self, client: &C, project: u32, file: u32) -> Result<File, FetchError<C>> {
+		let data: Data<File> =
+			fetch_json(client, self.url(&["mods", &project.to_string(), "files", &file.to_string()])?).await?;
+		Ok(data.data)
+	}
+
+	/// Downloads the file into `dir`, verifying its sha1. Fails with
+	/// [`CurseForgeError::DownloadNotAllowed`] if the author does not allow
+	/// third party downloads, such files have to be downloaded by the user
+	pub async fn download<C: Client>(
+		&self,
+		client: &C,
+		file: &File,
+		dir: &Path,
+	) -> Result<(PathBuf, Checksum), CurseForgeError<C>> {
+		let url = file
+			.download_url
+			.as_deref()
+			.ok_or(CurseForgeError::DownloadNotAllowed { project: file.mod_id, file: file.id })?;
+		let path = dir.join(Path::new(&*file.file_name).file_name().unwrap_or_default());
+		let stream = fetch_stream(client, Url::parse(url)?).await?;
+		let checksum = download::save(stream, &path, file.sha1()).await?;
+
+		Ok((path, checksum))
+	}
+}
+
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Error)]
+pub enum ApiKeyError {
+	#[error("Api key is not a valid header value")]
+	Invalid(
+		#[from]
+		#[source]
+		reqwest::header::InvalidHeaderValue,
+	),
+	#[error("Invalid api url: {0}")]
+	Url(
+		#[from]
+		#[source]
+		url::ParseError,
+	),
+	#[error("Failed to build the client: {0}")]
+	Build(
+		#[from]
+		#[source]
+		reqwest::Error,
+	),
+}
+
+#[derive(Debug, Error)]
+pub enum CurseForgeError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error("File {file} of project {project} can not be downloaded by third parties")]
+	DownloadNotAllowed { project: u32, file: u32 },
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+}
+
+impl<C: Client> From<url::ParseError> for CurseForgeError<C> {
+	fn from(value: url::ParseError) -> Self {
+		Self::Fetch(value.into())
+	}
+}
@@ -0,0 +1,313 @@
+//! Importer of curseforge modpacks. The zip carries `manifest.json` with the
+//! game and loader versions and the project files to download, and an
+//! `overrides` directory copied into the game directory as is
+//!
+//! # Example
+//! ```no_run
+//! # use std::error::Error;
+//! # use pollster::FutureExt;
+//! use spuz_folder::Folder;
+//! use spuz_get::{
+//!   curseforge::{pack::Modpack, CurseForge},
+//!   http::Http,
+//! };
+//!
+//! # async move {
+//! let curseforge = CurseForge::default();
+//! let api = curseforge.http("api key")?;
+//! let client = Http::builder().build()?;
+//! let folder = Folder::settle("./spuz").await?;
+//! let pack = Modpack::open("./pack.zip").await?;
+//! let imported = pack.install(&client, &curseforge, &api, &folder, "pack").await?;
+//! for file in &imported.blocked {
+//!   println!("{} has to be downloaded manually", file.file_name);
+//! }
+//! # Result::<(), Box<dyn Error>>::Ok(())
+//! # }.block_on();
+//! ```
+
+use std::{
+	collections::{hash_map::Entry, HashMap},
+	io::{self, Cursor, Read},
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use spuz_folder::{Folder, Instance};
+use spuz_piston::shared::{Arr, Str};
+use thiserror::Error;
+use tokio::fs;
+use tracing::debug;
+use zip::{result::ZipError, ZipArchive};
+
+use super::{Class, CurseForge, CurseForgeError, File};
+use crate::{
+	download::{self, DownloadError},
+	modpack::{self, InstallVersionError, LoaderKind},
+	Client, FetchError,
+};
+
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackManifest {
+	pub minecraft: PackMinecraft,
+	pub manifest_type: Str,
+	pub manifest_version: u32,
+	pub name: Str,
+	#[serde(default)]
+	pub version: Str,
+	pub author: Option<Str>,
+	#[serde(default)]
+	pub files: Arr<PackFile>,
+	/// Directory of the archive copied into the game directory
+	pub overrides: Option<Str>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackMinecraft {
+	pub version: Str,
+	#[serde(default)]
+	pub mod_loaders: Arr<PackModLoader>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PackModLoader {
+	/// Loader and its version, e.g. `forge-47.2.0`
+	pub id: Str,
+	#[serde(default)]
+	pub primary: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PackFile {
+	#[serde(rename = "projectID")]
+	pub project_id: u32,
+	#[serde(rename = "fileID")]
+	pub file_id: u32,
+	#[serde(default = "required")]
+	pub required: bool,
+}
+
+fn required() -> bool {
+	true
+}
+
+/// Result of the [import](Modpack::install)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Imported {
+	pub instance: Instance,
+	/// Files the authors do not allow to download by third parties. They have
+	/// to be downloaded by the user into the directory of their class
+	pub blocked: Arr<File>,
+}
+
+/// Opened curseforge modpack zip
+pub struct Modpack {
+	pub manifest: PackManifest,
+	archive: ZipArchive<Cursor<Vec<u8>>>,
+	java: PathBuf,
+}
+
+impl std::fmt::Debug for Modpack {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Modpack").field("manifest", &self.manifest).finish_non_exhaustive()
+	}
+}
+
+impl Modpack {
+	/// Reads the archive and parses its manifest
+	pub async fn open(path: impl AsRef<Path>) -> Result<Self, OpenPackError> {
+		Self::from_bytes(fs::read(path).await?)
+	}
+
+	pub fn from_bytes(content: Vec<u8>) -> Result<Self, OpenPackError> {
+		let mut archive = ZipArchive::new(Cursor::new(content))?;
+
+		let mut entry = archive.by_name(MANIFEST).map_err(|err| match err {
+			ZipError::FileNotFound => OpenPackError::MissingManifest,
+			err => err.into(),
+		})?;
+		let mut content = Vec::with_capacity(usize::try_from(entry.size()).unwrap_or_default());
+		entry.read_to_end(&mut content)?;
+		drop(entry);
+
+		let manifest: PackManifest = serde_json::from_slice(&content)?;
+		if &*manifest.manifest_type != "minecraftModpack" || manifest.manifest_version != 1 {
+			return Err(OpenPackError::Unsupported {
+				manifest_type: manifest.manifest_type,
+				manifest_version: manifest.manifest_version,
+			});
+		}
+
+		Ok(Self { manifest, archive, java: "java".into() })
+	}
+
+	/// Java binary running the processors of forge and neoforge installers,
+	/// `java` from the `PATH` by default
+	#[must_use]
+	pub fn java(mut self, java: impl Into<PathBuf>) -> Self {
+		self.java = java.into();
+		self
+	}
+
+	/// Primary loader of the pack and its version
+	pub fn loader(&self) -> Option<(LoaderKind, &str)> {
+		let loaders = &self.manifest.minecraft.mod_loaders;
+		let primary = loaders.iter().find(|it| it.primary).or_else(|| loaders.first())?;
+
+		match primary.id.split_once('-')? {
+			("forge", version) => Some((LoaderKind::Forge, version)),
+			("neoforge", version) => Some((LoaderKind::NeoForge, version)),
+			("fabric", version) => Some((LoaderKind::Fabric, version)),
+			("quilt", version) => Some((LoaderKind::Quilt, version)),
+			_ => None,
+		}
+	}
+
+	/// Creates the instance `name` in the `folder`, downloads required files
+	/// of the pack into its game directory, copies the overrides over them and
+	/// installs the game and the loader. Requests to `curseforge` go through
+	/// `api`, which carries the api key, everything else through `client`.
+	/// Files that can not be downloaded are [reported](Imported::blocked), the
+	/// instance is removed again if anything else fails
+	pub async fn install<C: Client>(
+		mut self,
+		client: &C,
+		curseforge: &CurseForge,
+		api: &C,
+		folder: &Folder,
+		name: &str,
+	) -> Result<Imported, PackError<C>> {
+		let minecraft = self.manifest.minecraft.version.clone();
+		let loader = self.loader().map(|(loader, version)| (loader, Str::from(version)));
+		let loader = loader.as_ref().map(|(loader, version)| (*loader, &**version));
+
+		let instance = folder.instances.create(name, modpack::config(&minecraft, loader)).await?;
+		let blocked = match self.install_into(client, curseforge, api, folder, &instance.game_dir(), loader).await {
+			Ok(blocked) => blocked,
+			Err(err) => {
+				folder.instances.delete(name).await.ok();
+				return Err(err);
+			}
+		};
+
+		debug!("Modpack {} installed as {name}", self.manifest.name);
+		Ok(Imported { instance, blocked: blocked.into() })
+	}
+
+	async fn install_into<C: Client>(
+		&mut self,
+		client: &C,
+		curseforge: &CurseForge,
+		api: &C,
+		folder: &Folder,
+		game_dir: &Path,
+		loader: Option<(LoaderKind, &str)>,
+	) -> Result<Vec<File>, PackError<C>> {
+		let mut blocked = Vec::new();
+		let mut classes = HashMap::new();
+		for pack_file in self.manifest.files.iter().filter(|it| it.required) {
+			let file = curseforge.file(api, pack_file.project_id, pack_file.file_id).await?;
+			if file.download_url.is_none() {
+				debug!("{} can not be downloaded by third parties", file.file_name);
+				blocked.push(file);
+				continue;
+			}
+
+			let class = match classes.entry(pack_file.project_id) {
+				Entry::Occupied(entry) => *entry.get(),
+				Entry::Vacant(entry) => {
+					let project = curseforge.project(api, pack_file.project_id).await?;
+					*entry.insert(project.class_id.and_then(Class::from_id).unwrap_or(Class::Mods))
+				}
+			};
+			install_file(client, curseforge, &file, &game_dir.join(class.dir())).await?;
+		}
+
+		if let Some(overrides) = self.manifest.overrides.as_deref() {
+			let entries = modpack::read_dir(&mut self.archive, overrides, game_dir, PackError::UnsafePath)?;
+			modpack::write_entries(entries).await?;
+		}
+
+		modpack::install_version(client, folder, &self.manifest.minecraft.version, loader, &self.java).await?;
+		Ok(blocked)
+	}
+}
+
+async fn install_file<C: Client>(
+	client: &C,
+	curseforge: &CurseForge,
+	file: &File,
+	dir: &Path,
+) -> Result<(), PackError<C>> {
+	let name = download::relative_path(&*file.file_name).ok_or_else(|| PackError::UnsafePath(file.file_name.clone()))?;
+
+	if let Some(sha1) = file.sha1() {
+		if download::is_valid(&dir.join(name), sha1).await? {
+			debug!("{name:?} is up to date, skipping");
+			return Ok(());
+		}
+	}
+
+	curseforge.download(client, file, dir).await?;
+	Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum OpenPackError {
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+	#[error("Invalid modpack archive: {0}")]
+	Zip(
+		#[from]
+		#[source]
+		ZipError,
+	),
+	#[error("Modpack has no `{MANIFEST}`")]
+	MissingManifest,
+	#[error("Invalid modpack manifest: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error("Unsupported modpack manifest {manifest_type} version {manifest_version}")]
+	Unsupported { manifest_type: Str, manifest_version: u32 },
+}
+
+#[derive(Debug, Error)]
+pub enum PackError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error(transparent)]
+	CurseForge(#[from] CurseForgeError<C>),
+	#[error(transparent)]
+	Open(#[from] OpenPackError),
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+	#[error(transparent)]
+	Version(#[from] InstallVersionError<C>),
+	#[error(transparent)]
+	Folder(#[from] spuz_folder::Error),
+	#[error("Path `{0}` leaves the game directory")]
+	UnsafePath(Str),
+	#[error("Invalid modpack archive: {0}")]
+	Zip(
+		#[from]
+		#[source]
+		ZipError,
+	),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
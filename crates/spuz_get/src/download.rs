@@ -0,0 +1,136 @@
+use std::{
+	io,
+	path::Path,
+	pin::Pin,
+	task::{Context, Poll},
+};
+
+use async_compat::CompatExt;
+use futures_lite::{io::copy, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use sha1::{Digest, Sha1};
+use thiserror::Error;
+use tokio::fs::{self, File};
+
+#[derive(Debug, Error)]
+pub enum DownloadError {
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+	#[error("Hash mismatch for {path:?}. Expected: {expected}, actual: {actual}")]
+	HashMismatch { path: Box<Path>, expected: Box<str>, actual: Box<str> },
+}
+
+/// Hash and size of the saved content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+	pub sha1: Box<str>,
+	pub size: u64,
+}
+
+/// Computes hex encoded sha1 of the whole stream
+pub async fn sha1(mut stream: impl AsyncRead + Unpin) -> io::Result<String> {
+	let mut hasher = Sha1::new();
+	let mut buf = vec![0u8; 0x4000];
+
+	loop {
+		let read = stream.read(&mut buf).await?;
+		if read == 0 {
+			break;
+		}
+		hasher.update(&buf[..read]);
+	}
+
+	Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Checks whether the file exists and its sha1 matches `expected`
+pub async fn is_valid(path: &Path, expected: &str) -> io::Result<bool> {
+	match File::open(path).await {
+		Ok(file) => Ok(sha1(file.compat()).await?.eq_ignore_ascii_case(expected)),
+		Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+		Err(err) => Err(err),
+	}
+}
+
+/// Paths from manifests and packs are written by third parties, so they must
+/// stay inside the directory they are joined to
+#[cfg(any(feature = "vanilla", feature = "modrinth", feature = "curseforge"))]
+pub(crate) fn relative_path<P: AsRef<Path> + ?Sized>(path: &P) -> Option<&Path> {
+	use std::path::Component;
+
+	let path = path.as_ref();
+	path.components().all(|it| matches!(it, Component::Normal(_))).then_some(path)
+}
+
+/// Copies the stream into the file at `path`, creating parent directories if
+/// needed. The content is written to a temporary `.part` file first and only
+/// renamed to `path` once the sha1 matches `expected` (if given).
+pub async fn save(
+	stream: impl AsyncRead + Unpin,
+	path: &Path,
+	expected: Option<&str>,
+) -> Result<Checksum, DownloadError> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent).await?;
+	}
+
+	let mut part = path.as_os_str().to_owned();
+	part.push(".part");
+	let part = Path::new(&part);
+
+	let file = File::create(part).await?;
+	let mut writer = HashWriter { inner: file.compat(), hasher: Sha1::new() };
+	let copied = async {
+		let size = copy(stream, &mut writer).await?;
+		writer.flush().await?;
+		Ok::<_, io::Error>(size)
+	};
+	let size = match copied.await {
+		Ok(size) => size,
+		Err(err) => {
+			drop(writer);
+			let _ = fs::remove_file(part).await;
+			return Err(err.into());
+		}
+	};
+
+	let actual = format!("{:x}", writer.hasher.finalize());
+
+	match expected {
+		Some(expected) if !expected.eq_ignore_ascii_case(&actual) => {
+			fs::remove_file(part).await?;
+			Err(DownloadError::HashMismatch { path: path.into(), expected: expected.into(), actual: actual.into() })
+		}
+		_ => {
+			fs::rename(part, path).await?;
+			Ok(Checksum { sha1: actual.into(), size })
+		}
+	}
+}
+
+struct HashWriter<W> {
+	inner: W,
+	hasher: Sha1,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for HashWriter<W> {
+	fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+		let this = &mut *self;
+		let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+		if let Poll::Ready(Ok(written)) = poll {
+			this.hasher.update(&buf[..written]);
+		}
+		poll
+	}
+
+	fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.inner).poll_flush(cx)
+	}
+
+	fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.inner).poll_close(cx)
+	}
+}
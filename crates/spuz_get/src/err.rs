@@ -1,29 +1,577 @@
-use std::fmt::Debug;
+//! Error every api of the crate converts into with `?`. Fetch failures carry
+//! the url and are split into http statuses and failures of the client
 
+use std::{io, path::Path};
+
+use spuz_piston::{maven::ParseCoordinateError, shared::BoxPath};
 use thiserror::Error;
+use url::Url;
 
+#[cfg(feature = "tokio")]
 use crate::{
+	cache::CacheError,
+	download::DownloadError,
 	ext::{FsExtLoadError, FsExtSaveError},
 	json_resource::{JsonResourceParseError, JsonResourceSaveError},
-	Client, FetchError,
+	offline::OfflineError,
 };
+use crate::{Client, FetchError};
 
 #[derive(Debug, Error)]
 pub enum Error {
-	#[error("FetchError: {0}")]
-	Fetch(#[source] Box<dyn std::error::Error>),
+	#[error("Fetch of {url} failed: {source}")]
+	Fetch {
+		url: Url,
+		#[source]
+		source: ClientError,
+	},
+	#[error("{url} responded with status {status}")]
+	Status {
+		url: Url,
+		status: u16,
+		#[source]
+		source: Box<dyn std::error::Error + Send + Sync>,
+	},
+	#[error(transparent)]
+	Client(#[from] ClientError),
+	#[error("Invalid url: {0}")]
+	Url(
+		#[from]
+		#[source]
+		url::ParseError,
+	),
+	#[error("Hash mismatch{}. Expected: {expected}, actual: {actual}", path.as_ref().map(|it| format!(" for {}", it.display())).unwrap_or_default())]
+	HashMismatch { path: Option<Box<Path>>, expected: Box<str>, actual: Box<str> },
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+	#[error("Parse error: {0}")]
+	Parse(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error(transparent)]
+	Coordinate(#[from] ParseCoordinateError),
+	#[error("Path {0:?} leaves the target directory")]
+	UnsafePath(BoxPath),
+	#[cfg(all(feature = "vanilla", feature = "tokio"))]
+	#[error("Component `{0}` is not available for the target runtime")]
+	UnknownComponent(Box<str>),
+	#[cfg(any(feature = "forge", feature = "neoforge"))]
+	#[error("{0} is not a sha1 checksum")]
+	InvalidChecksum(Box<str>),
+	#[cfg(any(feature = "forge", feature = "neoforge"))]
+	#[error("Processor `{0}` has no main class")]
+	MissingMainClass(Box<str>),
+	#[cfg(any(feature = "forge", feature = "neoforge"))]
+	#[error("Processor `{jar}` failed with code {code:?}: {stderr}")]
+	ProcessorFailed { jar: Box<str>, code: Option<i32>, stderr: Box<str> },
+	#[cfg(any(feature = "forge", feature = "neoforge"))]
 	#[error(transparent)]
-	JsonResourceSave(#[from] JsonResourceSaveError),
+	Installer(crate::installer::OpenInstallerError),
+	#[cfg(feature = "modrinth")]
+	#[error("No compatible version of {0}")]
+	Unresolved(Box<str>),
+	#[cfg(feature = "modrinth")]
 	#[error(transparent)]
-	JsonResourceParse(#[from] JsonResourceParseError),
+	Mrpack(crate::modrinth::mrpack::OpenMrpackError),
+	#[cfg(feature = "modrinth")]
+	#[error("Modpack does not depend on minecraft")]
+	MissingMinecraft,
+	#[cfg(any(feature = "modrinth", feature = "curseforge"))]
+	#[error("Version {0} is not in the version list")]
+	UnknownVersion(Box<str>),
+	#[cfg(feature = "tokio")]
 	#[error(transparent)]
-	FsExtSave(#[from] FsExtSaveError),
+	Folder(#[from] spuz_folder::Error),
+	#[cfg(feature = "modrinth")]
+	#[error("File `{0}` has no downloads")]
+	NoDownloads(Box<str>),
+	#[cfg(feature = "curseforge")]
+	#[error("File {file} of project {project} can not be downloaded by third parties")]
+	DownloadNotAllowed { project: u32, file: u32 },
+	#[cfg(all(feature = "curseforge", feature = "reqwest"))]
 	#[error(transparent)]
-	FsExtLoda(#[from] FsExtLoadError),
+	ApiKey(#[from] crate::curseforge::ApiKeyError),
+	#[cfg(feature = "curseforge")]
+	#[error(transparent)]
+	Pack(crate::curseforge::pack::OpenPackError),
+	#[cfg(any(feature = "forge", feature = "neoforge", feature = "modrinth", feature = "curseforge"))]
+	#[error("Invalid archive: {0}")]
+	Zip(
+		#[from]
+		#[source]
+		zip::result::ZipError,
+	),
+	#[cfg(feature = "skins")]
+	#[error("Profile has no textures")]
+	NoTextures,
+	#[cfg(feature = "skins")]
+	#[error("Invalid textures property: {0}")]
+	Decode(
+		#[from]
+		#[source]
+		base64::DecodeError,
+	),
+	#[cfg(feature = "skins")]
+	#[error("Texture url {0} has no hash")]
+	InvalidTextureUrl(Box<str>),
+	#[cfg(all(feature = "skins", feature = "reqwest"))]
+	#[error("Invalid skin, {0}")]
+	InvalidSkin(Box<str>),
+}
+
+/// Failures of the clients of the crate. Errors of [`FetchError`] convert
+/// into [`Error`] as long as the error of the client converts into this
+#[derive(Debug, Error)]
+pub enum ClientError {
+	#[cfg(feature = "reqwest")]
+	#[error("{0}")]
+	Reqwest(
+		#[from]
+		#[source]
+		reqwest::Error,
+	),
+	#[cfg(feature = "reqwest")]
+	#[error("{0}")]
+	Http(
+		#[from]
+		#[source]
+		crate::http::HttpError,
+	),
+	#[cfg(feature = "ureq")]
+	#[error("{0}")]
+	Ureq(
+		#[from]
+		#[source]
+		ureq::Error,
+	),
+	#[cfg(any(feature = "tokio", feature = "blocking"))]
+	#[error("{0}")]
+	Local(
+		#[from]
+		#[source]
+		crate::local::LocalError,
+	),
+	#[cfg(feature = "tokio")]
+	#[error("{0}")]
+	Offline(
+		#[from]
+		#[source]
+		OfflineError,
+	),
+	#[cfg(any(test, feature = "testing"))]
+	#[error("{0}")]
+	Mock(
+		#[from]
+		#[source]
+		crate::testing::MockError,
+	),
+	#[error("{0} responded with 304 to a request without validators")]
+	UnexpectedNotModified(Url),
+	#[error("Invalid json: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
+
+#[cfg(feature = "tokio")]
+impl<C: Client> From<CacheError<C>> for ClientError
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: CacheError<C>) -> Self {
+		match value {
+			CacheError::Client(err) => err.into(),
+			CacheError::UnexpectedNotModified(url) => Self::UnexpectedNotModified(url),
+			CacheError::Json(err) => err.into(),
+			CacheError::Io(err) => err.into(),
+		}
+	}
 }
 
 impl Error {
-	pub fn from_fetch<C: Client + Debug + 'static>(err: FetchError<C>) -> Self {
-		Self::Fetch(Box::new(err))
+	fn fetch(url: Url, source: impl std::error::Error + Into<ClientError> + 'static) -> Self {
+		match status(&source) {
+			Some(status) => Self::Status { url, status, source: Box::new(source.into()) },
+			None => Self::Fetch { url, source: source.into() },
+		}
+	}
+
+	/// Url of the failed request, if the error is caused by one
+	pub fn url(&self) -> Option<&Url> {
+		match self {
+			Self::Fetch { url, .. } | Self::Status { url, .. } => Some(url),
+			_ => None,
+		}
+	}
+}
+
+/// Finds the http status in the chain of client errors
+pub(crate) fn status(err: &(dyn std::error::Error + 'static)) -> Option<u16> {
+	let mut err = Some(err);
+	while let Some(current) = err {
+		#[cfg(feature = "reqwest")]
+		if let Some(status) = current.downcast_ref::<reqwest::Error>().and_then(reqwest::Error::status) {
+			return Some(status.as_u16());
+		}
+		#[cfg(feature = "reqwest")]
+		if let Some(crate::http::HttpError::Status { status, .. }) = current.downcast_ref() {
+			return Some(*status);
+		}
+		#[cfg(feature = "ureq")]
+		if let Some(ureq::Error::StatusCode(status)) = current.downcast_ref::<ureq::Error>() {
+			return Some(*status);
+		}
+		err = current.source();
+	}
+	None
+}
+
+impl<C: Client> From<FetchError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: FetchError<C>) -> Self {
+		match value {
+			FetchError::Client { url, source } => Self::fetch(url, source),
+			FetchError::ParseUrl(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for Error {
+	fn from(value: reqwest::Error) -> Self {
+		match value.url().cloned() {
+			Some(url) => Self::fetch(url, value),
+			None => Self::Client(value.into()),
+		}
+	}
+}
+
+#[cfg(feature = "blocking")]
+impl<C: crate::blocking::Client> From<crate::blocking::FetchError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::blocking::FetchError<C>) -> Self {
+		match value {
+			crate::blocking::FetchError::Client { url, source } => Self::fetch(url, source),
+			crate::blocking::FetchError::ParseUrl(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl From<DownloadError> for Error {
+	fn from(value: DownloadError) -> Self {
+		match value {
+			DownloadError::Io(err) => err.into(),
+			DownloadError::HashMismatch { path, expected, actual } => {
+				Self::HashMismatch { path: Some(path), expected, actual }
+			}
+		}
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl From<JsonResourceParseError> for Error {
+	fn from(value: JsonResourceParseError) -> Self {
+		match value {
+			JsonResourceParseError::Read(err) => err.into(),
+			JsonResourceParseError::Parse(err) => err.into(),
+			JsonResourceParseError::HashMismatch { expected, actual } => Self::HashMismatch { path: None, expected, actual },
+		}
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl From<JsonResourceSaveError> for Error {
+	fn from(value: JsonResourceSaveError) -> Self {
+		match value {
+			JsonResourceSaveError::CreateFile(err) | JsonResourceSaveError::Copy(err) => err.into(),
+			JsonResourceSaveError::HashMismatch { expected, actual } => Self::HashMismatch { path: None, expected, actual },
+		}
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl From<FsExtSaveError> for Error {
+	fn from(value: FsExtSaveError) -> Self {
+		match value {
+			FsExtSaveError::CreateFile(err) | FsExtSaveError::Copy(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(feature = "tokio")]
+impl From<FsExtLoadError> for Error {
+	fn from(value: FsExtLoadError) -> Self {
+		match value {
+			FsExtLoadError::ReadFile(err) => err.into(),
+			FsExtLoadError::Deserialize(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(all(feature = "vanilla", feature = "tokio"))]
+impl<C: Client> From<crate::vanilla::runtime::InstallRuntimeError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::vanilla::runtime::InstallRuntimeError<C>) -> Self {
+		use crate::vanilla::runtime::InstallRuntimeError;
+
+		match value {
+			InstallRuntimeError::Fetch(err) => err.into(),
+			InstallRuntimeError::Download(err) => err.into(),
+			InstallRuntimeError::Io(err) => err.into(),
+			InstallRuntimeError::UnknownComponent(component) => Self::UnknownComponent((*component).into()),
+			InstallRuntimeError::UnsafePath(path) => Self::UnsafePath(path),
+		}
+	}
+}
+
+#[cfg(all(feature = "vanilla", feature = "tokio"))]
+impl<C: Client> From<crate::vanilla::repair::RepairError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::vanilla::repair::RepairError<C>) -> Self {
+		use crate::vanilla::repair::RepairError;
+
+		match value {
+			RepairError::Fetch(err) => err.into(),
+			RepairError::Download(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(any(feature = "fabric", feature = "quilt"))]
+impl<C: Client> From<crate::loader::InstallLoaderError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::loader::InstallLoaderError<C>) -> Self {
+		use crate::loader::InstallLoaderError;
+
+		match value {
+			InstallLoaderError::Fetch(err) => err.into(),
+			InstallLoaderError::Coordinate(err) => err.into(),
+			InstallLoaderError::Download(err) => err.into(),
+			InstallLoaderError::Save(err) => err.into(),
+			InstallLoaderError::InvalidId(id) => Self::UnsafePath(Path::new(&*id).into()),
+			InstallLoaderError::Io(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(any(feature = "forge", feature = "neoforge"))]
+impl From<crate::installer::OpenInstallerError> for Error {
+	fn from(value: crate::installer::OpenInstallerError) -> Self {
+		use crate::installer::OpenInstallerError;
+
+		match value {
+			OpenInstallerError::Io(err) => err.into(),
+			OpenInstallerError::Json(err) => err.into(),
+			OpenInstallerError::Coordinate(err) => err.into(),
+			OpenInstallerError::Zip(err) => err.into(),
+			err @ (OpenInstallerError::MissingEntry(_) | OpenInstallerError::UnsupportedProfile) => Self::Installer(err),
+		}
+	}
+}
+
+#[cfg(any(feature = "forge", feature = "neoforge"))]
+impl<C: Client> From<crate::installer::InstallerError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::installer::InstallerError<C>) -> Self {
+		use crate::installer::InstallerError;
+
+		match value {
+			InstallerError::Fetch(err) => err.into(),
+			InstallerError::Open(err) => err.into(),
+			InstallerError::Download(err) => err.into(),
+			InstallerError::Coordinate(err) => err.into(),
+			InstallerError::Save(err) => err.into(),
+			InstallerError::Io(err) => err.into(),
+			InstallerError::InvalidChecksum(checksum) => Self::InvalidChecksum(checksum),
+			InstallerError::MissingMainClass(jar) => Self::MissingMainClass(jar),
+			InstallerError::ProcessorFailed { jar, code, stderr } => Self::ProcessorFailed { jar, code, stderr },
+		}
+	}
+}
+
+#[cfg(feature = "modrinth")]
+impl<C: Client> From<crate::modrinth::ModrinthError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::modrinth::ModrinthError<C>) -> Self {
+		use crate::modrinth::ModrinthError;
+
+		match value {
+			ModrinthError::Fetch(err) => err.into(),
+			ModrinthError::Download(err) => err.into(),
+			ModrinthError::Unresolved(project) => Self::Unresolved(project),
+		}
+	}
+}
+
+#[cfg(feature = "modrinth")]
+impl From<crate::modrinth::mrpack::OpenMrpackError> for Error {
+	fn from(value: crate::modrinth::mrpack::OpenMrpackError) -> Self {
+		use crate::modrinth::mrpack::OpenMrpackError;
+
+		match value {
+			OpenMrpackError::Io(err) => err.into(),
+			OpenMrpackError::Json(err) => err.into(),
+			OpenMrpackError::Zip(err) => err.into(),
+			err @ (OpenMrpackError::MissingIndex | OpenMrpackError::Unsupported { .. }) => Self::Mrpack(err),
+		}
+	}
+}
+
+#[cfg(feature = "modrinth")]
+impl<C: Client> From<crate::modrinth::mrpack::MrpackError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::modrinth::mrpack::MrpackError<C>) -> Self {
+		use crate::modrinth::mrpack::MrpackError;
+
+		match value {
+			MrpackError::Fetch(err) => err.into(),
+			MrpackError::Open(err) => err.into(),
+			MrpackError::Download(err) => err.into(),
+			MrpackError::Io(err) => err.into(),
+			MrpackError::Folder(err) => err.into(),
+			MrpackError::MissingMinecraft => Self::MissingMinecraft,
+			MrpackError::UnsafePath(path) => Self::UnsafePath(Path::new(&*path).into()),
+			MrpackError::NoDownloads(file) => Self::NoDownloads(file),
+			MrpackError::Zip(err) => err.into(),
+			MrpackError::Version(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(any(feature = "modrinth", feature = "curseforge"))]
+impl<C: Client> From<crate::modpack::InstallVersionError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::modpack::InstallVersionError<C>) -> Self {
+		use crate::modpack::InstallVersionError;
+
+		match value {
+			InstallVersionError::Fetch(err) => err.into(),
+			InstallVersionError::UnknownVersion(id) => Self::UnknownVersion(id),
+			InstallVersionError::Package(err) => err.into(),
+			InstallVersionError::Folder(err) => err.into(),
+			InstallVersionError::Repair(err) => err.into(),
+			InstallVersionError::Loader(err) => err.into(),
+			InstallVersionError::Installer(err) => err.into(),
+			InstallVersionError::Io(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(feature = "curseforge")]
+impl<C: Client> From<crate::curseforge::CurseForgeError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::curseforge::CurseForgeError<C>) -> Self {
+		use crate::curseforge::CurseForgeError;
+
+		match value {
+			CurseForgeError::Fetch(err) => err.into(),
+			CurseForgeError::Download(err) => err.into(),
+			CurseForgeError::DownloadNotAllowed { project, file } => Self::DownloadNotAllowed { project, file },
+		}
+	}
+}
+
+#[cfg(feature = "curseforge")]
+impl From<crate::curseforge::pack::OpenPackError> for Error {
+	fn from(value: crate::curseforge::pack::OpenPackError) -> Self {
+		use crate::curseforge::pack::OpenPackError;
+
+		match value {
+			OpenPackError::Io(err) => err.into(),
+			OpenPackError::Json(err) => err.into(),
+			OpenPackError::Zip(err) => err.into(),
+			err @ (OpenPackError::MissingManifest | OpenPackError::Unsupported { .. }) => Self::Pack(err),
+		}
+	}
+}
+
+#[cfg(feature = "curseforge")]
+impl<C: Client> From<crate::curseforge::pack::PackError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::curseforge::pack::PackError<C>) -> Self {
+		use crate::curseforge::pack::PackError;
+
+		match value {
+			PackError::Fetch(err) => err.into(),
+			PackError::CurseForge(err) => err.into(),
+			PackError::Open(err) => err.into(),
+			PackError::Download(err) => err.into(),
+			PackError::Io(err) => err.into(),
+			PackError::Folder(err) => err.into(),
+			PackError::UnsafePath(path) => Self::UnsafePath(Path::new(&*path).into()),
+			PackError::Zip(err) => err.into(),
+			PackError::Version(err) => err.into(),
+		}
+	}
+}
+
+#[cfg(feature = "skins")]
+impl<C: Client> From<crate::skins::TexturesError<C>> for Error
+where
+	C::Error: Into<ClientError>,
+{
+	fn from(value: crate::skins::TexturesError<C>) -> Self {
+		use crate::skins::TexturesError;
+
+		match value {
+			TexturesError::Fetch(err) => err.into(),
+			TexturesError::Json(err) => err.into(),
+			TexturesError::Download(err) => err.into(),
+			TexturesError::NoTextures => Self::NoTextures,
+			TexturesError::Decode(err) => err.into(),
+			TexturesError::InvalidUrl(url) => Self::InvalidTextureUrl(url),
+		}
+	}
+}
+
+#[cfg(all(feature = "skins", feature = "reqwest"))]
+impl From<crate::skins::SkinError> for Error {
+	fn from(value: crate::skins::SkinError) -> Self {
+		use crate::skins::SkinError;
+
+		match value {
+			SkinError::Request(err) => err.into(),
+			SkinError::Status { ref url, status, .. } => Self::Status { url: url.clone(), status, source: Box::new(value) },
+			SkinError::Url(err) => err.into(),
+			SkinError::Io(err) => err.into(),
+			SkinError::InvalidSkin(reason) => Self::InvalidSkin(reason),
+		}
 	}
 }
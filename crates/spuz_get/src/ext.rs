@@ -1,7 +1,7 @@
 use std::{io, path::Path};
 
 use async_trait::async_trait;
-use spuz_piston::{AssetIndex, Manifest, RuntimeManifest};
+use spuz_piston::{AssetIndex, InheritedManifest, Manifest, RuntimeManifest};
 use thiserror::Error;
 use tokio::{fs, io::AsyncWriteExt};
 
@@ -84,3 +84,4 @@ macro_rules! save_ext {
 save_ext!(Manifest);
 save_ext!(AssetIndex);
 save_ext!(RuntimeManifest);
+save_ext!(InheritedManifest);
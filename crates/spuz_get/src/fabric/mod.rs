@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use spuz_piston::{shared::Arr, InheritedManifest};
+
+pub use crate::loader::{GameVersion, InstallLoaderError, LoaderMeta, LoaderProfile, LoaderVersion, MavenLibrary};
+use crate::{Client, FetchError};
+
+/// Official fabric meta api
+pub const META: &str = "https://meta.fabricmc.net/v2";
+
+/// Client of the fabric meta api. Use [`Fabric::default`] to talk to the
+/// official one or [`Fabric::new`] to point it anywhere else
+#[derive(Debug, Clone)]
+pub struct Fabric {
+	pub meta: Box<str>,
+}
+
+impl Default for Fabric {
+	fn default() -> Self {
+		Self::new(META)
+	}
+}
+
+impl Fabric {
+	pub fn new(meta: impl Into<Box<str>>) -> Self {
+		Self { meta: meta.into() }
+	}
+
+	fn api(&self) -> LoaderMeta<&str> {
+		LoaderMeta { meta: &self.meta }
+	}
+
+	/// Lists game versions supported by fabric
+	pub async fn game_versions<C: Client>(&self, client: &C) -> Result<Arr<GameVersion>, FetchError<C>> {
+		self.api().game_versions(client).await
+	}
+
+	/// Lists all loader versions
+	pub async fn loader_versions<C: Client>(&self, client: &C) -> Result<Arr<LoaderVersion>, FetchError<C>> {
+		self.api().loader_versions(client).await
+	}
+
+	/// Lists loader versions compatible with the `game` version
+	pub async fn loaders_for<C: Client>(&self, client: &C, game: &str) -> Result<Arr<LoaderVersion>, FetchError<C>> {
+		self.api().loaders_for(client, game).await
+	}
+
+	/// Requests the launch profile for the `game` and `loader` versions pair
+	pub async fn profile<C: Client>(&self, client: &C, game: &str, loader: &str) -> Result<LoaderProfile, FetchError<C>> {
+		self.api().profile(client, game, loader).await
+	}
+
+	/// Installs the loader profile with its libraries. See
+	/// [`install_profile`](crate::loader::install_profile)
+	///
+	/// # Example
+	/// ```no_run
+	/// # use std::{error::Error, path::Path};
+	/// # use pollster::FutureExt;
+	/// use spuz_get::fabric::Fabric;
+	///
+	/// # async move {
+	/// let client = reqwest::Client::new();
+	/// let manifest = Fabric::default()
+	///   .install(&client, "1.20.6", "0.15.11", Path::new("./libraries"), Path::new("./versions"))
+	///   .await?;
+	/// assert_eq!(&*manifest.inherits_from, "1.20.6");
+	/// # Result::<(), Box<dyn Error>>::Ok(())
+	/// # }.block_on();
+	/// ```
+	pub async fn install<C: Client>(
+		&self,
+		client: &C,
+		game: &str,
+		loader: &str,
+		libraries_dir: &Path,
+		versions_dir: &Path,
+	) -> Result<InheritedManifest, InstallLoaderError<C>> {
+		self.api().install(client, game, loader, libraries_dir, versions_dir).await
+	}
+}
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, path::Path};
+
+use spuz_piston::{
+	maven::Coordinate,
+	shared::{Arr, Str},
+	InheritedManifest,
+};
+use url::Url;
+
+pub use crate::installer::{InstallProfile, Installer, InstallerError, OpenInstallerError};
+use crate::{client::fetch_json, installer::download_installer, Client, FetchError};
+
+/// Official forge maven repository
+pub const MAVEN: &str = "https://maven.minecraftforge.net";
+
+/// Client of the forge maven repository. Use [`Forge::default`] to talk to the
+/// official one or [`Forge::new`] to point it anywhere else
+///
+/// # Example
+/// ```no_run
+/// # use std::{error::Error, path::Path};
+/// # use pollster::FutureExt;
+/// use spuz_get::forge::Forge;
+///
+/// # async move {
+/// let client = reqwest::Client::new();
+/// let forge = Forge::default();
+/// let builds = forge.builds_for(&client, "1.20.6").await?;
+/// let build = builds.last().ok_or("no forge builds for 1.20.6")?;
+/// let manifest = forge
+///   .install(&client, build, Path::new("./libraries"), Path::new("./versions"), Path::new("java"))
+///   .await?;
+/// assert_eq!(&*manifest.inherits_from, "1.20.6");
+/// # Result::<(), Box<dyn Error>>::Ok(())
+/// # }.block_on();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Forge {
+	pub maven: Box<str>,
+}
+
+impl Default for Forge {
+	fn default() -> Self {
+		Self::new(MAVEN)
+	}
+}
+
+impl Forge {
+	pub fn new(maven: impl Into<Box<str>>) -> Self {
+		Self { maven: maven.into() }
+	}
+
+	fn url(&self, path: &str) -> Result<Url, url::ParseError> {
+		Url::parse(&format!("{}/{path}", self.maven.trim_end_matches('/')))
+	}
+
+	/// Lists forge builds, such as `1.20.6-50.1.0`, grouped by game version
+	pub async fn versions<C: Client>(&self, client: &C) -> Result<HashMap<Str, Arr<Str>>, FetchError<C>> {
+		fetch_json(client, self.url("net/minecraftforge/forge/maven-metadata.json")?).await
+	}
+
+	/// Lists forge builds for the `game` version, the newest go last
+	pub async fn builds_for<C: Client>(&self, client: &C, game: &str) -> Result<Arr<Str>, FetchError<C>> {
+		let mut versions = self.versions(client).await?;
+		Ok(versions.remove(game).unwrap_or_default())
+	}
+
+	/// Coordinate of the installer jar of the forge `build`
+	pub fn installer(&self, build: &str) -> Coordinate {
+		Coordinate {
+			group: "net.minecraftforge".into(),
+			artifact: "forge".into(),
+			version: build.into(),
+			classifier: Some("installer".into()),
+			extension: "jar".into(),
+		}
+	}
+
+	/// Downloads the installer of the forge `build` into `libraries_dir` and
+	/// installs it. See [`Installer::install`]
+	pub async fn install<C: Client>(
+		&self,
+		client: &C,
+		build: &str,
+		libraries_dir: &Path,
+		versions_dir: &Path,
+		java: &Path,
+	) -> Result<InheritedManifest, InstallerError<C>> {
+		let installer = self.installer(build);
+		let path = libraries_dir.join(installer.path());
+		download_installer(client, self.url(&installer.url_path())?, &path).await?;
+
+		Installer::open(path).await?.install(client, libraries_dir, versions_dir, java).await
+	}
+}
@@ -0,0 +1,264 @@
+//! [`Http`] client with configurable redirects, timeouts and size limits.
+//! A plain [`reqwest::Client`] is a [`Client`] as well, this one is for when
+//! the defaults are not enough
+//!
+//! # Example
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use spuz_get::{http::Http, vanilla};
+//!
+//! # async fn run() -> Result<(), spuz_get::Error> {
+//! let client = Http::builder()
+//!     .redirects(3)
+//!     .connect_timeout(Duration::from_secs(10))
+//!     .max_size(64 * 1024 * 1024)
+//!     .build()?;
+//!
+//! let versions = vanilla::list(&client).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::{
+	header::{HeaderMap, HeaderName, HeaderValue},
+	redirect::Policy,
+	RequestBuilder, Response, StatusCode,
+};
+use serde::de::DeserializeOwned;
+use spuz_piston::shared::Str;
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+	client::{cache_headers, conditional_request, response_body, Body, Conditional, Validators},
+	Client,
+};
+
+const USER_AGENT: &str = concat!("spuz_get/", env!("CARGO_PKG_VERSION"));
+
+/// Redirects followed by default, same as reqwest
+const REDIRECTS: usize = 10;
+
+#[derive(Debug, Clone, Default)]
+pub struct Http {
+	pub client: reqwest::Client,
+	/// Responses larger than this fail with [`HttpError::TooLarge`]
+	pub max_size: Option<u64>,
+	/// Headers sent only with requests under the url, e.g. an api key
+	pub scoped_headers: Vec<(Url, HeaderMap)>,
+}
+
+impl Http {
+	pub fn new(client: reqwest::Client) -> Self {
+		Self { client, max_size: None, scoped_headers: Vec::new() }
+	}
+
+	pub fn builder() -> HttpBuilder {
+		HttpBuilder::default()
+	}
+
+	async fn send(&self, url: Url) -> Result<Response, HttpError> {
+		let response = self.request(&url).send().await?;
+		self.check(url, response)
+	}
+
+	fn request(&self, url: &Url) -> RequestBuilder {
+		let mut request = self.client.get(url.clone());
+		for (base, headers) in &self.scoped_headers {
+			if is_under(url, base) {
+				request = request.headers(headers.clone());
+			}
+		}
+		request
+	}
+
+	fn check(&self, url: Url, response: Response) -> Result<Response, HttpError> {
+		let status = response.status();
+		if !status.is_success() {
+			return Err(HttpError::Status { url, status: status.as_u16() });
+		}
+		if let (Some(size), Some(limit)) = (response.content_length(), self.max_size) {
+			if size > limit {
+				return Err(HttpError::TooLarge { url, limit });
+			}
+		}
+
+		Ok(response)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpBuilder {
+	redirects: usize,
+	timeout: Option<Duration>,
+	connect_timeout: Option<Duration>,
+	user_agent: Str,
+	headers: HeaderMap,
+	scoped_headers: Vec<(Url, HeaderMap)>,
+	max_size: Option<u64>,
+}
+
+impl Default for HttpBuilder {
+	fn default() -> Self {
+		Self {
+			redirects: REDIRECTS,
+			timeout: None,
+			connect_timeout: None,
+			user_agent: USER_AGENT.into(),
+			headers: HeaderMap::new(),
+			scoped_headers: Vec::new(),
+			max_size: None,
+		}
+	}
+}
+
+impl HttpBuilder {
+	/// Maximum number of redirects to follow, `0` does not follow any and
+	/// fails with the redirect status instead
+	#[must_use]
+	pub fn redirects(mut self, redirects: usize) -> Self {
+		self.redirects = redirects;
+		self
+	}
+
+	/// Timeout of the whole request, including reading the body
+	#[must_use]
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	#[must_use]
+	pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+		self.connect_timeout = Some(timeout);
+		self
+	}
+
+	#[must_use]
+	pub fn user_agent(mut self, user_agent: impl Into<Str>) -> Self {
+		self.user_agent = user_agent.into();
+		self
+	}
+
+	/// Sends the header with every request, e.g. an api key
+	#[must_use]
+	pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+		self.headers.insert(name, value);
+		self
+	}
+
+	/// Sends the header only with requests to the `base` url and paths under
+	/// it, so a secret for one api does not reach other hosts
+	#[must_use]
+	pub fn scoped_header(mut self, base: Url, name: HeaderName, value: HeaderValue) -> Self {
+		match self.scoped_headers.iter_mut().find(|(it, _)| *it == base) {
+			Some((_, headers)) => {
+				headers.insert(name, value);
+			}
+			None => self.scoped_headers.push((base, HeaderMap::from_iter([(name, value)]))),
+		}
+		self
+	}
+
+	/// Maximum size of a response body in bytes
+	#[must_use]
+	pub fn max_size(mut self, max_size: u64) -> Self {
+		self.max_size = Some(max_size);
+		self
+	}
+
+	pub fn build(self) -> Result<Http, reqwest::Error> {
+		let redirect = match self.redirects {
+			0 => Policy::none(),
+			redirects => Policy::limited(redirects),
+		};
+		let mut builder =
+			reqwest::Client::builder().redirect(redirect).user_agent(&*self.user_agent).default_headers(self.headers);
+		if let Some(timeout) = self.timeout {
+			builder = builder.timeout(timeout);
+		}
+		if let Some(timeout) = self.connect_timeout {
+			builder = builder.connect_timeout(timeout);
+		}
+
+		Ok(Http { client: builder.build()?, max_size: self.max_size, scoped_headers: self.scoped_headers })
+	}
+}
+
+#[async_trait]
+impl Client for Http {
+	type Error = HttpError;
+
+	async fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		let mut response = self.send(url.clone()).await?;
+
+		let mut content = Vec::new();
+		while let Some(chunk) = response.chunk().await? {
+			content.extend_from_slice(&chunk);
+			if let Some(limit) = self.max_size.filter(|it| content.len() as u64 > *it) {
+				return Err(HttpError::TooLarge { url, limit });
+			}
+		}
+
+		Ok(serde_json::from_slice(&content)?)
+	}
+
+	async fn get_stream(&self, url: Url) -> Result<Body, Self::Error> {
+		Ok(self.body(self.send(url).await?))
+	}
+
+	async fn get_conditional(&self, url: Url, validators: &Validators) -> Result<Conditional, Self::Error> {
+		let response = conditional_request(self.request(&url), validators).send().await?;
+		if response.status() == StatusCode::NOT_MODIFIED {
+			return Ok(Conditional::NotModified(cache_headers(response.headers())));
+		}
+
+		let response = self.check(url, response)?;
+		let headers = cache_headers(response.headers());
+		Ok(Conditional::Modified(self.body(response), headers))
+	}
+}
+
+impl Http {
+	fn body(&self, response: Response) -> Body {
+		let body = response_body(response);
+		match self.max_size {
+			Some(limit) => body.limit(limit),
+			None => body,
+		}
+	}
+}
+
+/// Whether the `url` is the `base` or below it, on the same origin
+fn is_under(url: &Url, base: &Url) -> bool {
+	let path = base.path().trim_end_matches('/');
+	url.origin() == base.origin()
+		&& url.path().strip_prefix(path).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+	#[error("Request failed: {0}")]
+	Request(
+		#[from]
+		#[source]
+		reqwest::Error,
+	),
+	#[error("{url} responded with status {status}")]
+	Status { url: Url, status: u16 },
+	#[error("{url} is larger than the limit of {limit} bytes")]
+	TooLarge { url: Url, limit: u64 },
+	#[error("Invalid json: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+}
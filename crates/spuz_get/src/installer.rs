@@ -0,0 +1,413 @@
+//! Installer of forge-like loaders (forge, neoforge). Their installer jar
+//! carries `install_profile.json` describing libraries and "processors" -
+//! java programs that have to be run to produce patched game jars, and the
+//! `version.json` that launches the result
+
+use std::{
+	collections::HashMap,
+	io::{self, Cursor, Read},
+	path::{Path, PathBuf},
+	process::Stdio,
+};
+
+use cfg_if::cfg_if;
+use futures_lite::AsyncReadExt;
+use serde::{Deserialize, Serialize};
+use spuz_piston::{
+	manifest::Library,
+	maven::{Coordinate, ParseCoordinateError},
+	shared::{Arr, Str},
+	InheritedManifest,
+};
+use thiserror::Error;
+use tokio::{fs, process::Command};
+use tracing::debug;
+use url::Url;
+use zip::{result::ZipError, ZipArchive};
+
+use crate::{
+	client::fetch_stream,
+	download::{self, DownloadError},
+	ext::FsExtSaveError,
+	Client, FetchError, FsExt,
+};
+
+cfg_if! {
+	if #[cfg(target_os = "windows")] {
+		const SEP: &str = ";";
+	} else {
+		const SEP: &str = ":";
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SidedData {
+	pub client: Str,
+	pub server: Str,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Processor {
+	pub sides: Option<Arr<Str>>,
+	pub jar: Str,
+	#[serde(default)]
+	pub classpath: Arr<Str>,
+	#[serde(default)]
+	pub args: Arr<Str>,
+	#[serde(default)]
+	pub outputs: HashMap<Str, Str>,
+}
+
+impl Processor {
+	pub fn is_client(&self) -> bool {
+		self.sides.as_ref().is_none_or(|sides| sides.iter().any(|it| &**it == "client"))
+	}
+}
+
+/// `install_profile.json` of the installer jar
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InstallProfile {
+	pub spec: Option<u32>,
+	pub version: Str,
+	pub minecraft: Str,
+	pub json: Str,
+	#[serde(default)]
+	pub data: HashMap<Str, SidedData>,
+	#[serde(default)]
+	pub processors: Arr<Processor>,
+	#[serde(default)]
+	pub libraries: Arr<Library>,
+}
+
+/// Installer jar loaded into memory
+pub struct Installer {
+	path: PathBuf,
+	archive: ZipArchive<Cursor<Vec<u8>>>,
+	pub profile: InstallProfile,
+	pub version: InheritedManifest,
+}
+
+impl std::fmt::Debug for Installer {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Installer").field("path", &self.path).field("profile", &self.profile).finish_non_exhaustive()
+	}
+}
+
+impl Installer {
+	/// Reads the installer jar and parses `install_profile.json` and the
+	/// version json it refers to
+	pub async fn open(path: impl Into<PathBuf>) -> Result<Self, OpenInstallerError> {
+		let path = path.into();
+		let mut archive = ZipArchive::new(Cursor::new(fs::read(&path).await?))?;
+
+		let profile = read_entry(&mut archive, "install_profile.json")?;
+		let profile: InstallProfile =
+			serde_json::from_slice(&profile).map_err(|_| OpenInstallerError::UnsupportedProfile)?;
+		let version = read_entry(&mut archive, profile.json.trim_start_matches('/'))?;
+		let version = serde_json::from_slice(&version)?;
+
+		Ok(Self { path, archive, profile, version })
+	}
+
+	/// Downloads libraries, runs client processors with the `java` binary and
+	/// saves the version manifest to `versions_dir/<id>/<id>.json`. The vanilla
+	/// version the loader is made for must be already installed into
+	/// `versions_dir`, because processors patch its client jar
+	pub async fn install<C: Client>(
+		mut self,
+		client: &C,
+		libraries_dir: &Path,
+		versions_dir: &Path,
+		java: &Path,
+	) -> Result<InheritedManifest, InstallerError<C>> {
+		let libraries = self.profile.libraries.iter().chain(self.version.libraries.iter()).cloned().collect::<Vec<_>>();
+		for library in &libraries {
+			self.install_library(client, library, libraries_dir).await?;
+		}
+
+		let dir = versions_dir.join(&*self.version.id);
+		let work_dir = dir.join(".installer");
+		fs::create_dir_all(&work_dir).await?;
+
+		let data = self.client_data(libraries_dir, versions_dir, &work_dir).await?;
+		for processor in self.profile.processors.iter().filter(|it| it.is_client()) {
+			run_processor(processor, &data, libraries_dir, java).await?;
+		}
+
+		fs::remove_dir_all(&work_dir).await?;
+		self.version.save(dir.join(format!("{}.json", self.version.id))).await?;
+
+		debug!("Version {} installed into {dir:?}", self.version.id);
+
+		Ok(self.version)
+	}
+
+	async fn install_library<C: Client>(
+		&mut self,
+		client: &C,
+		library: &Library,
+		libraries_dir: &Path,
+	) -> Result<(), InstallerError<C>> {
+		let Some(artifact) = &library.downloads.artifact else {
+			return Ok(());
+		};
+
+		let target = libraries_dir.join(&artifact.path);
+		let sha1 = Some(&*artifact.sha1).filter(|it| !it.is_empty());
+
+		if let Some(sha1) = sha1 {
+			if download::is_valid(&target, sha1).await? {
+				debug!("{target:?} is up to date, skipping");
+				return Ok(());
+			}
+		}
+
+		if artifact.url.is_empty() {
+			// Either bundled into the installer or produced by processors
+			let entry = format!("maven/{}", artifact.path.to_string_lossy().replace('\\', "/"));
+			match read_entry(&mut self.archive, &entry) {
+				Ok(content) => {
+					download::save(futures_lite::io::Cursor::new(content), &target, sha1).await?;
+				}
+				Err(OpenInstallerError::MissingEntry(_)) => {}
+				Err(err) => return Err(err.into()),
+			}
+		} else {
+			let stream = fetch_stream(client, Url::parse(&artifact.url)?).await?;
+			download::save(stream, &target, sha1).await?;
+		}
+
+		Ok(())
+	}
+
+	/// Resolves values of the `data` section for the client side. Files bundled
+	/// into the installer are extracted into `work_dir`
+	async fn client_data(
+		&mut self,
+		libraries_dir: &Path,
+		versions_dir: &Path,
+		work_dir: &Path,
+	) -> Result<HashMap<Str, Str>, OpenInstallerError> {
+		let minecraft = &*self.profile.minecraft;
+		let root = versions_dir.parent().unwrap_or(versions_dir);
+		let client_jar = versions_dir.join(minecraft).join(format!("{minecraft}.jar"));
+
+		let mut data: HashMap<Str, Str> = HashMap::from([
+			("SIDE".into(), "client".into()),
+			("MINECRAFT_VERSION".into(), minecraft.into()),
+			("MINECRAFT_JAR".into(), path_str(&client_jar)),
+			("ROOT".into(), path_str(root)),
+			("INSTALLER".into(), path_str(&self.path)),
+			("LIBRARY_DIR".into(), path_str(libraries_dir)),
+		]);
+
+		let entries: Vec<_> = self.profile.data.iter().map(|(key, value)| (key.clone(), value.client.clone())).collect();
+		for (key, value) in entries {
+			let value = if let Some(literal) = value.strip_prefix('\'').and_then(|it| it.strip_suffix('\'')) {
+				literal.into()
+			} else if let Some(coordinate) = value.strip_prefix('[').and_then(|it| it.strip_suffix(']')) {
+				path_str(&libraries_dir.join(coordinate.parse::<Coordinate>()?.path()))
+			} else if let Some(entry) = value.strip_prefix('/') {
+				let target = work_dir.join(entry);
+				if let Some(parent) = target.parent() {
+					fs::create_dir_all(parent).await?;
+				}
+				fs::write(&target, read_entry(&mut self.archive, entry)?).await?;
+				path_str(&target)
+			} else {
+				value
+			};
+
+			data.insert(key, value);
+		}
+
+		Ok(data)
+	}
+}
+
+fn path_str(path: &Path) -> Str {
+	path.to_string_lossy().into()
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> Result<Vec<u8>, OpenInstallerError> {
+	let mut entry = archive.by_name(name).map_err(|err| match err {
+		ZipError::FileNotFound => OpenInstallerError::MissingEntry(name.into()),
+		err => err.into(),
+	})?;
+	let mut content = Vec::with_capacity(usize::try_from(entry.size()).unwrap_or_default());
+	entry.read_to_end(&mut content)?;
+	Ok(content)
+}
+
+/// Replaces `{KEY}` tokens with data values and `[coordinate]` arguments with
+/// library paths
+fn resolve(arg: &str, data: &HashMap<Str, Str>, libraries_dir: &Path) -> Result<Str, ParseCoordinateError> {
+	if let Some(coordinate) = arg.strip_prefix('[').and_then(|it| it.strip_suffix(']')) {
+		return Ok(path_str(&libraries_dir.join(coordinate.parse::<Coordinate>()?.path())));
+	}
+
+	let mut resolved = arg.to_owned();
+	for (key, value) in data {
+		resolved = resolved.replace(&format!("{{{key}}}"), value);
+	}
+
+	Ok(resolved.into())
+}
+
+fn jar_main_class(jar: &Path) -> Result<Option<String>, OpenInstallerError> {
+	let file = std::fs::File::open(jar)?;
+	let mut archive = ZipArchive::new(file)?;
+	let mut manifest = String::new();
+	archive.by_name("META-INF/MANIFEST.MF")?.read_to_string(&mut manifest)?;
+
+	Ok(manifest.lines().find_map(|line| line.strip_prefix("Main-Class:")).map(|it| it.trim().to_owned()))
+}
+
+async fn run_processor<C: Client>(
+	processor: &Processor,
+	data: &HashMap<Str, Str>,
+	libraries_dir: &Path,
+	java: &Path,
+) -> Result<(), InstallerError<C>> {
+	let mut outputs = Vec::with_capacity(processor.outputs.len());
+	for (path, sha1) in &processor.outputs {
+		let path = resolve(path, data, libraries_dir)?;
+		let sha1 = resolve(sha1, data, libraries_dir)?;
+		outputs.push((PathBuf::from(&*path), sha1.trim_matches('\'').to_owned()));
+	}
+
+	let mut up_to_date = !outputs.is_empty();
+	for (path, sha1) in &outputs {
+		up_to_date = up_to_date && download::is_valid(path, sha1).await?;
+	}
+	if up_to_date {
+		debug!("Outputs of {} are up to date, skipping", processor.jar);
+		return Ok(());
+	}
+
+	let jar = libraries_dir.join(processor.jar.parse::<Coordinate>()?.path());
+	let main_class = jar_main_class(&jar)?.ok_or_else(|| InstallerError::MissingMainClass(processor.jar.clone()))?;
+
+	let mut classpath = vec![path_str(&jar)];
+	for coordinate in &processor.classpath {
+		classpath.push(path_str(&libraries_dir.join(coordinate.parse::<Coordinate>()?.path())));
+	}
+
+	let mut args = Vec::with_capacity(processor.args.len());
+	for arg in &processor.args {
+		args.push(resolve(arg, data, libraries_dir)?.into_string());
+	}
+
+	debug!(?args, "Running processor {}", processor.jar);
+
+	let output = Command::new(java)
+		.arg("-cp")
+		.arg(classpath.join(SEP))
+		.arg(&main_class)
+		.args(&args)
+		.stdin(Stdio::null())
+		.output()
+		.await?;
+
+	if !output.status.success() {
+		return Err(InstallerError::ProcessorFailed {
+			jar: processor.jar.clone(),
+			code: output.status.code(),
+			stderr: String::from_utf8_lossy(&output.stderr).into(),
+		});
+	}
+
+	for (path, expected) in outputs {
+		let actual = match fs::File::open(&path).await {
+			Ok(file) => download::sha1(async_compat::CompatExt::compat(file)).await?,
+			Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+			Err(err) => return Err(err.into()),
+		};
+		if !actual.eq_ignore_ascii_case(&expected) {
+			return Err(
+				DownloadError::HashMismatch { path: path.into(), expected: expected.into(), actual: actual.into() }.into(),
+			);
+		}
+	}
+
+	Ok(())
+}
+
+/// Downloads the installer jar at `url` into `path`, checked against the
+/// `.sha1` the maven publishes next to it. Processors of the installer are run
+/// with the java of the user, so an unverified jar is never opened
+pub(crate) async fn download_installer<C: Client>(client: &C, url: Url, path: &Path) -> Result<(), InstallerError<C>> {
+	let checksum_url = Url::parse(&format!("{url}.sha1"))?;
+	let mut checksum = String::new();
+	fetch_stream(client, checksum_url.clone()).await?.read_to_string(&mut checksum).await?;
+
+	let sha1 = checksum.split_whitespace().next().unwrap_or_default();
+	if sha1.len() != 40 || !sha1.bytes().all(|it| it.is_ascii_hexdigit()) {
+		return Err(InstallerError::InvalidChecksum(checksum_url.as_str().into()));
+	}
+
+	let stream = fetch_stream(client, url).await?;
+	download::save(stream, path, Some(sha1)).await?;
+
+	Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum OpenInstallerError {
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+	#[error("Invalid installer archive: {0}")]
+	Zip(
+		#[from]
+		#[source]
+		ZipError,
+	),
+	#[error("Installer has no `{0}` entry")]
+	MissingEntry(Str),
+	#[error("Unsupported installer profile, legacy installers are not supported")]
+	UnsupportedProfile,
+	#[error("Invalid version json: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error(transparent)]
+	Coordinate(#[from] ParseCoordinateError),
+}
+
+#[derive(Debug, Error)]
+pub enum InstallerError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error(transparent)]
+	Open(#[from] OpenInstallerError),
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+	#[error(transparent)]
+	Coordinate(#[from] ParseCoordinateError),
+	#[error(transparent)]
+	Save(#[from] FsExtSaveError),
+	#[error("{0} is not a sha1 checksum")]
+	InvalidChecksum(Str),
+	#[error("Processor `{0}` has no main class")]
+	MissingMainClass(Str),
+	#[error("Processor `{jar}` failed with code {code:?}: {stderr}")]
+	ProcessorFailed { jar: Str, code: Option<i32>, stderr: Str },
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
+
+impl<C: Client> From<url::ParseError> for InstallerError<C> {
+	fn from(value: url::ParseError) -> Self {
+		Self::Fetch(value.into())
+	}
+}
@@ -1,26 +1,63 @@
 use std::{
 	fmt::{Debug, Formatter},
+	io,
 	marker::PhantomData,
-	path::Path,
+	path::{Path, PathBuf},
+};
+#[cfg(feature = "blocking")]
+use std::{
+	fs::{self, File},
+	io::{BufReader, Read, Write},
 };
 
+#[cfg(feature = "tokio")]
 use async_compat::CompatExt;
-use futures_lite::{io, AsyncRead};
-use futures_util::AsyncReadExt;
+#[cfg(feature = "tokio")]
+use futures_lite::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use serde::de::DeserializeOwned;
+use sha1::{Digest, Sha1};
 use thiserror::Error;
-use tokio::fs::File;
+
+#[cfg(feature = "tokio")]
+use crate::download::{self, DownloadError};
 
 /// You can get `JsonResource` from some api calls. If you just need to get the
 /// structure from the json response, use the [json](JsonResource::json) method.
 /// Sometimes you may need to save the result to a file, then use the
-/// [save](JsonResource::save) method to avoid unnecessary parsing.
+/// [save](JsonResource::save) method to avoid unnecessary parsing, or
+/// [tee](JsonResource::tee) to save and parse in one pass. The content can be
+/// checked against the sha1 of the parent reference with
+/// [verify](JsonResource::verify).
 ///
 /// It is recommended that you perform one of the actions immediately, as
 /// prolonged inactivity may result in a timeout.
 pub struct JsonResource<R, D> {
 	pub(crate) stream: R,
 	pub(crate) json: PhantomData<D>,
+	pub(crate) sha1: Option<Box<str>>,
+	pub(crate) tee: Option<PathBuf>,
+}
+
+impl<R, D> JsonResource<R, D> {
+	/// Wraps the body of a json response
+	pub fn new(stream: R) -> Self {
+		Self { stream, json: PhantomData, sha1: None, tee: None }
+	}
+
+	/// Fails parsing or saving if the sha1 of the content is not `sha1`
+	#[must_use]
+	pub fn verify(mut self, sha1: impl Into<Box<str>>) -> Self {
+		self.sha1 = Some(sha1.into());
+		self
+	}
+
+	/// Writes the content to `path` while [parsing](Self::json). The file only
+	/// appears once the whole content is read and verified
+	#[must_use]
+	pub fn tee(mut self, path: impl Into<PathBuf>) -> Self {
+		self.tee = Some(path.into());
+		self
+	}
 }
 
 impl<R, D> Debug for JsonResource<R, D> {
@@ -29,44 +66,256 @@ impl<R, D> Debug for JsonResource<R, D> {
 	}
 }
 
+#[cfg(feature = "tokio")]
 impl<R, D> JsonResource<R, D>
 where
 	R: AsyncRead + Unpin,
 	D: DeserializeOwned,
 {
-	/// Reads the underlying stream to end and parses as [D]
-	pub async fn json(&mut self) -> Result<D, JsonResourceParseError> {
-		let mut content = String::new();
-		self.stream.read_to_string(&mut content).await?;
-		Ok(serde_json::from_str(&content)?)
+	/// Deserializes [D] incrementally while reading the underlying stream, so
+	/// the body is never held in memory as a whole. The content is hashed and
+	/// written to the [tee](Self::tee) on the way
+	pub async fn json(&mut self) -> Result<D, JsonResourceParseError>
+	where
+		D: Send + 'static,
+	{
+		let part = self.tee.as_deref().map(part_path);
+		let result = self.parse(part.as_deref()).await;
+
+		match (result, self.tee.as_deref(), part) {
+			(Ok(json), Some(tee), Some(part)) => {
+				tokio::fs::rename(part, tee).await?;
+				Ok(json)
+			}
+			(Err(err), _, Some(part)) => {
+				tokio::fs::remove_file(part).await.ok();
+				Err(err)
+			}
+			(result, ..) => result,
+		}
 	}
 
 	/// Copies the underlying stream to the file
 	pub async fn save(&mut self, path: impl AsRef<Path>) -> Result<(), JsonResourceSaveError> {
-		let mut file = File::create(path).await.map_err(JsonResourceSaveError::CreateFile)?;
-		io::copy(&mut self.stream, file.compat_mut()).await.map_err(JsonResourceSaveError::Copy)?;
-		Ok(())
+		match download::save(&mut self.stream, path.as_ref(), self.sha1.as_deref()).await {
+			Ok(_) => Ok(()),
+			Err(DownloadError::Io(err)) => Err(JsonResourceSaveError::Copy(err)),
+			Err(DownloadError::HashMismatch { expected, actual, .. }) => {
+				Err(JsonResourceSaveError::HashMismatch { expected, actual })
+			}
+		}
+	}
+
+	/// Feeds the chunks of the stream to the parser running on a blocking
+	/// thread, the stream itself stays borrowed by this task
+	async fn parse(&mut self, part: Option<&Path>) -> Result<D, JsonResourceParseError>
+	where
+		D: Send + 'static,
+	{
+		let (sender, receiver) = tokio::sync::mpsc::channel(4);
+		let parser = tokio::task::spawn_blocking(move || -> Result<D, JsonResourceParseError> {
+			let mut reader = ChannelReader { receiver, chunk: Vec::new(), pos: 0 };
+			let json = serde_json::from_reader(&mut reader)?;
+			// Let the reading side finish even if the parser is done early
+			io::copy(&mut reader, &mut io::sink())?;
+			Ok(json)
+		});
+
+		let mut file = match part {
+			Some(part) => Some(tokio::fs::File::create(part).await?.compat()),
+			None => None,
+		};
+		let mut hasher = Sha1::new();
+		let mut sender = Some(sender);
+
+		loop {
+			let mut buf = vec![0; 0x4000];
+			let read = self.stream.read(&mut buf).await?;
+			if read == 0 {
+				break;
+			}
+			buf.truncate(read);
+			hasher.update(&buf);
+			if let Some(file) = &mut file {
+				file.write_all(&buf).await?;
+			}
+			// The parser hangs up once it fails, the rest is only hashed
+			if let Some(it) = &sender {
+				if it.send(buf).await.is_err() {
+					sender = None;
+				}
+			}
+		}
+		if let Some(file) = &mut file {
+			file.flush().await?;
+		}
+		drop(sender);
+
+		check(self.sha1.as_deref(), &format!("{:x}", hasher.finalize()))?;
+		parser.await.map_err(io::Error::other)?
+	}
+}
+
+/// Blocking reader over the chunks sent by [`JsonResource::parse`]
+#[cfg(feature = "tokio")]
+struct ChannelReader {
+	receiver: tokio::sync::mpsc::Receiver<Vec<u8>>,
+	chunk: Vec<u8>,
+	pos: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl std::io::Read for ChannelReader {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		while self.pos == self.chunk.len() {
+			match self.receiver.blocking_recv() {
+				Some(chunk) => {
+					self.chunk = chunk;
+					self.pos = 0;
+				}
+				None => return Ok(0),
+			}
+		}
+
+		let read = buf.len().min(self.chunk.len() - self.pos);
+		buf[..read].copy_from_slice(&self.chunk[self.pos..self.pos + read]);
+		self.pos += read;
+		Ok(read)
+	}
+}
+
+/// Parses the json from a blocking reader, hashing it and writing it to the
+/// `.part` of `tee` on the way. The tee appears only if the content is valid
+#[cfg(feature = "blocking")]
+pub(crate) fn read_json<R, D>(stream: R, sha1: Option<&str>, tee: Option<&Path>) -> Result<D, JsonResourceParseError>
+where
+	R: Read,
+	D: DeserializeOwned,
+{
+	let part = tee.map(part_path);
+	let file = part.as_deref().map(File::create).transpose()?;
+	let mut reader = TeeReader { stream, hasher: Sha1::new(), file };
+
+	let result = parse(&mut reader, sha1);
+	drop(reader);
+
+	match (result, tee, part) {
+		(Ok(json), Some(tee), Some(part)) => {
+			fs::rename(part, tee)?;
+			Ok(json)
+		}
+		(Err(err), _, Some(part)) => {
+			fs::remove_file(part).ok();
+			Err(err)
+		}
+		(result, ..) => result,
+	}
+}
+
+/// Copies a blocking reader to `path` through its `.part`, checking the sha1
+/// before the file appears
+#[cfg(feature = "blocking")]
+pub(crate) fn save_blocking<R: Read>(stream: R, path: &Path, sha1: Option<&str>) -> Result<(), JsonResourceSaveError> {
+	let part = part_path(path);
+	let file = File::create(&part).map_err(JsonResourceSaveError::CreateFile)?;
+	let mut reader = TeeReader { stream, hasher: Sha1::new(), file: Some(file) };
+
+	let copied = io::copy(&mut reader, &mut io::sink()).and_then(|_| reader.file.as_mut().map_or(Ok(()), Write::flush));
+	let actual = format!("{:x}", reader.hasher.finalize_reset());
+	drop(reader);
+
+	let result = match (copied, sha1) {
+		(Err(err), _) => Err(JsonResourceSaveError::Copy(err)),
+		(Ok(()), Some(expected)) if !expected.eq_ignore_ascii_case(&actual) => {
+			Err(JsonResourceSaveError::HashMismatch { expected: expected.into(), actual: actual.into() })
+		}
+		(Ok(()), _) => fs::rename(&part, path).map_err(JsonResourceSaveError::Copy),
+	};
+	if result.is_err() {
+		fs::remove_file(part).ok();
+	}
+	result
+}
+
+fn part_path(path: &Path) -> PathBuf {
+	let mut part = path.as_os_str().to_owned();
+	part.push(".part");
+	part.into()
+}
+
+#[cfg(feature = "blocking")]
+fn parse<R, D>(reader: &mut TeeReader<R>, sha1: Option<&str>) -> Result<D, JsonResourceParseError>
+where
+	R: Read,
+	D: DeserializeOwned,
+{
+	let mut buffered = BufReader::with_capacity(0x4000, &mut *reader);
+	let json = serde_json::from_reader(&mut buffered)?;
+	// Trailing bytes are not part of the json, but they are hashed
+	io::copy(&mut buffered, &mut io::sink())?;
+
+	if let Some(file) = &mut reader.file {
+		file.flush()?;
+	}
+
+	check(sha1, &format!("{:x}", reader.hasher.finalize_reset()))?;
+	Ok(json)
+}
+
+/// Compares hex digests regardless of their case
+fn check(expected: Option<&str>, actual: &str) -> Result<(), JsonResourceParseError> {
+	match expected {
+		Some(expected) if !expected.eq_ignore_ascii_case(actual) => {
+			Err(JsonResourceParseError::HashMismatch { expected: expected.into(), actual: actual.into() })
+		}
+		_ => Ok(()),
+	}
+}
+
+/// Reader that hashes everything read and copies it to the file
+#[cfg(feature = "blocking")]
+struct TeeReader<R> {
+	stream: R,
+	hasher: Sha1,
+	file: Option<File>,
+}
+
+#[cfg(feature = "blocking")]
+impl<R: Read> Read for TeeReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let read = self.stream.read(buf)?;
+		self.hasher.update(&buf[..read]);
+		if let Some(file) = &mut self.file {
+			file.write_all(&buf[..read])?;
+		}
+		Ok(read)
 	}
 }
 
 #[derive(Debug, Error)]
-#[error("JsonResource parse error: {0}")]
 pub enum JsonResourceParseError {
+	#[error("JsonResource parse error: {0}")]
 	Read(
 		#[source]
 		#[from]
 		io::Error,
 	),
+	#[error("JsonResource parse error: {0}")]
 	Parse(
 		#[source]
 		#[from]
 		serde_json::Error,
 	),
+	#[error("JsonResource hash mismatch. Expected: {expected}, actual: {actual}")]
+	HashMismatch { expected: Box<str>, actual: Box<str> },
 }
 
 #[derive(Debug, Error)]
-#[error("JsonResource save error: {0}")]
 pub enum JsonResourceSaveError {
+	#[error("JsonResource save error: {0}")]
 	CreateFile(#[source] io::Error),
+	#[error("JsonResource save error: {0}")]
 	Copy(#[source] io::Error),
+	#[error("JsonResource hash mismatch. Expected: {expected}, actual: {actual}")]
+	HashMismatch { expected: Box<str>, actual: Box<str> },
 }
@@ -1,13 +1,54 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "tokio")]
+pub mod cache;
 pub mod client;
+#[cfg(feature = "curseforge")]
+pub mod curseforge;
+#[cfg(feature = "tokio")]
+pub mod download;
 mod err;
+#[cfg(feature = "tokio")]
 pub mod ext;
+#[cfg(feature = "fabric")]
+pub mod fabric;
+#[cfg(feature = "forge")]
+pub mod forge;
+#[cfg(feature = "reqwest")]
+pub mod http;
+#[cfg(any(feature = "forge", feature = "neoforge"))]
+pub mod installer;
+#[cfg(any(feature = "tokio", feature = "blocking"))]
 pub mod json_resource;
+#[cfg(any(feature = "fabric", feature = "quilt"))]
+pub mod loader;
+#[cfg(any(feature = "tokio", feature = "blocking"))]
+pub mod local;
+pub mod mirror;
+#[cfg(any(feature = "modrinth", feature = "curseforge"))]
+pub mod modpack;
+#[cfg(feature = "modrinth")]
+pub mod modrinth;
+#[cfg(feature = "neoforge")]
+pub mod neoforge;
+#[cfg(feature = "tokio")]
+pub mod offline;
+#[cfg(feature = "quilt")]
+pub mod quilt;
+#[cfg(feature = "skins")]
+pub mod skins;
+#[cfg(test)]
+mod test;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 #[cfg(feature = "vanilla")]
 pub mod vanilla;
 
+#[cfg(feature = "tokio")]
+pub use crate::ext::FsExt;
+#[cfg(any(feature = "tokio", feature = "blocking"))]
+pub use crate::json_resource::JsonResource;
 pub use crate::{
-	client::{Client, FetchError},
-	err::Error,
-	ext::FsExt,
-	json_resource::JsonResource,
+	client::{Body, Client, FetchError},
+	err::{ClientError, Error},
 };
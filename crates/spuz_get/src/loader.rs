@@ -0,0 +1,247 @@
+//! Common parts of fabric-like meta apis. Both fabric and quilt serve the same
+//! documents, so they are described once here
+
+use std::{
+	io,
+	path::{Component, Path},
+};
+
+use serde::{Deserialize, Serialize};
+use spuz_piston::{
+	manifest::{Arguments, Artifact, Library, Specifiers},
+	maven::{Coordinate, ParseCoordinateError},
+	shared::{Arr, Size, Str},
+	InheritedManifest,
+};
+use thiserror::Error;
+use tokio::fs;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+	client::{fetch_json, fetch_stream},
+	download::{self, Checksum, DownloadError},
+	ext::FsExtSaveError,
+	Client, FetchError, FsExt,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GameVersion {
+	pub version: Str,
+	#[serde(default)]
+	pub stable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LoaderVersion {
+	pub separator: Str,
+	pub build: u32,
+	pub maven: Str,
+	pub version: Str,
+	#[serde(default)]
+	pub stable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LoaderEntry {
+	loader: LoaderVersion,
+}
+
+/// Library that is only referenced by the maven coordinate and the repository
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MavenLibrary {
+	pub name: Str,
+	pub url: Str,
+	pub sha1: Option<Str>,
+	pub size: Option<Size>,
+}
+
+/// Launch profile of the loader for the specific game version, as served by
+/// the meta api
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderProfile {
+	pub id: Str,
+	pub inherits_from: Str,
+	pub r#type: Option<Str>,
+	pub time: Option<Str>,
+	pub release_time: Option<Str>,
+	pub main_class: Str,
+	pub arguments: Option<Arguments>,
+	#[serde(default)]
+	pub libraries: Arr<MavenLibrary>,
+}
+
+/// Client of a fabric-like meta api located at [`meta`](Self::meta). The
+/// location is either owned or borrowed from [`Fabric`](crate::fabric::Fabric)
+/// and [`Quilt`](crate::quilt::Quilt)
+#[derive(Debug, Clone)]
+pub struct LoaderMeta<M = Box<str>> {
+	pub meta: M,
+}
+
+impl LoaderMeta {
+	pub fn new(meta: impl Into<Box<str>>) -> Self {
+		Self { meta: meta.into() }
+	}
+
+	/// Official fabric meta api
+	#[cfg(feature = "fabric")]
+	pub fn fabric() -> Self {
+		Self::new(crate::fabric::META)
+	}
+
+	/// Official quilt meta api
+	#[cfg(feature = "quilt")]
+	pub fn quilt() -> Self {
+		Self::new(crate::quilt::META)
+	}
+}
+
+impl<M: AsRef<str>> LoaderMeta<M> {
+	/// Url of the endpoint under the meta api, the segments are percent-encoded
+	fn url(&self, segments: &[&str]) -> Result<Url, url::ParseError> {
+		let mut url = Url::parse(self.meta.as_ref())?;
+		url
+			.path_segments_mut()
+			.map_err(|()| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+			.pop_if_empty()
+			.extend(segments);
+		Ok(url)
+	}
+
+	/// Lists game versions supported by the loader
+	pub async fn game_versions<C: Client>(&self, client: &C) -> Result<Arr<GameVersion>, FetchError<C>> {
+		fetch_json(client, self.url(&["versions", "game"])?).await
+	}
+
+	/// Lists all loader versions
+	pub async fn loader_versions<C: Client>(&self, client: &C) -> Result<Arr<LoaderVersion>, FetchError<C>> {
+		fetch_json(client, self.url(&["versions", "loader"])?).await
+	}
+
+	/// Lists loader versions compatible with the `game` version
+	pub async fn loaders_for<C: Client>(&self, client: &C, game: &str) -> Result<Arr<LoaderVersion>, FetchError<C>> {
+		let url = self.url(&["versions", "loader", game])?;
+		let entries: Vec<LoaderEntry> = fetch_json(client, url).await?;
+		Ok(entries.into_iter().map(|it| it.loader).collect())
+	}
+
+	/// Requests the launch profile for the `game` and `loader` versions pair
+	pub async fn profile<C: Client>(&self, client: &C, game: &str, loader: &str) -> Result<LoaderProfile, FetchError<C>> {
+		let url = self.url(&["versions", "loader", game, loader, "profile", "json"])?;
+		fetch_json(client, url).await
+	}
+
+	/// Requests the launch profile and installs it with its libraries. See
+	/// [`install_profile`]
+	pub async fn install<C: Client>(
+		&self,
+		client: &C,
+		game: &str,
+		loader: &str,
+		libraries_dir: &Path,
+		versions_dir: &Path,
+	) -> Result<InheritedManifest, InstallLoaderError<C>> {
+		let profile = self.profile(client, game, loader).await?;
+		install_profile(client, profile, libraries_dir, versions_dir).await
+	}
+}
+
+/// Downloads all libraries of the profile into `libraries_dir` and saves the
+/// resolved version manifest to `versions_dir/<id>/<id>.json`. The saved
+/// manifest inherits from the vanilla version, which has to be installed
+/// separately. The `id` must be a single path component
+pub async fn install_profile<C: Client>(
+	client: &C,
+	profile: LoaderProfile,
+	libraries_dir: &Path,
+	versions_dir: &Path,
+) -> Result<InheritedManifest, InstallLoaderError<C>> {
+	let mut components = Path::new(&*profile.id).components();
+	if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+		return Err(InstallLoaderError::InvalidId(profile.id));
+	}
+
+	let mut libraries = Vec::with_capacity(profile.libraries.len());
+
+	for library in &profile.libraries {
+		libraries.push(install_library(client, library, libraries_dir).await?);
+	}
+
+	let manifest = InheritedManifest {
+		id: profile.id,
+		inherits_from: profile.inherits_from,
+		r#type: profile.r#type,
+		time: profile.time,
+		release_time: profile.release_time,
+		main_class: Some(profile.main_class),
+		libraries: libraries.into(),
+		arguments: profile.arguments,
+	};
+
+	let dir = versions_dir.join(&*manifest.id);
+	fs::create_dir_all(&dir).await?;
+	manifest.save(dir.join(format!("{}.json", manifest.id))).await?;
+
+	debug!("Version {} installed into {dir:?}", manifest.id);
+
+	Ok(manifest)
+}
+
+async fn install_library<C: Client>(
+	client: &C,
+	library: &MavenLibrary,
+	libraries_dir: &Path,
+) -> Result<Library, InstallLoaderError<C>> {
+	let coordinate: Coordinate = library.name.parse()?;
+	let url = format!("{}/{}", library.url.trim_end_matches('/'), coordinate.url_path());
+	let path = coordinate.path();
+	let target = libraries_dir.join(&path);
+
+	let checksum = match &library.sha1 {
+		Some(sha1) if download::is_valid(&target, sha1).await? => {
+			debug!("{target:?} is up to date, skipping");
+			Checksum { sha1: sha1.clone(), size: fs::metadata(&target).await?.len() }
+		}
+		_ => {
+			let stream = fetch_stream(client, Url::parse(&url)?).await?;
+			download::save(stream, &target, library.sha1.as_deref()).await?
+		}
+	};
+
+	let artifact = Artifact { path: path.into(), sha1: checksum.sha1, size: checksum.size, url: url.into() };
+
+	Ok(Library {
+		name: library.name.clone(),
+		downloads: Specifiers { artifact: Some(artifact), classifiers: None },
+		rules: None,
+		extract: None,
+	})
+}
+
+#[derive(Debug, Error)]
+pub enum InstallLoaderError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error(transparent)]
+	Coordinate(#[from] ParseCoordinateError),
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+	#[error(transparent)]
+	Save(#[from] FsExtSaveError),
+	#[error("Version id {0:?} of the profile is not a valid directory name")]
+	InvalidId(Str),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
+
+impl<C: Client> From<url::ParseError> for InstallLoaderError<C> {
+	fn from(value: url::ParseError) -> Self {
+		Self::Fetch(value.into())
+	}
+}
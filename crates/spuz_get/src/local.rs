@@ -0,0 +1,151 @@
+//! Client that reads everything from the local file system, for installs
+//! without internet access. `file://` urls are read as is, other urls are
+//! mapped onto a directory tree by their host, so a mirror directory shipped
+//! on a usb stick or a network share can serve the whole install flow
+
+use std::{borrow::Cow, collections::HashMap, io, path::PathBuf};
+
+#[cfg(feature = "tokio")]
+use async_compat::CompatExt;
+#[cfg(feature = "tokio")]
+use async_trait::async_trait;
+use percent_encoding::percent_decode_str;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use url::Url;
+
+#[cfg(feature = "blocking")]
+use crate::blocking::{self, BoxedRead};
+#[cfg(feature = "tokio")]
+use crate::{client::Body, Client};
+
+/// Maps urls onto local directories. The path of the url is appended to the
+/// root of its host, e.g. with `piston-meta.mojang.com` mapped to `/mnt/meta`,
+/// `https://piston-meta.mojang.com/mc/game/version_manifest_v2.json` is read
+/// from `/mnt/meta/mc/game/version_manifest_v2.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalClient {
+	pub roots: HashMap<Box<str>, PathBuf>,
+	/// Directory with a subdirectory per host, used for hosts that have no
+	/// root of their own
+	pub fallback: Option<PathBuf>,
+}
+
+impl LocalClient {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Serves every host from the `dir/<host>` directory, the layout `wget
+	/// --mirror` produces
+	pub fn mirror_of(dir: impl Into<PathBuf>) -> Self {
+		Self { roots: HashMap::new(), fallback: Some(dir.into()) }
+	}
+
+	/// Serves the `host` from the `root` directory
+	#[must_use]
+	pub fn with(mut self, host: impl Into<Box<str>>, root: impl Into<PathBuf>) -> Self {
+		self.roots.insert(host.into(), root.into());
+		self
+	}
+
+	/// Local path of the `url`, if it is mapped. Segments are percent-decoded,
+	/// urls with segments that would leave the root are not mapped
+	pub fn path(&self, url: &Url) -> Option<PathBuf> {
+		if url.scheme() == "file" {
+			return url.to_file_path().ok();
+		}
+
+		let host = url.host_str()?;
+		let mut path = match self.roots.get(host) {
+			Some(root) => root.clone(),
+			None => self.fallback.as_deref().map(|it| it.join(host))?,
+		};
+		for segment in url.path_segments()?.filter(|it| !it.is_empty()) {
+			path.push(&*decode_segment(segment)?);
+		}
+
+		Some(path)
+	}
+
+	fn resolve(&self, url: Url) -> Result<PathBuf, LocalError> {
+		self.path(&url).ok_or(LocalError::Unmapped(url))
+	}
+}
+
+/// Percent-decoded path segment of a url, `None` if it would leave the
+/// directory it is joined to
+pub(crate) fn decode_segment(segment: &str) -> Option<Cow<'_, str>> {
+	let decoded = percent_decode_str(segment).decode_utf8().ok()?;
+	(!matches!(&*decoded, "." | "..") && !decoded.contains(['/', '\\'])).then_some(decoded)
+}
+
+fn not_found(err: io::Error, url: Url) -> LocalError {
+	if err.kind() == io::ErrorKind::NotFound {
+		LocalError::NotFound(url)
+	} else {
+		err.into()
+	}
+}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl Client for LocalClient {
+	type Error = LocalError;
+
+	async fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		let path = self.resolve(url.clone())?;
+		let content = tokio::fs::read(path).await.map_err(|err| not_found(err, url))?;
+		Ok(serde_json::from_slice(&content)?)
+	}
+
+	async fn get_stream(&self, url: Url) -> Result<Body, Self::Error> {
+		let path = self.resolve(url.clone())?;
+		let file = tokio::fs::File::open(path).await.map_err(|err| not_found(err, url))?;
+		let len = file.metadata().await?.len();
+		Ok(Body::new(file.compat(), Some(len)))
+	}
+}
+
+#[cfg(feature = "blocking")]
+impl blocking::Client for LocalClient {
+	type Error = LocalError;
+
+	fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		let path = self.resolve(url.clone())?;
+		let content = std::fs::read(path).map_err(|err| not_found(err, url))?;
+		Ok(serde_json::from_slice(&content)?)
+	}
+
+	fn get_reader(&self, url: Url) -> Result<BoxedRead, Self::Error> {
+		let path = self.resolve(url.clone())?;
+		let file = std::fs::File::open(path).map_err(|err| not_found(err, url))?;
+		Ok(Box::new(io::BufReader::new(file)))
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum LocalError {
+	#[error("{0} is not mapped to a local directory")]
+	Unmapped(Url),
+	#[error("Not found: {0}")]
+	NotFound(Url),
+	#[error("Invalid json: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
@@ -0,0 +1,184 @@
+//! Mirrors and url rewriting. [`Mirrored`] wraps any [`Client`] and sends
+//! every request to the mirrors first, falling back to the next one on error,
+//! so all apis of the crate (version lists, packages, objects, resources,
+//! libraries and runtime files) are served by them without any changes
+
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::Debug,
+};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+	client::{Body, Conditional, Validators},
+	Client,
+};
+
+/// Url rewriting policy
+pub trait Mirror: Send + Sync {
+	/// Returns the url the request should be sent to instead of `url`, or
+	/// `None` if the mirror does not serve it
+	fn rewrite(&self, url: &Url) -> Option<Url>;
+}
+
+impl<F> Mirror for F
+where
+	F: Fn(&Url) -> Option<Url> + Send + Sync,
+{
+	fn rewrite(&self, url: &Url) -> Option<Url> {
+		self(url)
+	}
+}
+
+/// Mirror that maps hosts to base urls, e.g. `libraries.minecraft.net` to
+/// `https://bmclapi2.bangbang93.com/maven`. The path and the query of the
+/// original url are appended to the base
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostMap {
+	pub hosts: HashMap<Box<str>, Box<str>>,
+}
+
+impl HostMap {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Maps `host` to the `base` url
+	#[must_use]
+	pub fn with(mut self, host: impl Into<Box<str>>, base: impl Into<Box<str>>) -> Self {
+		self.hosts.insert(host.into(), base.into());
+		self
+	}
+
+	/// Host mapping of the [BMCLAPI](https://bmclapidoc.bangbang93.com) mirror
+	pub fn bmclapi() -> Self {
+		Self::mirror_of("https://bmclapi2.bangbang93.com")
+	}
+
+	/// BMCLAPI-style mapping to the mirror located at `base`
+	pub fn mirror_of(base: &str) -> Self {
+		let base = base.trim_end_matches('/');
+		let at = |path: &str| format!("{base}{path}");
+
+		Self::new()
+			.with("launchermeta.mojang.com", base)
+			.with("launcher.mojang.com", base)
+			.with("piston-meta.mojang.com", base)
+			.with("piston-data.mojang.com", base)
+			.with("resources.download.minecraft.net", at("/assets"))
+			.with("libraries.minecraft.net", at("/maven"))
+			.with("maven.minecraftforge.net", at("/maven"))
+			.with("maven.neoforged.net", at("/maven"))
+			.with("maven.fabricmc.net", at("/maven"))
+			.with("meta.fabricmc.net", at("/fabric-meta"))
+	}
+}
+
+impl Mirror for HostMap {
+	fn rewrite(&self, url: &Url) -> Option<Url> {
+		let base = self.hosts.get(url.host_str()?)?;
+		let mut rewritten = Url::parse(&format!("{}{}", base.trim_end_matches('/'), url.path())).ok()?;
+		rewritten.set_query(url.query());
+		Some(rewritten)
+	}
+}
+
+/// Client that tries [`mirrors`](Self::mirrors) in order and then the
+/// original url, unless [`origin`](Self::origin) is disabled. Only the
+/// request itself is retried, errors while reading the stream are not
+pub struct Mirrored<C> {
+	pub client: C,
+	pub mirrors: Vec<Box<dyn Mirror>>,
+	pub origin: bool,
+}
+
+impl<C: Debug> Debug for Mirrored<C> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Mirrored")
+			.field("client", &self.client)
+			.field("mirrors", &self.mirrors.len())
+			.field("origin", &self.origin)
+			.finish()
+	}
+}
+
+impl<C> Mirrored<C> {
+	pub fn new(client: C) -> Self {
+		Self { client, mirrors: Vec::new(), origin: true }
+	}
+
+	/// Adds the mirror after already added ones
+	#[must_use]
+	pub fn with(mut self, mirror: impl Mirror + 'static) -> Self {
+		self.mirrors.push(Box::new(mirror));
+		self
+	}
+
+	/// Disables falling back to the original url, so nothing is requested
+	/// from it unless no mirror serves the url
+	#[must_use]
+	pub fn without_origin(mut self) -> Self {
+		self.origin = false;
+		self
+	}
+
+	/// Urls to request in order. Never empty, as the original url is used when
+	/// no mirror serves it
+	pub fn candidates(&self, url: &Url) -> Vec<Url> {
+		let mut seen = HashSet::new();
+		let mut candidates: Vec<Url> =
+			self.mirrors.iter().filter_map(|it| it.rewrite(url)).filter(|it| seen.insert(it.clone())).collect();
+		if (self.origin || candidates.is_empty()) && seen.insert(url.clone()) {
+			candidates.push(url.clone());
+		}
+		candidates
+	}
+}
+
+#[async_trait]
+impl<C: Client + Send + Sync> Client for Mirrored<C> {
+	type Error = C::Error;
+
+	async fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		let mut candidates = self.candidates(&url);
+		let last = candidates.pop().unwrap_or(url);
+		for candidate in candidates {
+			match self.client.get_json(candidate.clone()).await {
+				Ok(json) => return Ok(json),
+				Err(err) => debug!("{candidate} failed: {err}, trying next mirror"),
+			}
+		}
+		self.client.get_json(last).await
+	}
+
+	async fn get_stream(&self, url: Url) -> Result<Body, Self::Error> {
+		let mut candidates = self.candidates(&url);
+		let last = candidates.pop().unwrap_or(url);
+		for candidate in candidates {
+			match self.client.get_stream(candidate.clone()).await {
+				Ok(stream) => return Ok(stream),
+				Err(err) => debug!("{candidate} failed: {err}, trying next mirror"),
+			}
+		}
+		self.client.get_stream(last).await
+	}
+
+	async fn get_conditional(&self, url: Url, validators: &Validators) -> Result<Conditional, Self::Error> {
+		let mut candidates = self.candidates(&url);
+		let last = candidates.pop().unwrap_or(url);
+		for candidate in candidates {
+			match self.client.get_conditional(candidate.clone(), validators).await {
+				Ok(response) => return Ok(response),
+				Err(err) => debug!("{candidate} failed: {err}, trying next mirror"),
+			}
+		}
+		self.client.get_conditional(last, validators).await
+	}
+}
@@ -0,0 +1,186 @@
+//! Parts shared by modpack formats: the instance a pack installs into,
+//! installing the game and the loader it is made for, and copying the
+//! `overrides` of the archive into the game directory
+
+use std::{
+	collections::HashSet,
+	io::{self, Cursor, Read},
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+pub use spuz_folder::LoaderKind;
+use spuz_folder::{FileKind, Folder, InstanceConfig, Loader};
+use spuz_piston::{rule::RuleCompilance, shared::Str, Manifest};
+use thiserror::Error;
+use tokio::fs;
+use tracing::debug;
+use zip::{result::ZipError, ZipArchive};
+
+use crate::{
+	fabric::Fabric,
+	forge::Forge,
+	installer::InstallerError,
+	json_resource::JsonResourceParseError,
+	loader::InstallLoaderError,
+	neoforge::NeoForge,
+	quilt::Quilt,
+	vanilla::{self, repair::RepairError},
+	Client, FetchError,
+};
+
+/// Config of an instance launching the `minecraft` version with the `loader`
+pub(crate) fn config(minecraft: &str, loader: Option<(LoaderKind, &str)>) -> InstanceConfig {
+	let loader = loader.map(|(kind, version)| Loader { kind, version: version.into() });
+	InstanceConfig { loader, ..InstanceConfig::new(minecraft) }
+}
+
+/// Installs the `minecraft` version with all its files and the `loader` on top
+/// of it into the `folder`. Forge and neoforge installers run their processors
+/// with the `java` binary. Returns the id of the version to launch
+pub(crate) async fn install_version<C: Client>(
+	client: &C,
+	folder: &Folder,
+	minecraft: &str,
+	loader: Option<(LoaderKind, &str)>,
+	java: &Path,
+) -> Result<Str, InstallVersionError<C>> {
+	install_minecraft(client, folder, minecraft).await?;
+
+	let libraries_dir = &folder.libraries.path;
+	let versions_dir = &folder.versions.path;
+	let id = match loader {
+		None => minecraft.into(),
+		Some((LoaderKind::Fabric, loader)) => {
+			Fabric::default().install(client, minecraft, loader, libraries_dir, versions_dir).await?.id
+		}
+		Some((LoaderKind::Quilt, loader)) => {
+			Quilt::default().install(client, minecraft, loader, libraries_dir, versions_dir).await?.id
+		}
+		Some((LoaderKind::Forge, loader)) => {
+			let build = format!("{minecraft}-{loader}");
+			Forge::default().install(client, &build, libraries_dir, versions_dir, java).await?.id
+		}
+		Some((LoaderKind::NeoForge, loader)) => {
+			NeoForge::default().install(client, loader, libraries_dir, versions_dir, java).await?.id
+		}
+	};
+
+	debug!("Version {id} is installed");
+
+	Ok(id)
+}
+
+/// Saves the manifest of the vanilla version and downloads every file it
+/// needs that is not in place yet
+async fn install_minecraft<C: Client>(client: &C, folder: &Folder, id: &str) -> Result<(), InstallVersionError<C>> {
+	let version = folder.versions.get(id);
+
+	let manifest = if let Some(manifest) = version.manifest().await? {
+		manifest
+	} else {
+		let versions = vanilla::list(client).await?;
+		let entry = versions
+			.versions
+			.iter()
+			.find(|it| &*it.id == id)
+			.ok_or_else(|| InstallVersionError::UnknownVersion(id.into()))?;
+
+		fs::create_dir_all(version.path()).await?;
+		let manifest: Manifest =
+			vanilla::package(client, &entry.sha1, id).await?.tee(&**version.manifest_path()).json().await?;
+		Arc::new(manifest)
+	};
+
+	let target = RuleCompilance::new(HashSet::new());
+	let report = folder.verify(&manifest, version.client_path(), &target).await?;
+	vanilla::repair::repair(client, &report).await?;
+
+	// Objects of the asset index are only known once the index is in place
+	if report.damaged().any(|it| it.kind == FileKind::AssetIndex) {
+		let report = folder.verify(&manifest, version.client_path(), &target).await?;
+		vanilla::repair::repair(client, &report).await?;
+	}
+
+	Ok(())
+}
+
+/// Entry of the archive read into memory, directories have no content
+pub(crate) type Entry = (PathBuf, Option<Vec<u8>>);
+
+/// Reads entries under the `prefix` directory of the archive, targeted at
+/// `game_dir`. Entries escaping the archive are reported with `unsafe_path`
+pub(crate) fn read_dir<E>(
+	archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+	prefix: &str,
+	game_dir: &Path,
+	unsafe_path: fn(Str) -> E,
+) -> Result<Vec<Entry>, E>
+where
+	E: From<ZipError> + From<io::Error>,
+{
+	let mut entries = Vec::new();
+
+	for i in 0..archive.len() {
+		let mut entry = archive.by_index(i)?;
+		let Some(name) = entry.enclosed_name() else {
+			return Err(unsafe_path(entry.name().into()));
+		};
+		let Ok(relative) = name.strip_prefix(prefix) else {
+			continue;
+		};
+		let target = game_dir.join(relative);
+
+		if entry.is_dir() {
+			entries.push((target, None));
+			continue;
+		}
+
+		let mut content = Vec::with_capacity(usize::try_from(entry.size()).unwrap_or_default());
+		entry.read_to_end(&mut content)?;
+		entries.push((target, Some(content)));
+	}
+
+	Ok(entries)
+}
+
+/// Writes the entries [read](read_dir) from the archive
+pub(crate) async fn write_entries(entries: Vec<Entry>) -> io::Result<()> {
+	for (target, content) in entries {
+		match content {
+			Some(content) => {
+				if let Some(parent) = target.parent() {
+					fs::create_dir_all(parent).await?;
+				}
+				fs::write(&target, content).await?;
+			}
+			None => fs::create_dir_all(&target).await?,
+		}
+	}
+
+	Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum InstallVersionError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error("Version {0} is not in the version list")]
+	UnknownVersion(Str),
+	#[error(transparent)]
+	Package(#[from] JsonResourceParseError),
+	#[error(transparent)]
+	Folder(#[from] spuz_folder::Error),
+	#[error(transparent)]
+	Repair(#[from] RepairError<C>),
+	#[error(transparent)]
+	Loader(#[from] InstallLoaderError<C>),
+	#[error(transparent)]
+	Installer(#[from] InstallerError<C>),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
@@ -0,0 +1,500 @@
+//! Client of the [modrinth](https://modrinth.com) api: search projects, list
+//! their versions and files, look versions up by file hash and resolve
+//! required dependencies. Modrinth asks clients to send a meaningful
+//! `User-Agent`, which [`Http`](crate::http::Http) does by default
+
+pub mod mrpack;
+
+use std::{
+	collections::{HashSet, VecDeque},
+	fmt::{Display, Formatter},
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use spuz_piston::shared::{Arr, Str};
+use thiserror::Error;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+	client::{fetch_json, fetch_stream},
+	download::{self, Checksum, DownloadError},
+	Client, FetchError,
+};
+
+/// Official modrinth api
+pub const API: &str = "https://api.modrinth.com/v2";
+
+/// Project property to filter the [search](Modrinth::search) by
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Facet {
+	GameVersion(Str),
+	/// Loaders are categories on modrinth, but they are kept apart for clarity
+	Loader(Str),
+	Category(Str),
+	/// `mod`, `modpack`, `resourcepack`, `shader`, ...
+	ProjectType(Str),
+	/// Any other facet in the `type:value` form
+	Custom(Str),
+}
+
+impl Display for Facet {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::GameVersion(version) => write!(f, "versions:{version}"),
+			Self::Loader(category) | Self::Category(category) => write!(f, "categories:{category}"),
+			Self::ProjectType(project_type) => write!(f, "project_type:{project_type}"),
+			Self::Custom(facet) => f.write_str(facet),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortIndex {
+	Relevance,
+	Downloads,
+	Follows,
+	Newest,
+	Updated,
+}
+
+impl SortIndex {
+	fn as_str(self) -> &'static str {
+		match self {
+			Self::Relevance => "relevance",
+			Self::Downloads => "downloads",
+			Self::Follows => "follows",
+			Self::Newest => "newest",
+			Self::Updated => "updated",
+		}
+	}
+}
+
+/// Search parameters. Facets are combined as an `AND` of `OR` groups
+///
+/// # Example
+/// ```
+/// use spuz_get::modrinth::{Facet, SearchQuery};
+///
+/// let query = SearchQuery::new("sodium")
+///   .facet(Facet::GameVersion("1.20.6".into()))
+///   .any_of([Facet::Loader("fabric".into()), Facet::Loader("quilt".into())])
+///   .limit(5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+	pub query: Option<Str>,
+	pub facets: Vec<Vec<Facet>>,
+	pub index: Option<SortIndex>,
+	pub offset: Option<u32>,
+	pub limit: Option<u32>,
+}
+
+impl SearchQuery {
+	pub fn new(query: impl Into<Str>) -> Self {
+		Self { query: Some(query.into()), ..Self::default() }
+	}
+
+	/// Requires the `facet`
+	#[must_use]
+	pub fn facet(self, facet: Facet) -> Self {
+		self.any_of([facet])
+	}
+
+	/// Requires at least one of the `facets`
+	#[must_use]
+	pub fn any_of(mut self, facets: impl IntoIterator<Item = Facet>) -> Self {
+		self.facets.push(facets.into_iter().collect());
+		self
+	}
+
+	#[must_use]
+	pub fn index(mut self, index: SortIndex) -> Self {
+		self.index = Some(index);
+		self
+	}
+
+	#[must_use]
+	pub fn offset(mut self, offset: u32) -> Self {
+		self.offset = Some(offset);
+		self
+	}
+
+	#[must_use]
+	pub fn limit(mut self, limit: u32) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	fn apply(&self, url: &mut Url) {
+		let mut pairs = url.query_pairs_mut();
+		if let Some(query) = &self.query {
+			pairs.append_pair("query", query);
+		}
+		if !self.facets.is_empty() {
+			let facets: Vec<Vec<String>> =
+				self.facets.iter().map(|group| group.iter().map(ToString::to_string).collect()).collect();
+			pairs.append_pair("facets", &json_array(&facets));
+		}
+		if let Some(index) = self.index {
+			pairs.append_pair("index", index.as_str());
+		}
+		if let Some(offset) = self.offset {
+			pairs.append_pair("offset", &offset.to_string());
+		}
+		if let Some(limit) = self.limit {
+			pairs.append_pair("limit", &limit.to_string());
+		}
+	}
+}
+
+/// Filters of the [project versions](Modrinth::versions)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionQuery {
+	pub loaders: Vec<Str>,
+	pub game_versions: Vec<Str>,
+	pub featured: Option<bool>,
+}
+
+impl VersionQuery {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	#[must_use]
+	pub fn loader(mut self, loader: impl Into<Str>) -> Self {
+		self.loaders.push(loader.into());
+		self
+	}
+
+	#[must_use]
+	pub fn game_version(mut self, version: impl Into<Str>) -> Self {
+		self.game_versions.push(version.into());
+		self
+	}
+
+	#[must_use]
+	pub fn featured(mut self, featured: bool) -> Self {
+		self.featured = Some(featured);
+		self
+	}
+
+	fn apply(&self, url: &mut Url) {
+		let mut pairs = url.query_pairs_mut();
+		if !self.loaders.is_empty() {
+			pairs.append_pair("loaders", &json_array(&self.loaders));
+		}
+		if !self.game_versions.is_empty() {
+			pairs.append_pair("game_versions", &json_array(&self.game_versions));
+		}
+		if let Some(featured) = self.featured {
+			pairs.append_pair("featured", &featured.to_string());
+		}
+	}
+}
+
+fn json_array(value: &impl Serialize) -> String {
+	serde_json::to_string(value).unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchResults {
+	pub hits: Arr<SearchHit>,
+	pub offset: u32,
+	pub limit: u32,
+	pub total_hits: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+	pub project_id: Str,
+	pub slug: Option<Str>,
+	pub project_type: Str,
+	pub title: Str,
+	pub description: Str,
+	#[serde(default)]
+	pub categories: Arr<Str>,
+	#[serde(default)]
+	pub versions: Arr<Str>,
+	pub latest_version: Option<Str>,
+	pub author: Option<Str>,
+	pub downloads: Option<u64>,
+	pub icon_url: Option<Str>,
+	pub client_side: Option<Str>,
+	pub server_side: Option<Str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Project {
+	pub id: Str,
+	pub slug: Option<Str>,
+	pub project_type: Str,
+	pub title: Str,
+	pub description: Str,
+	#[serde(default)]
+	pub categories: Arr<Str>,
+	#[serde(default)]
+	pub game_versions: Arr<Str>,
+	#[serde(default)]
+	pub loaders: Arr<Str>,
+	/// Ids of the project versions
+	#[serde(default)]
+	pub versions: Arr<Str>,
+	pub icon_url: Option<Str>,
+	pub client_side: Option<Str>,
+	pub server_side: Option<Str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Version {
+	pub id: Str,
+	pub project_id: Str,
+	pub name: Str,
+	pub version_number: Str,
+	pub version_type: Option<Str>,
+	#[serde(default)]
+	pub game_versions: Arr<Str>,
+	#[serde(default)]
+	pub loaders: Arr<Str>,
+	#[serde(default)]
+	pub featured: bool,
+	pub date_published: Option<Str>,
+	#[serde(default)]
+	pub dependencies: Arr<Dependency>,
+	pub files: Arr<VersionFile>,
+}
+
+impl Version {
+	/// The file marked as primary, or the first one if none is
+	pub fn primary_file(&self) -> Option<&VersionFile> {
+		self.files.iter().find(|it| it.primary).or_else(|| self.files.first())
+	}
+
+	/// Dependencies that have to be installed along with the version
+	pub fn required(&self) -> impl Iterator<Item = &Dependency> {
+		self.dependencies.iter().filter(|it| it.dependency_type == DependencyType::Required)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionFile {
+	pub url: Str,
+	pub filename: Str,
+	#[serde(default)]
+	pub primary: bool,
+	pub size: u64,
+	pub hashes: Hashes,
+	pub file_type: Option<Str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hashes {
+	pub sha1: Str,
+	pub sha512: Option<Str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+	pub version_id: Option<Str>,
+	pub project_id: Option<Str>,
+	pub file_name: Option<Str>,
+	pub dependency_type: DependencyType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyType {
+	Required,
+	Optional,
+	Incompatible,
+	Embedded,
+}
+
+/// Algorithm of the hash to [look versions up](Modrinth::version_from_hash) by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HashAlgorithm {
+	#[default]
+	Sha1,
+	Sha512,
+}
+
+impl HashAlgorithm {
+	fn as_str(self) -> &'static str {
+		match self {
+			Self::Sha1 => "sha1",
+			Self::Sha512 => "sha512",
+		}
+	}
+}
+
+/// Client of the modrinth api located at [`api`](Self::api). Use
+/// [`Modrinth::default`] to talk to the official one or [`Modrinth::new`] to
+/// point it anywhere else
+///
+/// # Example
+/// ```no_run
+/// # use std::error::Error;
+/// # use pollster::FutureExt;
+/// use spuz_get::modrinth::{Facet, Modrinth, SearchQuery, VersionQuery};
+///
+/// # async move {
+/// let client = spuz_get::http::Http::builder().build()?;
+/// let modrinth = Modrinth::default();
+///
+/// let query = SearchQuery::new("sodium").facet(Facet::Loader("fabric".into()));
+/// let hit = &modrinth.search(&client, &query).await?.hits[0];
+/// let filter = VersionQuery::new().loader("fabric").game_version("1.20.6");
+/// let versions = modrinth.versions(&client, &hit.project_id, &filter).await?;
+/// # Result::<(), Box<dyn Error>>::Ok(())
+/// # }.block_on();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Modrinth {
+	pub api: Box<str>,
+}
+
+impl Default for Modrinth {
+	fn default() -> Self {
+		Self::new(API)
+	}
+}
+
+impl Modrinth {
+	pub fn new(api: impl Into<Box<str>>) -> Self {
+		Self { api: api.into() }
+	}
+
+	/// Url of the endpoint under the api, the segments are percent-encoded
+	fn url(&self, segments: &[&str]) -> Result<Url, url::ParseError> {
+		let mut url = Url::parse(&self.api)?;
+		url
+			.path_segments_mut()
+			.map_err(|()| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+			.pop_if_empty()
+			.extend(segments);
+		Ok(url)
+	}
+
+	/// Searches projects matching the `query`
+	pub async fn search<C: Client>(&self, client: &C, query: &SearchQuery) -> Result<SearchResults, FetchError<C>> {
+		let mut url = self.url(&["search"])?;
+		query.apply(&mut url);
+		fetch_json(client, url).await
+	}
+
+	/// Requests the project by its id or slug
+	pub async fn project<C: Client>(&self, client: &C, id: &str) -> Result<Project, FetchError<C>> {
+		fetch_json(client, self.url(&["project", id])?).await
+	}
+
+	/// Lists versions of the project matching the `query`, newest first
+	pub async fn versions<C: Client>(
+		&self,
+		client: &C,
+		project: &str,
+		query: &VersionQuery,
+	) -> Result<Arr<Version>, FetchError<C>> {
+		let mut url = self.url(&["project", project, "version"])?;
+		query.apply(&mut url);
+		fetch_json(client, url).await
+	}
+
+	/// Requests the version by its id
+	pub async fn version<C: Client>(&self, client: &C, id: &str) -> Result<Version, FetchError<C>> {
+		fetch_json(client, self.url(&["version", id])?).await
+	}
+
+	/// Requests multiple versions by their ids in one request
+	pub async fn versions_by_id<C: Client>(&self, client: &C, ids: &[&str]) -> Result<Arr<Version>, FetchError<C>> {
+		let mut url = self.url(&["versions"])?;
+		url.query_pairs_mut().append_pair("ids", &json_array(&ids));
+		fetch_json(client, url).await
+	}
+
+	/// Finds the version that contains a file with the `hash`
+	pub async fn version_from_hash<C: Client>(
+		&self,
+		client: &C,
+		hash: &str,
+		algorithm: HashAlgorithm,
+	) -> Result<Version, FetchError<C>> {
+		let mut url = self.url(&["version_file", hash])?;
+		url.query_pairs_mut().append_pair("algorithm", algorithm.as_str());
+		fetch_json(client, url).await
+	}
+
+	/// Resolves required dependencies of the `version` recursively. Pinned
+	/// dependencies are taken as is, for the others the newest version
+	/// matching the `query` is picked. Each project appears once and the
+	/// `version` itself is not included
+	pub async fn resolve_dependencies<C: Client>(
+		&self,
+		client: &C,
+		version: &Version,
+		query: &VersionQuery,
+	) -> Result<Arr<Version>, ModrinthError<C>> {
+		let mut seen = HashSet::from([version.project_id.clone()]);
+		let mut queue: VecDeque<Dependency> = version.required().cloned().collect();
+		let mut resolved = Vec::new();
+
+		while let Some(dependency) = queue.pop_front() {
+			if dependency.project_id.as_ref().is_some_and(|it| seen.contains(it)) {
+				continue;
+			}
+
+			let version = match (&dependency.version_id, &dependency.project_id) {
+				(Some(id), _) => self.version(client, id).await?,
+				(None, Some(project)) => {
+					let versions = self.versions(client, project, query).await?;
+					versions.into_vec().into_iter().next().ok_or_else(|| ModrinthError::Unresolved(project.clone()))?
+				}
+				(None, None) => {
+					debug!("Skipping dependency without project: {:?}", dependency.file_name);
+					continue;
+				}
+			};
+
+			if !seen.insert(version.project_id.clone()) {
+				continue;
+			}
+			debug!("Resolved dependency {} {}", version.project_id, version.version_number);
+			queue.extend(version.required().cloned());
+			resolved.push(version);
+		}
+
+		Ok(resolved.into())
+	}
+
+	/// Downloads the file into `dir`, verifying its sha1. Returns the path of
+	/// the file
+	pub async fn download<C: Client>(
+		&self,
+		client: &C,
+		file: &VersionFile,
+		dir: &Path,
+	) -> Result<(PathBuf, Checksum), ModrinthError<C>> {
+		let path = dir.join(Path::new(&*file.filename).file_name().unwrap_or_default());
+		let stream = fetch_stream(client, Url::parse(&file.url)?).await?;
+		let checksum = download::save(stream, &path, Some(&file.hashes.sha1)).await?;
+
+		Ok((path, checksum))
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum ModrinthError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error("No compatible version of {0}")]
+	Unresolved(Str),
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+}
+
+impl<C: Client> From<url::ParseError> for ModrinthError<C> {
+	fn from(value: url::ParseError) -> Self {
+		Self::Fetch(value.into())
+	}
+}
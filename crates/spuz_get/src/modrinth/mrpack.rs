@@ -0,0 +1,360 @@
+//! Installer of modrinth modpacks. A `.mrpack` is a zip archive with the
+//! `modrinth.index.json` listing files to download, the game and loader
+//! versions the pack is made for, and `overrides` directories copied into the
+//! game directory as is
+//!
+//! # Example
+//! ```no_run
+//! # use std::error::Error;
+//! # use pollster::FutureExt;
+//! use spuz_folder::Folder;
+//! use spuz_get::modrinth::mrpack::{Mrpack, Side};
+//!
+//! # async move {
+//! let client = reqwest::Client::new();
+//! let folder = Folder::settle("./spuz").await?;
+//! let pack = Mrpack::open("./pack.mrpack").await?;
+//! let instance = pack.side(Side::Client).install(&client, &folder, "pack").await?;
+//! println!("{} launches {}", instance.name, instance.config.version_id());
+//! # Result::<(), Box<dyn Error>>::Ok(())
+//! # }.block_on();
+//! ```
+
+use std::{
+	collections::HashMap,
+	io::{self, Cursor, Read},
+	path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use spuz_folder::{Folder, Instance};
+use spuz_piston::shared::{Arr, Str};
+use thiserror::Error;
+use tokio::fs;
+use tracing::debug;
+use url::Url;
+use zip::{result::ZipError, ZipArchive};
+
+pub use crate::modpack::{InstallVersionError, LoaderKind};
+use crate::{
+	client::fetch_stream,
+	download::{self, relative_path, DownloadError},
+	modpack, Client, FetchError,
+};
+
+const INDEX: &str = "modrinth.index.json";
+const OVERRIDES: &str = "overrides";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackIndex {
+	pub format_version: u32,
+	pub game: Str,
+	pub version_id: Str,
+	pub name: Str,
+	pub summary: Option<Str>,
+	#[serde(default)]
+	pub files: Arr<MrpackFile>,
+	/// `minecraft` and the loader, keyed by `fabric-loader`, `quilt-loader`,
+	/// `forge` or `neoforge`
+	pub dependencies: HashMap<Str, Str>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackFile {
+	/// Destination relative to the game directory
+	pub path: Str,
+	pub hashes: MrpackHashes,
+	pub env: Option<Env>,
+	/// Mirrors of the file, tried in order
+	pub downloads: Arr<Str>,
+	pub file_size: u64,
+}
+
+impl MrpackFile {
+	/// Whether the file is installed on the `side`, optional files only if
+	/// `optional` is set
+	pub fn is_for(&self, side: Side, optional: bool) -> bool {
+		let requirement = match (&self.env, side) {
+			(None, _) => Requirement::Required,
+			(Some(env), Side::Client) => env.client,
+			(Some(env), Side::Server) => env.server,
+		};
+		match requirement {
+			Requirement::Required => true,
+			Requirement::Optional => optional,
+			Requirement::Unsupported => false,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MrpackHashes {
+	pub sha1: Str,
+	pub sha512: Str,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Env {
+	pub client: Requirement,
+	pub server: Requirement,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Requirement {
+	Required,
+	Optional,
+	Unsupported,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Side {
+	#[default]
+	Client,
+	Server,
+}
+
+impl Side {
+	fn overrides(self) -> &'static str {
+		match self {
+			Self::Client => "client-overrides",
+			Self::Server => "server-overrides",
+		}
+	}
+}
+
+/// Key of the loader in the index dependencies
+fn dependency(loader: LoaderKind) -> &'static str {
+	match loader {
+		LoaderKind::Fabric => "fabric-loader",
+		LoaderKind::Quilt => "quilt-loader",
+		LoaderKind::Forge => "forge",
+		LoaderKind::NeoForge => "neoforge",
+	}
+}
+
+/// Opened `.mrpack` archive
+pub struct Mrpack {
+	pub index: MrpackIndex,
+	archive: ZipArchive<Cursor<Vec<u8>>>,
+	side: Side,
+	optional: bool,
+	java: PathBuf,
+}
+
+impl std::fmt::Debug for Mrpack {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Mrpack").field("index", &self.index).field("side", &self.side).finish_non_exhaustive()
+	}
+}
+
+impl Mrpack {
+	/// Reads the archive and parses its index
+	pub async fn open(path: impl AsRef<Path>) -> Result<Self, OpenMrpackError> {
+		Self::from_bytes(fs::read(path).await?)
+	}
+
+	pub fn from_bytes(content: Vec<u8>) -> Result<Self, OpenMrpackError> {
+		let mut archive = ZipArchive::new(Cursor::new(content))?;
+
+		let mut entry = archive.by_name(INDEX).map_err(|err| match err {
+			ZipError::FileNotFound => OpenMrpackError::MissingIndex,
+			err => err.into(),
+		})?;
+		let mut content = Vec::with_capacity(usize::try_from(entry.size()).unwrap_or_default());
+		entry.read_to_end(&mut content)?;
+		drop(entry);
+
+		let index: MrpackIndex = serde_json::from_slice(&content)?;
+		if index.format_version != 1 || &*index.game != "minecraft" {
+			return Err(OpenMrpackError::Unsupported { format_version: index.format_version, game: index.game });
+		}
+
+		Ok(Self { index, archive, side: Side::Client, optional: true, java: "java".into() })
+	}
+
+	/// Side to install the pack for, client by default
+	#[must_use]
+	pub fn side(mut self, side: Side) -> Self {
+		self.side = side;
+		self
+	}
+
+	/// Whether to install optional files, enabled by default
+	#[must_use]
+	pub fn optional(mut self, optional: bool) -> Self {
+		self.optional = optional;
+		self
+	}
+
+	/// Java binary running the processors of forge and neoforge installers,
+	/// `java` from the `PATH` by default
+	#[must_use]
+	pub fn java(mut self, java: impl Into<PathBuf>) -> Self {
+		self.java = java.into();
+		self
+	}
+
+	/// Version of minecraft the pack is made for
+	pub fn minecraft(&self) -> Option<&str> {
+		self.index.dependencies.get("minecraft").map(|it| &**it)
+	}
+
+	/// Loader the pack is made for and its version
+	pub fn loader(&self) -> Option<(LoaderKind, &str)> {
+		[LoaderKind::Fabric, LoaderKind::Quilt, LoaderKind::Forge, LoaderKind::NeoForge]
+			.into_iter()
+			.find_map(|loader| self.index.dependencies.get(dependency(loader)).map(|it| (loader, &**it)))
+	}
+
+	/// Files installed on the configured side
+	pub fn files(&self) -> impl Iterator<Item = &MrpackFile> {
+		self.index.files.iter().filter(|it| it.is_for(self.side, self.optional))
+	}
+
+	/// Creates the instance `name` in the `folder`, downloads the files of the
+	/// configured side into its game directory, copies the overrides over them
+	/// and installs the game and the loader. The instance is removed again if
+	/// any of it fails
+	pub async fn install<C: Client>(
+		mut self,
+		client: &C,
+		folder: &Folder,
+		name: &str,
+	) -> Result<Instance, MrpackError<C>> {
+		let minecraft: Str = self.minecraft().ok_or(MrpackError::MissingMinecraft)?.into();
+		let loader = self.loader().map(|(loader, version)| (loader, Str::from(version)));
+		let loader = loader.as_ref().map(|(loader, version)| (*loader, &**version));
+
+		let instance = folder.instances.create(name, modpack::config(&minecraft, loader)).await?;
+		if let Err(err) = self.install_into(client, folder, &instance.game_dir(), &minecraft, loader).await {
+			folder.instances.delete(name).await.ok();
+			return Err(err);
+		}
+
+		debug!("Modpack {} installed as {name}", self.index.name);
+		Ok(instance)
+	}
+
+	async fn install_into<C: Client>(
+		&mut self,
+		client: &C,
+		folder: &Folder,
+		game_dir: &Path,
+		minecraft: &str,
+		loader: Option<(LoaderKind, &str)>,
+	) -> Result<(), MrpackError<C>> {
+		for file in self.files() {
+			install_file(client, file, game_dir).await?;
+		}
+
+		self.extract(OVERRIDES, game_dir).await?;
+		self.extract(self.side.overrides(), game_dir).await?;
+
+		modpack::install_version(client, folder, minecraft, loader, &self.java).await?;
+		Ok(())
+	}
+
+	/// Copies entries under the `prefix` directory of the archive into
+	/// `game_dir`
+	async fn extract<C: Client>(&mut self, prefix: &str, game_dir: &Path) -> Result<(), MrpackError<C>> {
+		let entries = modpack::read_dir(&mut self.archive, prefix, game_dir, MrpackError::UnsafePath)?;
+		Ok(modpack::write_entries(entries).await?)
+	}
+}
+
+async fn install_file<C: Client>(client: &C, file: &MrpackFile, game_dir: &Path) -> Result<(), MrpackError<C>> {
+	let relative = relative_path(&*file.path).ok_or_else(|| MrpackError::UnsafePath(file.path.clone()))?;
+	let target = game_dir.join(relative);
+
+	if download::is_valid(&target, &file.hashes.sha1).await? {
+		debug!("{target:?} is up to date, skipping");
+		return Ok(());
+	}
+
+	let mut last = None;
+	for url in &file.downloads {
+		let result = match Url::parse(url) {
+			Ok(url) => match fetch_stream(client, url).await {
+				Ok(stream) => download::save(stream, &target, Some(&file.hashes.sha1)).await.map_err(Into::into),
+				Err(err) => Err(err.into()),
+			},
+			Err(err) => Err(err.into()),
+		};
+		match result {
+			Ok(_) => return Ok(()),
+			Err(err) => {
+				debug!("{url} failed: {err}, trying next download");
+				last = Some(err);
+			}
+		}
+	}
+
+	Err(last.unwrap_or_else(|| MrpackError::NoDownloads(file.path.clone())))
+}
+
+#[derive(Debug, Error)]
+pub enum OpenMrpackError {
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+	#[error("Invalid modpack archive: {0}")]
+	Zip(
+		#[from]
+		#[source]
+		ZipError,
+	),
+	#[error("Modpack has no `{INDEX}`")]
+	MissingIndex,
+	#[error("Invalid modpack index: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error("Unsupported modpack format {format_version} for {game}")]
+	Unsupported { format_version: u32, game: Str },
+}
+
+#[derive(Debug, Error)]
+pub enum MrpackError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error(transparent)]
+	Open(#[from] OpenMrpackError),
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+	#[error(transparent)]
+	Version(#[from] InstallVersionError<C>),
+	#[error(transparent)]
+	Folder(#[from] spuz_folder::Error),
+	#[error("Modpack does not depend on minecraft")]
+	MissingMinecraft,
+	#[error("Path `{0}` leaves the game directory")]
+	UnsafePath(Str),
+	#[error("File `{0}` has no downloads")]
+	NoDownloads(Str),
+	#[error("Invalid modpack archive: {0}")]
+	Zip(
+		#[from]
+		#[source]
+		ZipError,
+	),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
+
+impl<C: Client> From<url::ParseError> for MrpackError<C> {
+	fn from(value: url::ParseError) -> Self {
+		Self::Fetch(value.into())
+	}
+}
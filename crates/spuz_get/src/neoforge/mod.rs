@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use spuz_piston::{
+	maven::Coordinate,
+	shared::{Arr, Str},
+	InheritedManifest,
+};
+use url::Url;
+
+pub use crate::installer::{InstallProfile, Installer, InstallerError, OpenInstallerError};
+use crate::{client::fetch_json, installer::download_installer, Client, FetchError};
+
+/// Official neoforge maven repository
+pub const MAVEN: &str = "https://maven.neoforged.net";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MavenVersions {
+	versions: Arr<Str>,
+}
+
+/// Client of the neoforge maven repository. Use [`NeoForge::default`] to talk
+/// to the official one or [`NeoForge::new`] to point it anywhere else
+///
+/// # Example
+/// ```no_run
+/// # use std::{error::Error, path::Path};
+/// # use pollster::FutureExt;
+/// use spuz_get::neoforge::NeoForge;
+///
+/// # async move {
+/// let client = reqwest::Client::new();
+/// let neoforge = NeoForge::default();
+/// let builds = neoforge.builds_for(&client, "1.20.6").await?;
+/// let build = builds.last().ok_or("no neoforge builds for 1.20.6")?;
+/// let manifest = neoforge
+///   .install(&client, build, Path::new("./libraries"), Path::new("./versions"), Path::new("java"))
+///   .await?;
+/// assert_eq!(&*manifest.inherits_from, "1.20.6");
+/// # Result::<(), Box<dyn Error>>::Ok(())
+/// # }.block_on();
+/// ```
+#[derive(Debug, Clone)]
+pub struct NeoForge {
+	pub maven: Box<str>,
+}
+
+impl Default for NeoForge {
+	fn default() -> Self {
+		Self::new(MAVEN)
+	}
+}
+
+impl NeoForge {
+	pub fn new(maven: impl Into<Box<str>>) -> Self {
+		Self { maven: maven.into() }
+	}
+
+	fn url(&self, path: &str) -> Result<Url, url::ParseError> {
+		Url::parse(&format!("{}/{path}", self.maven.trim_end_matches('/')))
+	}
+
+	/// Lists all neoforge builds, such as `20.6.119`
+	pub async fn versions<C: Client>(&self, client: &C) -> Result<Arr<Str>, FetchError<C>> {
+		let url = self.url("api/maven/versions/releases/net/neoforged/neoforge")?;
+		let versions: MavenVersions = fetch_json(client, url).await?;
+		Ok(versions.versions)
+	}
+
+	/// Lists neoforge builds for the `game` version. Neoforge versions drop the
+	/// leading `1.` of the game version, so `1.20.6` is served by `20.6.*` and
+	/// `1.21` by `21.0.*`. The newest go last
+	pub async fn builds_for<C: Client>(&self, client: &C, game: &str) -> Result<Arr<Str>, FetchError<C>> {
+		let Some(prefix) = build_prefix(game) else {
+			return Ok(Arr::default());
+		};
+		let versions = self.versions(client).await?;
+		Ok(versions.into_vec().into_iter().filter(|it| it.starts_with(&prefix)).collect())
+	}
+
+	/// Coordinate of the installer jar of the neoforge `build`
+	pub fn installer(&self, build: &str) -> Coordinate {
+		Coordinate {
+			group: "net.neoforged".into(),
+			artifact: "neoforge".into(),
+			version: build.into(),
+			classifier: Some("installer".into()),
+			extension: "jar".into(),
+		}
+	}
+
+	/// Downloads the installer of the neoforge `build` into `libraries_dir` and
+	/// installs it. See [`Installer::install`]
+	pub async fn install<C: Client>(
+		&self,
+		client: &C,
+		build: &str,
+		libraries_dir: &Path,
+		versions_dir: &Path,
+		java: &Path,
+	) -> Result<InheritedManifest, InstallerError<C>> {
+		let installer = self.installer(build);
+		let path = libraries_dir.join(installer.path());
+		let url = self.url(&format!("releases/{}", installer.url_path()))?;
+		download_installer(client, url, &path).await?;
+
+		Installer::open(path).await?.install(client, libraries_dir, versions_dir, java).await
+	}
+}
+
+fn build_prefix(game: &str) -> Option<String> {
+	let mut parts = game.strip_prefix("1.")?.split('.');
+	let major = parts.next()?;
+	let minor = parts.next().unwrap_or("0");
+	Some(format!("{major}.{minor}."))
+}
@@ -0,0 +1,138 @@
+//! Client that never touches the network. Requests are answered from the
+//! files installed into the folder and from the [`Cache`], so the same
+//! install and launch code works without a connection as long as everything
+//! needed is already present
+
+use std::{
+	borrow::Cow,
+	io,
+	path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use futures_lite::io::Cursor;
+use serde::de::DeserializeOwned;
+use spuz_folder::Folder;
+use thiserror::Error;
+use tokio::fs;
+use url::Url;
+
+use crate::{cache::Cache, client::Body, local::decode_segment, Client};
+
+/// Maven repositories whose artifacts are stored in the libraries directory
+pub const REPOSITORIES: &[&str] = &[
+	"https://libraries.minecraft.net",
+	"https://maven.fabricmc.net",
+	"https://maven.quiltmc.org/repository/release",
+	"https://maven.minecraftforge.net",
+	"https://maven.neoforged.net/releases",
+];
+
+#[derive(Debug, Clone)]
+pub struct Offline {
+	pub libraries_dir: PathBuf,
+	pub versions_dir: PathBuf,
+	pub assets_dir: PathBuf,
+	pub cache: Cache,
+	/// Urls of maven repositories mirrored by
+	/// [`libraries_dir`](Self::libraries_dir)
+	pub repositories: Vec<Box<str>>,
+}
+
+impl Offline {
+	/// Serves files of the standard folder layout under `root`
+	pub fn new(root: &Path) -> Self {
+		Self {
+			libraries_dir: root.join("libraries"),
+			versions_dir: root.join("versions"),
+			assets_dir: root.join("assets"),
+			cache: Cache::new(root.join("cache")),
+			repositories: REPOSITORIES.iter().map(|&it| it.into()).collect(),
+		}
+	}
+
+	pub fn from_folder(folder: &Folder) -> Self {
+		Self::new(&folder.root)
+	}
+
+	/// Local files that may hold the content of the `url`, in order of
+	/// preference
+	pub fn candidates(&self, url: &Url) -> Vec<PathBuf> {
+		let mut candidates = Vec::new();
+		let segments: Vec<Cow<str>> =
+			url.path_segments().and_then(|it| it.map(decode_segment).collect()).unwrap_or_default();
+		let segments: Vec<&str> = segments.iter().map(|it| &**it).collect();
+
+		match (url.host_str(), &*segments) {
+			(Some("piston-meta.mojang.com"), ["v1", "packages", _hash, file]) => {
+				if let Some(id) = file.strip_suffix(".json") {
+					candidates.push(self.versions_dir.join(id).join(file));
+				}
+			}
+			(Some("resources.download.minecraft.net"), [h2, hash]) => {
+				candidates.push(self.assets_dir.join("objects").join(h2).join(hash));
+			}
+			_ => {}
+		}
+
+		for repository in &self.repositories {
+			let path = url.as_str().strip_prefix(&**repository).and_then(|it| it.strip_prefix('/'));
+			let path: Option<PathBuf> =
+				path.and_then(|it| it.split('/').map(|it| decode_segment(it).map(|it| PathBuf::from(&*it))).collect());
+			if let Some(path) = path {
+				candidates.push(self.libraries_dir.join(path));
+			}
+		}
+
+		candidates.push(self.cache.body_path(url));
+		candidates
+	}
+
+	async fn read(&self, url: Url) -> Result<Vec<u8>, OfflineError> {
+		for candidate in self.candidates(&url) {
+			match fs::read(&candidate).await {
+				Ok(content) => return Ok(content),
+				Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+				Err(err) => return Err(err.into()),
+			}
+		}
+
+		Err(OfflineError::NotAvailable(url))
+	}
+}
+
+#[async_trait]
+impl Client for Offline {
+	type Error = OfflineError;
+
+	async fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		Ok(serde_json::from_slice(&self.read(url).await?)?)
+	}
+
+	async fn get_stream(&self, url: Url) -> Result<Body, Self::Error> {
+		let content = self.read(url).await?;
+		let len = content.len() as u64;
+		Ok(Body::new(Cursor::new(content), Some(len)))
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum OfflineError {
+	#[error("{0} is not available offline")]
+	NotAvailable(Url),
+	#[error("Stored response is not valid json: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
@@ -0,0 +1,335 @@
+//! Skins and capes of players. [Textures](Skins::textures) of any player are
+//! public and fetched with a [`Client`]. Reading and changing the profile of
+//! the signed in player needs the minecraft access token and is only
+//! available with the `reqwest` feature, those requests go through a
+//! [`reqwest::Client`] rather than any [`Client`]
+//!
+//! # Example
+//! ```no_run
+//! # use std::{error::Error, path::Path};
+//! # use pollster::FutureExt;
+//! use spuz_get::skins::{Model, Skins, TextureCache};
+//!
+//! # async move {
+//! let client = reqwest::Client::new();
+//! let skins = Skins::default();
+//!
+//! let textures = skins.textures(&client, "069a79f444e94726a5befca90e38aaf5").await?;
+//! if let Some(skin) = &textures.skin {
+//!   let path = TextureCache::new("./textures").texture(&client, &skin.url).await?;
+//! }
+//!
+//! let profile = skins.upload_skin_file(&client, "access token", Path::new("./skin.png"), Model::Slim).await?;
+//! # Result::<(), Box<dyn Error>>::Ok(())
+//! # }.block_on();
+//! ```
+
+use std::path::PathBuf;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+pub use spuz_auth::{Cape, Model, Profile, Skin, TextureState};
+use spuz_folder::Folder;
+use spuz_piston::shared::{Arr, Str};
+use thiserror::Error;
+use tokio::fs;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+	client::{fetch_json, fetch_stream},
+	download::{self, DownloadError},
+	Client, FetchError,
+};
+
+/// Official session server, serves textures of any player
+pub const SESSION_API: &str = "https://sessionserver.mojang.com";
+
+/// Official minecraft services, serves the profile of the signed in player
+pub const SERVICES_API: &str = "https://api.minecraftservices.com";
+
+/// Textures of a player as the session server tells them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Textures {
+	pub profile_id: Str,
+	pub profile_name: Str,
+	/// Missing if the player uses the default skin
+	pub skin: Option<SkinTexture>,
+	pub cape: Option<Str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkinTexture {
+	pub url: Str,
+	pub model: Model,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionProfile {
+	#[serde(default)]
+	properties: Arr<Property>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Property {
+	name: Str,
+	value: Str,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TexturesProperty {
+	profile_id: Str,
+	profile_name: Str,
+	#[serde(default)]
+	textures: RawTextures,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+struct RawTextures {
+	skin: Option<RawTexture>,
+	cape: Option<RawTexture>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTexture {
+	url: Str,
+	metadata: Option<RawMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawMetadata {
+	model: Option<Model>,
+}
+
+/// Client of the session server and the minecraft services, both official
+/// by [default](Skins::default)
+#[derive(Debug, Clone)]
+pub struct Skins {
+	pub session_api: Box<str>,
+	pub services_api: Box<str>,
+}
+
+impl Default for Skins {
+	fn default() -> Self {
+		Self::new(SESSION_API, SERVICES_API)
+	}
+}
+
+impl Skins {
+	pub fn new(session_api: impl Into<Box<str>>, services_api: impl Into<Box<str>>) -> Self {
+		Self { session_api: session_api.into(), services_api: services_api.into() }
+	}
+
+	fn url(base: &str, path: &str) -> Result<Url, url::ParseError> {
+		Url::parse(&format!("{}/{path}", base.trim_end_matches('/')))
+	}
+
+	/// Textures of the player with the `uuid`
+	pub async fn textures<C: Client>(&self, client: &C, uuid: &str) -> Result<Textures, TexturesError<C>> {
+		let url = Self::url(&self.session_api, &format!("session/minecraft/profile/{uuid}"))?;
+		let profile: SessionProfile = fetch_json(client, url).await?;
+
+		let property = profile.properties.iter().find(|it| &*it.name == "textures").ok_or(TexturesError::NoTextures)?;
+		let property: TexturesProperty = serde_json::from_slice(&STANDARD.decode(&*property.value)?)?;
+
+		let RawTextures { skin, cape } = property.textures;
+		Ok(Textures {
+			profile_id: property.profile_id,
+			profile_name: property.profile_name,
+			skin: skin.map(|it| SkinTexture { url: it.url, model: it.metadata.and_then(|it| it.model).unwrap_or_default() }),
+			cape: cape.map(|it| it.url),
+		})
+	}
+}
+
+/// Requests to the profile of the signed in player. [`Client`] only sends
+/// `GET` requests while these post, put and delete, so they are reqwest-only
+#[cfg(feature = "reqwest")]
+impl Skins {
+	/// Profile of the player the `token` belongs to, lists their capes as well
+	pub async fn profile(&self, http: &reqwest::Client, token: &str) -> Result<Profile, SkinError> {
+		let url = Self::url(&self.services_api, "minecraft/profile")?;
+		profile(http.get(url).bearer_auth(token)).await
+	}
+
+	/// Sets the skin to the png at the `url`
+	pub async fn set_skin(
+		&self,
+		http: &reqwest::Client,
+		token: &str,
+		url: &str,
+		model: Model,
+	) -> Result<Profile, SkinError> {
+		let endpoint = Self::url(&self.services_api, "minecraft/profile/skins")?;
+		let body = serde_json::json!({ "variant": model.as_str(), "url": url });
+		profile(http.post(endpoint).bearer_auth(token).json(&body)).await
+	}
+
+	/// Uploads the `png` as the skin, it has to be 64x64 or legacy 64x32
+	pub async fn upload_skin(
+		&self,
+		http: &reqwest::Client,
+		token: &str,
+		png: Vec<u8>,
+		model: Model,
+	) -> Result<Profile, SkinError> {
+		validate_skin(&png)?;
+
+		let url = Self::url(&self.services_api, "minecraft/profile/skins")?;
+		let file = reqwest::multipart::Part::bytes(png).file_name("skin.png").mime_str("image/png")?;
+		let form = reqwest::multipart::Form::new().text("variant", model.as_str()).part("file", file);
+		profile(http.post(url).bearer_auth(token).multipart(form)).await
+	}
+
+	pub async fn upload_skin_file(
+		&self,
+		http: &reqwest::Client,
+		token: &str,
+		path: &std::path::Path,
+		model: Model,
+	) -> Result<Profile, SkinError> {
+		self.upload_skin(http, token, fs::read(path).await?, model).await
+	}
+
+	/// Goes back to the default skin
+	pub async fn reset_skin(&self, http: &reqwest::Client, token: &str) -> Result<Profile, SkinError> {
+		let url = Self::url(&self.services_api, "minecraft/profile/skins/active")?;
+		profile(http.delete(url).bearer_auth(token)).await
+	}
+
+	/// Shows the cape with the `id`, one of [`Profile::capes`]
+	pub async fn select_cape(&self, http: &reqwest::Client, token: &str, id: &str) -> Result<Profile, SkinError> {
+		let url = Self::url(&self.services_api, "minecraft/profile/capes/active")?;
+		profile(http.put(url).bearer_auth(token).json(&serde_json::json!({ "capeId": id }))).await
+	}
+
+	pub async fn hide_cape(&self, http: &reqwest::Client, token: &str) -> Result<Profile, SkinError> {
+		let url = Self::url(&self.services_api, "minecraft/profile/capes/active")?;
+		profile(http.delete(url).bearer_auth(token)).await
+	}
+}
+
+#[cfg(feature = "reqwest")]
+async fn profile(request: reqwest::RequestBuilder) -> Result<Profile, SkinError> {
+	let response = request.send().await?;
+	let status = response.status();
+	if !status.is_success() {
+		let url = response.url().clone();
+		let body = response.text().await.unwrap_or_default().into();
+		return Err(SkinError::Status { url, status: status.as_u16(), body });
+	}
+	Ok(response.json().await?)
+}
+
+#[cfg(feature = "reqwest")]
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[cfg(feature = "reqwest")]
+/// Checks the png signature and the size from the `IHDR` chunk
+fn validate_skin(png: &[u8]) -> Result<(), SkinError> {
+	if !png.starts_with(PNG_SIGNATURE) || png.get(12..16) != Some(b"IHDR") {
+		return Err(SkinError::InvalidSkin("not a png".into()));
+	}
+	let size = |range: std::ops::Range<usize>| png.get(range).and_then(|it| it.try_into().ok()).map(u32::from_be_bytes);
+	match (size(16..20), size(20..24)) {
+		(Some(64), Some(64 | 32)) => Ok(()),
+		(Some(width), Some(height)) => Err(SkinError::InvalidSkin(format!("size is {width}x{height}").into())),
+		_ => Err(SkinError::InvalidSkin("truncated png".into())),
+	}
+}
+
+/// Texture pngs stored by their hash, the last segment of their url. Textures
+/// never change under the same url, so cached ones are not fetched again
+#[derive(Debug, Clone)]
+pub struct TextureCache {
+	pub dir: PathBuf,
+}
+
+impl TextureCache {
+	pub fn new(dir: impl Into<PathBuf>) -> Self {
+		Self { dir: dir.into() }
+	}
+
+	pub fn from_folder(folder: &Folder) -> Self {
+		Self::new(folder.root.join("textures"))
+	}
+
+	/// Path of the cached texture, fetches it on the first call
+	pub async fn texture<C: Client>(&self, client: &C, url: &str) -> Result<PathBuf, TexturesError<C>> {
+		let url = Url::parse(url)?;
+		let hash = url
+			.path_segments()
+			.and_then(|mut it| it.next_back())
+			.filter(|it| !it.is_empty() && it.bytes().all(|it| it.is_ascii_alphanumeric()))
+			.ok_or_else(|| TexturesError::InvalidUrl(url.as_str().into()))?;
+		let path = self.dir.join(format!("{hash}.png"));
+
+		if fs::try_exists(&path).await.map_err(DownloadError::from)? {
+			return Ok(path);
+		}
+
+		debug!("Caching texture {hash}");
+		let stream = fetch_stream(client, url.clone()).await?;
+		download::save(stream, &path, None).await?;
+		Ok(path)
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum TexturesError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error("Profile has no textures")]
+	NoTextures,
+	#[error("Invalid textures property: {0}")]
+	Decode(
+		#[from]
+		#[source]
+		base64::DecodeError,
+	),
+	#[error("Invalid textures property: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+	#[error("Texture url {0} has no hash")]
+	InvalidUrl(Str),
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+}
+
+impl<C: Client> From<url::ParseError> for TexturesError<C> {
+	fn from(value: url::ParseError) -> Self {
+		Self::Fetch(value.into())
+	}
+}
+
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Error)]
+pub enum SkinError {
+	#[error("Request failed: {0}")]
+	Request(
+		#[from]
+		#[source]
+		reqwest::Error,
+	),
+	#[error("{url} responded with status {status}: {body}")]
+	Status { url: Url, status: u16, body: Str },
+	#[error("Invalid url: {0}")]
+	Url(
+		#[from]
+		#[source]
+		url::ParseError,
+	),
+	#[error("Invalid skin, {0}")]
+	InvalidSkin(Str),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		std::io::Error,
+	),
+}
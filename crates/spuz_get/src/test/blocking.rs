@@ -0,0 +1,84 @@
+use std::io::Read;
+
+use mockito::Server;
+use serde_json::{json, Value};
+
+use crate::{
+	blocking::{vanilla, Blocking, Client, FetchError},
+	json_resource::{JsonResourceParseError, JsonResourceSaveError},
+	mirror::{HostMap, Mirrored},
+};
+
+#[test]
+fn fetch_through_mirror() {
+	let mut server = Server::new();
+	server
+		.mock("GET", "/mc/game/version_manifest_v2.json")
+		.with_body(json!({ "latest": { "release": "1.21", "snapshot": "1.21" }, "versions": [] }).to_string())
+		.create();
+	server
+		.mock("GET", "/v1/packages/f064f34723ef37a918a5c71e8eec6406376138d0/1.21.json")
+		.with_body(json!({ "id": "1.21" }).to_string())
+		.create();
+	server.mock("GET", "/assets/ab/abcdef").with_body("resource").create();
+
+	let client = Mirrored::new(ureq::Agent::new_with_defaults()).with(HostMap::mirror_of(&server.url())).without_origin();
+
+	vanilla::list(&client).unwrap();
+
+	let package: Value =
+		vanilla::package(&client, "f064f34723ef37a918a5c71e8eec6406376138d0", "1.21").unwrap().json().unwrap();
+	assert_eq!(package["id"], "1.21");
+
+	let mut content = String::new();
+	vanilla::resource(&client, "abcdef").unwrap().read_to_string(&mut content).unwrap();
+	assert_eq!(content, "resource");
+}
+
+#[test]
+fn save_package() {
+	let mut server = Server::new();
+	server.mock("GET", "/package.json").with_body("{\"id\":\"1.21\"}").create();
+
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("1.21.json");
+	let agent = ureq::Agent::new_with_defaults();
+
+	let reader = agent.get_reader(format!("{}/package.json", server.url()).parse().unwrap()).unwrap();
+	crate::JsonResource::<_, Value>::new(Blocking(reader)).save(&path).unwrap();
+	assert_eq!(std::fs::read_to_string(path).unwrap(), "{\"id\":\"1.21\"}");
+}
+
+#[test]
+fn status_errors() {
+	let mut server = Server::new();
+	server.mock("GET", "/v1/objects/abc/client.jar").with_status(404).create();
+
+	let client = Mirrored::new(ureq::Agent::new_with_defaults()).with(HostMap::mirror_of(&server.url())).without_origin();
+
+	assert!(matches!(
+		vanilla::object(&client, "abc", "client.jar"),
+		Err(FetchError::Client { source: ureq::Error::StatusCode(404), .. })
+	));
+}
+
+#[test]
+fn verify_package() {
+	let mut server = Server::new();
+	server.mock("GET", "/package.json").with_body("{\"id\":\"1.21\"}").create();
+
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("1.21.json");
+	let agent = ureq::Agent::new_with_defaults();
+	let url: url::Url = format!("{}/package.json", server.url()).parse().unwrap();
+
+	let resource = crate::JsonResource::<_, Value>::new(Blocking(agent.get_reader(url.clone()).unwrap()));
+	let result = resource.verify("0000000000000000000000000000000000000000").tee(&path).json();
+	assert!(matches!(result, Err(JsonResourceParseError::HashMismatch { .. })));
+	assert!(!path.exists());
+
+	let resource = crate::JsonResource::<_, Value>::new(Blocking(agent.get_reader(url).unwrap()));
+	let result = resource.verify("0000000000000000000000000000000000000000").save(&path);
+	assert!(matches!(result, Err(JsonResourceSaveError::HashMismatch { .. })));
+	assert!(!path.exists());
+}
@@ -0,0 +1,143 @@
+use futures_lite::AsyncReadExt;
+use mockito::{Matcher, Server};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+	cache::{Cache, CacheError, Cached},
+	testing::MockClient,
+	Client,
+};
+
+fn cached(dir: &tempfile::TempDir) -> Cached<reqwest::Client> {
+	Cached::new(reqwest::Client::new(), Cache::new(dir.path()))
+}
+
+#[tokio::test]
+async fn fresh_responses_are_not_requested() {
+	let mut server = Server::new_async().await;
+	let mock = server
+		.mock("GET", "/list.json")
+		.with_header("Cache-Control", "public, max-age=3600")
+		.with_body(json!({ "latest": "1.21" }).to_string())
+		.expect(1)
+		.create_async()
+		.await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let url: Url = format!("{}/list.json", server.url()).parse().unwrap();
+
+	for _ in 0..2 {
+		let value: Value = cached(&temp).get_json(url.clone()).await.unwrap();
+		assert_eq!(value["latest"], "1.21");
+	}
+
+	mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn stale_responses_are_revalidated() {
+	let mut server = Server::new_async().await;
+	let full = server
+		.mock("GET", "/list.json")
+		.match_header("If-None-Match", Matcher::Missing)
+		.with_header("ETag", "\"v1\"")
+		.with_header("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT")
+		.with_body(json!({ "latest": "1.21" }).to_string())
+		.expect(1)
+		.create_async()
+		.await;
+	let conditional = server
+		.mock("GET", "/list.json")
+		.match_header("If-None-Match", "\"v1\"")
+		.match_header("If-Modified-Since", "Wed, 21 Oct 2015 07:28:00 GMT")
+		.with_status(304)
+		.expect(1)
+		.create_async()
+		.await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let client = cached(&temp);
+	let url: Url = format!("{}/list.json", server.url()).parse().unwrap();
+
+	let first: Value = client.get_json(url.clone()).await.unwrap();
+	let second: Value = client.get_json(url).await.unwrap();
+	assert_eq!(first, second);
+
+	full.assert_async().await;
+	conditional.assert_async().await;
+}
+
+#[tokio::test]
+async fn stale_responses_are_served_on_errors() {
+	let mut server = Server::new_async().await;
+	let ok = server.mock("GET", "/list.json").with_body("[1, 2]").create_async().await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let url: Url = format!("{}/list.json", server.url()).parse().unwrap();
+
+	let _: Value = cached(&temp).get_json(url.clone()).await.unwrap();
+	ok.remove_async().await;
+	server.mock("GET", "/list.json").with_status(503).create_async().await;
+
+	let stale: Vec<u8> = cached(&temp).get_json(url.clone()).await.unwrap();
+	assert_eq!(stale, [1, 2]);
+
+	let strict = Cached { stale_if_error: false, ..cached(&temp) };
+	assert!(matches!(strict.get_json::<Value>(url).await, Err(CacheError::Client(_))));
+}
+
+#[tokio::test]
+async fn no_store_is_respected() {
+	let mut server = Server::new_async().await;
+	let mock = server
+		.mock("GET", "/secret")
+		.with_header("Cache-Control", "no-store")
+		.with_body("{}")
+		.expect(2)
+		.create_async()
+		.await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let url: Url = format!("{}/secret", server.url()).parse().unwrap();
+
+	for _ in 0..2 {
+		let _: Value = cached(&temp).get_json(url.clone()).await.unwrap();
+	}
+	assert!(!Cache::new(temp.path()).body_path(&url).exists());
+
+	mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn chunked_bodies_over_the_limit_are_not_cached() {
+	let mut server = Server::new_async().await;
+	let mock =
+		server.mock("GET", "/client.jar").with_chunked_body(|it| it.write_all(&[7; 64])).expect(2).create_async().await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let client = Cached { stream_limit: 16, ..cached(&temp) };
+	let url: Url = format!("{}/client.jar", server.url()).parse().unwrap();
+
+	for _ in 0..2 {
+		let mut content = Vec::new();
+		client.get_stream(url.clone()).await.unwrap().read_to_end(&mut content).await.unwrap();
+		assert_eq!(content, [7; 64]);
+	}
+	assert!(!Cache::new(temp.path()).body_path(&url).exists());
+
+	mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn content_addressed_urls_are_not_revalidated() {
+	let url = "https://piston-meta.mojang.com/v1/packages/4d6a6b7ab7ba3a1c4d4bb6a3a2ed5b9f3d1b8e34/1.20.6.json";
+	let temp = tempfile::tempdir().unwrap();
+	let client = Cached::new(MockClient::new().with(url, "{}"), Cache::new(temp.path()));
+
+	for _ in 0..2 {
+		let _: Value = client.get_json(url.parse().unwrap()).await.unwrap();
+	}
+
+	assert_eq!(client.client.requested(url), 1);
+}
@@ -0,0 +1,204 @@
+use std::io::{Cursor, Write};
+
+use mockito::{Matcher, Server};
+use serde_json::{json, Value};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use super::{folder, modpack::game};
+use crate::{
+	curseforge::{
+		pack::{Modpack, OpenPackError},
+		Class, CurseForge, CurseForgeError, ModLoader, ModSearch,
+	},
+	download,
+	modpack::LoaderKind,
+	testing::MockClient,
+};
+
+const KEY: &str = "secret";
+const API: &str = "https://api.curseforge.com/v1";
+const CDN: &str = "https://edge.forgecdn.net/files";
+const JEI: &[u8] = b"jei";
+const FAITHFUL: &[u8] = b"faithful";
+
+async fn file(id: u32, project: u32, name: &str, url: Option<String>, content: &[u8]) -> Value {
+	json!({
+		"id": id,
+		"modId": project,
+		"displayName": name,
+		"fileName": name,
+		"downloadUrl": url,
+		"hashes": [{ "value": "d41d8cd98f00b204e9800998ecf8427e", "algo": 2 }, { "value": download::sha1(content).await.unwrap(), "algo": 1 }],
+		"fileLength": content.len(),
+		"gameVersions": ["1.20.1", "Forge"]
+	})
+}
+
+fn project(id: u32, class: u32) -> Value {
+	json!({ "data": { "id": id, "name": format!("Project {id}"), "slug": format!("project-{id}"), "summary": "", "classId": class } })
+}
+
+fn pack(manifest: &Value, entries: &[(&str, &[u8])]) -> Vec<u8> {
+	let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+	writer.start_file("manifest.json", SimpleFileOptions::default()).unwrap();
+	writer.write_all(manifest.to_string().as_bytes()).unwrap();
+	for (name, content) in entries {
+		writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+		writer.write_all(content).unwrap();
+	}
+	writer.finish().unwrap().into_inner()
+}
+
+#[tokio::test]
+async fn search_sends_api_key() {
+	let mut server = Server::new_async().await;
+	let search = server
+		.mock("GET", "/v1/mods/search")
+		.match_header("x-api-key", KEY)
+		.match_query(Matcher::AllOf(vec![
+			Matcher::UrlEncoded("gameId".into(), "432".into()),
+			Matcher::UrlEncoded("searchFilter".into(), "jei".into()),
+			Matcher::UrlEncoded("classId".into(), "6".into()),
+			Matcher::UrlEncoded("gameVersion".into(), "1.20.1".into()),
+			Matcher::UrlEncoded("modLoaderType".into(), "1".into()),
+		]))
+		.with_body(
+			json!({
+				"data": [{ "id": 238_222, "name": "JEI", "slug": "jei", "summary": "Item list", "classId": 6, "downloadCount": 10 }],
+				"pagination": { "index": 0, "pageSize": 50, "resultCount": 1, "totalCount": 1 }
+			})
+			.to_string(),
+		)
+		.create_async()
+		.await;
+
+	let other = server.mock("GET", "/other").match_header("x-api-key", Matcher::Missing).create_async().await;
+
+	let curseforge = CurseForge::new(format!("{}/v1", server.url()));
+	let client = curseforge.http(KEY).unwrap();
+	let query = ModSearch::new("jei").class(Class::Mods).game_version("1.20.1").loader(ModLoader::Forge);
+	let page = curseforge.search(&client, &query).await.unwrap();
+
+	assert_eq!(page.pagination.total_count, 1);
+	assert_eq!(&*page.data[0].slug, "jei");
+	search.assert_async().await;
+
+	// The key is not sent outside of the api
+	crate::client::fetch_stream(&client, format!("{}/other", server.url()).parse().unwrap()).await.unwrap();
+	other.assert_async().await;
+}
+
+#[tokio::test]
+async fn downloads_files() {
+	let mut server = Server::new_async().await;
+	let url = server.url();
+	server
+		.mock("GET", "/v1/mods/1/files/10")
+		.with_body(json!({ "data": file(10, 1, "jei.jar", Some(format!("{url}/jei.jar")), JEI).await }).to_string())
+		.create_async()
+		.await;
+	server
+		.mock("GET", "/v1/mods/1/files/11")
+		.with_body(json!({ "data": file(11, 1, "jei-old.jar", None, JEI).await }).to_string())
+		.create_async()
+		.await;
+	server.mock("GET", "/jei.jar").with_body(JEI).create_async().await;
+
+	let client = reqwest::Client::new();
+	let curseforge = CurseForge::new(format!("{url}/v1"));
+	let temp = tempfile::tempdir().unwrap();
+
+	let file = curseforge.file(&client, 1, 10).await.unwrap();
+	assert_eq!(file.sha1(), Some(&*download::sha1(JEI).await.unwrap()));
+	let (path, _) = curseforge.download(&client, &file, temp.path()).await.unwrap();
+	assert_eq!(std::fs::read(path).unwrap(), JEI);
+
+	let file = curseforge.file(&client, 1, 11).await.unwrap();
+	let result = curseforge.download(&client, &file, temp.path()).await;
+	assert!(matches!(result, Err(CurseForgeError::DownloadNotAllowed { project: 1, file: 11 })));
+}
+
+#[tokio::test]
+async fn imports_modpack() {
+	let mut api = MockClient::new();
+	let mut client = MockClient::new();
+	for (project, id, name, content, allowed) in [
+		(1, 10, "jei.jar", JEI, true),
+		(1, 11, "jei-addon.jar", JEI, true),
+		(2, 20, "faithful.zip", FAITHFUL, true),
+		(3, 30, "blocked.jar", JEI, false),
+	] {
+		let download = allowed.then(|| format!("{CDN}/{name}"));
+		api = api.with_json(
+			&format!("{API}/mods/{project}/files/{id}"),
+			&json!({ "data": file(id, project, name, download, content).await }),
+		);
+		client = client.with(&format!("{CDN}/{name}"), content);
+	}
+	let api =
+		api.with_json(&format!("{API}/mods/1"), &project(1, 6)).with_json(&format!("{API}/mods/2"), &project(2, 12));
+	let client = game(client).await;
+
+	let mut manifest = json!({
+		"minecraft": { "version": "1.20.6", "modLoaders": [{ "id": "fabric-0.15.11", "primary": true }] },
+		"manifestType": "minecraftModpack",
+		"manifestVersion": 1,
+		"name": "Pack",
+		"version": "1.0.0",
+		"author": "someone",
+		"files": [
+			{ "projectID": 1, "fileID": 10, "required": true },
+			{ "projectID": 1, "fileID": 11, "required": true },
+			{ "projectID": 2, "fileID": 20, "required": true },
+			{ "projectID": 3, "fileID": 30, "required": true },
+			{ "projectID": 4, "fileID": 40, "required": false }
+		],
+		"overrides": "overrides"
+	});
+	let archive = pack(&manifest, &[("overrides/config/jei.toml", b"config"), ("other/ignored.txt", b"ignored")]);
+
+	let (_temp, folder) = folder().await;
+	let pack = Modpack::from_bytes(archive.clone()).unwrap();
+	assert_eq!(pack.loader(), Some((LoaderKind::Fabric, "0.15.11")));
+
+	// Failed imports leave no instance behind
+	let curseforge = CurseForge::new(API);
+	let result = pack.install(&client, &curseforge, &MockClient::new(), &folder, "pack").await;
+	assert!(result.is_err());
+	assert_eq!(folder.instances.get("pack").await.unwrap(), None);
+
+	let pack = Modpack::from_bytes(archive).unwrap();
+	let imported = pack.install(&client, &curseforge, &api, &folder, "pack").await.unwrap();
+	let instance = &imported.instance;
+	let game_dir = instance.game_dir();
+
+	assert_eq!(folder.instances.get("pack").await.unwrap().as_ref(), Some(instance));
+	assert_eq!(instance.config.version_id(), "fabric-loader-0.15.11-1.20.6");
+	assert!(folder.versions.get("1.20.6").client_path().exists());
+	assert!(folder.versions.get(&instance.config.version_id()).manifest_path().exists());
+	assert_eq!(imported.blocked.len(), 1);
+	assert_eq!(&*imported.blocked[0].file_name, "blocked.jar");
+	assert_eq!(std::fs::read(game_dir.join("mods/jei.jar")).unwrap(), JEI);
+	assert_eq!(std::fs::read(game_dir.join("mods/jei-addon.jar")).unwrap(), JEI);
+	assert_eq!(std::fs::read(game_dir.join("resourcepacks/faithful.zip")).unwrap(), FAITHFUL);
+	assert_eq!(std::fs::read(game_dir.join("config/jei.toml")).unwrap(), b"config");
+	assert!(!game_dir.join("ignored.txt").exists());
+	assert_eq!(api.requested(&format!("{API}/mods/1")), 1);
+	assert_eq!(api.requested(&format!("{API}/mods/4/files/40")), 0);
+
+	manifest["minecraft"]["modLoaders"] = json!([{ "id": "fabric-0.15.11" }, { "id": "forge-47.2.0", "primary": true }]);
+	let pack = Modpack::from_bytes(self::pack(&manifest, &[])).unwrap();
+	assert_eq!(pack.loader(), Some((LoaderKind::Forge, "47.2.0")));
+}
+
+#[test]
+fn rejects_invalid_packs() {
+	let manifest =
+		json!({ "minecraft": { "version": "1.20.1" }, "manifestType": "other", "manifestVersion": 1, "name": "Pack" });
+	assert!(matches!(Modpack::from_bytes(pack(&manifest, &[])), Err(OpenPackError::Unsupported { .. })));
+
+	let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+	writer.start_file("overrides/a.txt", SimpleFileOptions::default()).unwrap();
+	let archive = writer.finish().unwrap().into_inner();
+	assert!(matches!(Modpack::from_bytes(archive), Err(OpenPackError::MissingManifest)));
+}
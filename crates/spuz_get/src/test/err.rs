@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use spuz_piston::Manifest;
+
+use crate::{download, testing::MockClient, vanilla, vanilla::runtime::InstallRuntimeError, Error};
+
+/// sha1 of the 1.20.6 fixture
+const HASH: &str = "e549ebaa9c8111c32c7341af92e809b8a5204590";
+const PACKAGE: &str = "https://piston-meta.mojang.com/v1/packages/e549ebaa9c8111c32c7341af92e809b8a5204590/1.20.6.json";
+
+const fn assert_send_sync<T: Send + Sync>() {}
+const _: () = assert_send_sync::<Error>();
+
+async fn manifest(client: &MockClient, hash: &str) -> Result<Manifest, Error> {
+	Ok(vanilla::package(client, hash, "1.20.6").await?.json().await?)
+}
+
+#[tokio::test]
+async fn converts_with_question_mark() {
+	let client = MockClient::new().with_fixture(PACKAGE, "../../versions/1.20.6.json").unwrap();
+	assert_eq!(&*manifest(&client, HASH).await.unwrap().id, "1.20.6");
+
+	let err = manifest(&client, "def").await.unwrap_err();
+	assert!(matches!(err, Error::Fetch { .. }));
+	assert_eq!(err.url().unwrap().as_str(), "https://piston-meta.mojang.com/v1/packages/def/1.20.6.json");
+	assert!(err.to_string().contains("/def/1.20.6.json"));
+}
+
+#[tokio::test]
+async fn maps_hash_mismatch() {
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("file");
+
+	let err: Error = download::save(&b"content"[..], &path, Some("0000")).await.unwrap_err().into();
+	assert!(matches!(err, Error::HashMismatch { path: Some(ref it), .. } if **it == *path));
+	assert!(!Path::new(&path).exists());
+}
+
+#[tokio::test]
+async fn removes_partial_files() {
+	use crate::client::Client;
+
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("file");
+	let client = MockClient::new().with(PACKAGE, "content").truncate(PACKAGE, 3);
+
+	let stream = client.get_stream(PACKAGE.parse().unwrap()).await.unwrap();
+	let err = download::save(stream, &path, None).await.unwrap_err();
+	assert!(matches!(err, download::DownloadError::Io(ref it) if it.kind() == std::io::ErrorKind::UnexpectedEof));
+	assert!(!path.exists());
+	assert!(!temp.path().join("file.part").exists());
+}
+
+#[tokio::test]
+async fn validates_hashes_case_insensitively() {
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("file");
+
+	let checksum = download::save(&b"content"[..], &path, None).await.unwrap();
+	assert!(download::is_valid(&path, &checksum.sha1.to_ascii_uppercase()).await.unwrap());
+}
+
+#[test]
+fn keeps_failures_typed() {
+	let err: Error = InstallRuntimeError::<MockClient>::UnknownComponent("jre-legacy".into()).into();
+	assert!(matches!(err, Error::UnknownComponent(ref it) if &**it == "jre-legacy"));
+
+	let err: Error = InstallRuntimeError::<MockClient>::UnsafePath(Path::new("../escape").into()).into();
+	assert!(matches!(err, Error::UnsafePath(ref it) if **it == *Path::new("../escape")));
+}
+
+#[cfg(feature = "reqwest")]
+#[tokio::test]
+async fn detects_http_status() {
+	use crate::cache::{Cache, Cached};
+
+	let mut server = mockito::Server::new_async().await;
+	server.mock("GET", "/missing.json").with_status(404).create_async().await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let client = Cached::new(reqwest::Client::new(), Cache::new(temp.path()));
+	let url: url::Url = format!("{}/missing.json", server.url()).parse().unwrap();
+
+	let result: Result<serde_json::Value, Error> =
+		async { Ok(crate::client::fetch_json(&client, url.clone()).await?) }.await;
+	let err = result.unwrap_err();
+	assert!(matches!(err, Error::Status { url: ref it, status: 404, .. } if *it == url));
+	assert!(std::error::Error::source(&err).is_some_and(|it| it.to_string().contains("404")));
+}
@@ -0,0 +1,142 @@
+use std::str::FromStr;
+
+use mockito::{Matcher, Server};
+use serde_json::json;
+use spuz_piston::Manifest;
+use tokio::fs;
+
+use crate::{download, fabric::Fabric, FsExt};
+
+const LOADER_JAR: &[u8] = b"fabric-loader";
+const INTERMEDIARY_JAR: &[u8] = b"intermediary";
+
+#[tokio::test]
+async fn list_versions() {
+	let mut server = Server::new_async().await;
+	let game = server
+		.mock("GET", "/v2/versions/game")
+		.with_body(json!([{ "version": "1.20.6", "stable": true }, { "version": "24w21b", "stable": false }]).to_string())
+		.create_async()
+		.await;
+	let loader = json!({ "separator": ".", "build": 11, "maven": "net.fabricmc:fabric-loader:0.15.11", "version": "0.15.11", "stable": true });
+	let loaders = server.mock("GET", "/v2/versions/loader").with_body(json!([loader]).to_string()).create_async().await;
+	let loaders_for = server
+		.mock("GET", "/v2/versions/loader/1.20.6")
+		.with_body(json!([{ "loader": loader, "intermediary": { "maven": "net.fabricmc:intermediary:1.20.6", "version": "1.20.6", "stable": true } }]).to_string())
+		.create_async()
+		.await;
+
+	let client = reqwest::Client::new();
+	let fabric = Fabric::new(format!("{}/v2", server.url()));
+
+	let versions = fabric.game_versions(&client).await.unwrap();
+	assert_eq!(versions.len(), 2);
+	assert!(versions[0].stable);
+	assert_eq!(&*versions[1].version, "24w21b");
+
+	let versions = fabric.loader_versions(&client).await.unwrap();
+	assert_eq!(&*versions[0].maven, "net.fabricmc:fabric-loader:0.15.11");
+
+	let versions = fabric.loaders_for(&client, "1.20.6").await.unwrap();
+	assert_eq!(&*versions[0].version, "0.15.11");
+
+	game.assert_async().await;
+	loaders.assert_async().await;
+	loaders_for.assert_async().await;
+}
+
+#[tokio::test]
+async fn install_profile() {
+	let mut server = Server::new_async().await;
+	let loader_sha1 = download::sha1(LOADER_JAR).await.unwrap();
+
+	let profile = json!({
+		"id": "fabric-loader-0.15.11-1.20.6",
+		"inheritsFrom": "1.20.6",
+		"releaseTime": "2024-05-09T10:52:01+0000",
+		"time": "2024-05-09T10:52:01+0000",
+		"type": "release",
+		"mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+		"arguments": { "game": [], "jvm": ["-DFabricMcEmu= net.minecraft.client.main.Main "] },
+		"libraries": [
+			{ "name": "net.fabricmc:fabric-loader:0.15.11", "url": format!("{}/maven/", server.url()), "sha1": loader_sha1, "size": LOADER_JAR.len() },
+			{ "name": "net.fabricmc:intermediary:1.20.6", "url": format!("{}/maven", server.url()) }
+		]
+	});
+
+	let profile_mock = server
+		.mock("GET", "/v2/versions/loader/1.20.6/0.15.11/profile/json")
+		.with_body(profile.to_string())
+		.expect(2)
+		.create_async()
+		.await;
+	let loader_mock = server
+		.mock("GET", "/maven/net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar")
+		.with_body(LOADER_JAR)
+		.expect(1)
+		.create_async()
+		.await;
+	let intermediary_mock = server
+		.mock("GET", Matcher::Exact("/maven/net/fabricmc/intermediary/1.20.6/intermediary-1.20.6.jar".into()))
+		.with_body(INTERMEDIARY_JAR)
+		.expect(2)
+		.create_async()
+		.await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let libraries_dir = temp.path().join("libraries");
+	let versions_dir = temp.path().join("versions");
+
+	let client = reqwest::Client::new();
+	let fabric = Fabric::new(format!("{}/v2", server.url()));
+	let manifest = fabric.install(&client, "1.20.6", "0.15.11", &libraries_dir, &versions_dir).await.unwrap();
+
+	assert_eq!(&*manifest.inherits_from, "1.20.6");
+	assert_eq!(manifest.libraries.len(), 2);
+
+	let artifact = manifest.libraries[1].downloads.artifact.as_ref().unwrap();
+	assert_eq!(&*artifact.sha1, download::sha1(INTERMEDIARY_JAR).await.unwrap());
+	assert_eq!(artifact.size, INTERMEDIARY_JAR.len() as u64);
+	assert_eq!(fs::read(libraries_dir.join(&artifact.path)).await.unwrap(), INTERMEDIARY_JAR);
+
+	let saved =
+		spuz_piston::InheritedManifest::load(versions_dir.join(&*manifest.id).join(format!("{}.json", manifest.id)))
+			.await
+			.unwrap();
+	assert_eq!(saved, manifest);
+
+	let parent = Manifest::from_str(include_str!("../../../../versions/1.20.6.json")).unwrap();
+	let launchable = saved.inherit(parent);
+	assert_eq!(&*launchable.main_class, "net.fabricmc.loader.impl.launch.knot.KnotClient");
+
+	// Libraries with known hashes are not downloaded again, the rest are
+	fabric.install(&client, "1.20.6", "0.15.11", &libraries_dir, &versions_dir).await.unwrap();
+
+	profile_mock.assert_async().await;
+	loader_mock.assert_async().await;
+	intermediary_mock.assert_async().await;
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn encodes_versions_and_rejects_unsafe_ids() {
+	use crate::{loader::install_profile, testing::MockClient};
+
+	let client = MockClient::new()
+		.with_json("https://meta.fabricmc.net/v2/versions/loader/1.20.6%2F..%3Fx/0.15.11/profile/json", &json!({}));
+	let fabric = Fabric::default();
+	assert!(fabric.profile(&client, "1.20.6/..?x", "0.15.11").await.is_err());
+	assert_eq!(client.requested("https://meta.fabricmc.net/v2/versions/loader/1.20.6%2F..%3Fx/0.15.11/profile/json"), 1);
+
+	let temp = tempfile::tempdir().unwrap();
+	let versions_dir = temp.path().join("versions");
+	let profile = serde_json::from_value(json!({
+		"id": "../escape",
+		"inheritsFrom": "1.20.6",
+		"mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient"
+	}))
+	.unwrap();
+	let err = install_profile(&client, profile, &temp.path().join("libraries"), &versions_dir).await.unwrap_err();
+	assert!(matches!(err, crate::loader::InstallLoaderError::InvalidId(ref it) if &**it == "../escape"));
+	assert!(!temp.path().join("escape").exists());
+}
@@ -0,0 +1,212 @@
+use std::io::{Cursor, Write};
+
+use mockito::Server;
+use serde_json::{json, Value};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use crate::{
+	download::{self, DownloadError},
+	forge::Forge,
+	neoforge::{InstallerError, NeoForge},
+};
+
+fn jar(entries: &[(&str, &[u8])]) -> Vec<u8> {
+	let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+	for (name, content) in entries {
+		writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+		writer.write_all(content).unwrap();
+	}
+	writer.finish().unwrap().into_inner()
+}
+
+#[tokio::test]
+async fn list_forge_builds() {
+	let mut server = Server::new_async().await;
+	let mock = server
+		.mock("GET", "/net/minecraftforge/forge/maven-metadata.json")
+		.with_body(json!({ "1.20.6": ["1.20.6-50.0.0", "1.20.6-50.1.0"], "1.20.4": ["1.20.4-49.0.0"] }).to_string())
+		.expect(2)
+		.create_async()
+		.await;
+
+	let client = reqwest::Client::new();
+	let forge = Forge::new(server.url());
+
+	let builds = forge.builds_for(&client, "1.20.6").await.unwrap();
+	assert_eq!(&*builds, ["1.20.6-50.0.0".into(), "1.20.6-50.1.0".into()]);
+	assert!(forge.builds_for(&client, "1.7.10").await.unwrap().is_empty());
+
+	mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn list_neoforge_builds() {
+	let mut server = Server::new_async().await;
+	server
+		.mock("GET", "/api/maven/versions/releases/net/neoforged/neoforge")
+		.with_body(
+			json!({ "isSnapshot": false, "versions": ["20.6.1-beta", "20.6.119", "21.0.10-beta", "21.1.1"] }).to_string(),
+		)
+		.create_async()
+		.await;
+
+	let client = reqwest::Client::new();
+	let neoforge = NeoForge::new(server.url());
+
+	assert_eq!(&*neoforge.builds_for(&client, "1.20.6").await.unwrap(), ["20.6.1-beta".into(), "20.6.119".into()]);
+	assert_eq!(&*neoforge.builds_for(&client, "1.21").await.unwrap(), ["21.0.10-beta".into()]);
+	assert!(neoforge.builds_for(&client, "24w21b").await.unwrap().is_empty());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn install_forge() {
+	use std::os::unix::fs::PermissionsExt;
+
+	use tokio::fs;
+
+	const CLEAN: &[u8] = b"clean";
+	const PATCH: &[u8] = b"+patch";
+	const UNIVERSAL: &[u8] = b"universal";
+
+	let mut server = Server::new_async().await;
+	let build = "1.20.6-50.1.0";
+
+	let patcher = jar(&[(
+		"META-INF/MANIFEST.MF",
+		b"Manifest-Version: 1.0\nMain-Class: net.minecraftforge.binarypatcher.ConsoleTool\n",
+	)]);
+	let patcher_sha1 = download::sha1(futures_lite::io::Cursor::new(&patcher)).await.unwrap();
+	let output_sha1 = download::sha1(futures_lite::io::Cursor::new([CLEAN, PATCH].concat())).await.unwrap();
+
+	let profile = json!({
+		"spec": 1,
+		"profile": "forge",
+		"version": "1.20.6-forge-50.1.0",
+		"minecraft": "1.20.6",
+		"json": "/version.json",
+		"data": {
+			"BINPATCH": { "client": "/data/client.lzma", "server": "/data/server.lzma" },
+			"PATCHED": { "client": "[net.minecraftforge:forge:1.20.6-50.1.0:client]", "server": "[net.minecraftforge:forge:1.20.6-50.1.0:server]" },
+			"PATCHED_SHA": { "client": format!("'{output_sha1}'"), "server": "''" }
+		},
+		"processors": [
+			{ "sides": ["server"], "jar": "net.minecraftforge:installertools:1.4.1", "args": ["--task", "EXTRACT_SERVER"] },
+			{
+				"jar": "net.minecraftforge:binarypatcher:1.1.1",
+				"classpath": [],
+				"args": ["--clean", "{MINECRAFT_JAR}", "--output", "{PATCHED}", "--apply", "{BINPATCH}"],
+				"outputs": { "{PATCHED}": "{PATCHED_SHA}" }
+			}
+		],
+		"libraries": [{
+			"name": "net.minecraftforge:binarypatcher:1.1.1",
+			"downloads": { "artifact": {
+				"path": "net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1.jar",
+				"url": format!("{}/net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1.jar", server.url()),
+				"sha1": patcher_sha1,
+				"size": patcher.len()
+			}}
+		}]
+	});
+
+	let version = json!({
+		"id": "1.20.6-forge-50.1.0",
+		"inheritsFrom": "1.20.6",
+		"type": "release",
+		"mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher",
+		"arguments": { "game": ["--launchTarget", "forgeclient"], "jvm": ["-DlibraryDirectory=${library_directory}"] },
+		"libraries": [{
+			"name": "net.minecraftforge:forge:1.20.6-50.1.0:universal",
+			"downloads": { "artifact": {
+				"path": "net/minecraftforge/forge/1.20.6-50.1.0/forge-1.20.6-50.1.0-universal.jar",
+				"url": "",
+				"sha1": download::sha1(futures_lite::io::Cursor::new(UNIVERSAL)).await.unwrap(),
+				"size": UNIVERSAL.len()
+			}}
+		}],
+		"logging": {}
+	});
+
+	let installer = jar(&[
+		("install_profile.json", profile.to_string().as_bytes()),
+		("version.json", version.to_string().as_bytes()),
+		("data/client.lzma", PATCH),
+		("maven/net/minecraftforge/forge/1.20.6-50.1.0/forge-1.20.6-50.1.0-universal.jar", UNIVERSAL),
+	]);
+
+	let installer_sha1 = download::sha1(&installer[..]).await.unwrap();
+	server
+		.mock("GET", "/net/minecraftforge/forge/1.20.6-50.1.0/forge-1.20.6-50.1.0-installer.jar")
+		.with_body(installer)
+		.create_async()
+		.await;
+	let checksum = server
+		.mock("GET", "/net/minecraftforge/forge/1.20.6-50.1.0/forge-1.20.6-50.1.0-installer.jar.sha1")
+		.with_body(installer_sha1)
+		.create_async()
+		.await;
+	server
+		.mock("GET", "/net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1.jar")
+		.with_body(patcher)
+		.create_async()
+		.await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let libraries_dir = temp.path().join("libraries");
+	let versions_dir = temp.path().join("versions");
+	fs::create_dir_all(versions_dir.join("1.20.6")).await.unwrap();
+	fs::write(versions_dir.join("1.20.6/1.20.6.jar"), CLEAN).await.unwrap();
+
+	// Emulates the binary patcher: `java -cp <cp> <main> --clean A --output B
+	// --apply C`
+	let java = temp.path().join("java");
+	fs::write(
+		&java,
+		"#!/bin/sh\n[ \"$3\" = \"net.minecraftforge.binarypatcher.ConsoleTool\" ] || exit 2\nmkdir -p \"$(dirname \"$7\")\"\ncat \"$5\" \"$9\" > \"$7\"\n",
+	)
+	.await
+	.unwrap();
+	fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).await.unwrap();
+
+	let client = reqwest::Client::new();
+	let manifest = Forge::new(server.url()).install(&client, build, &libraries_dir, &versions_dir, &java).await.unwrap();
+
+	assert_eq!(&*manifest.id, "1.20.6-forge-50.1.0");
+	assert_eq!(&*manifest.inherits_from, "1.20.6");
+
+	let client_jar = libraries_dir.join("net/minecraftforge/forge/1.20.6-50.1.0/forge-1.20.6-50.1.0-client.jar");
+	assert_eq!(fs::read(client_jar).await.unwrap(), [CLEAN, PATCH].concat());
+
+	let universal = libraries_dir.join("net/minecraftforge/forge/1.20.6-50.1.0/forge-1.20.6-50.1.0-universal.jar");
+	assert_eq!(fs::read(universal).await.unwrap(), UNIVERSAL);
+
+	let saved = versions_dir.join("1.20.6-forge-50.1.0/1.20.6-forge-50.1.0.json");
+	let saved: Value = serde_json::from_slice(&fs::read(saved).await.unwrap()).unwrap();
+	assert_eq!(saved["mainClass"], "cpw.mods.bootstraplauncher.BootstrapLauncher");
+	assert!(!fs::try_exists(versions_dir.join("1.20.6-forge-50.1.0/.installer")).await.unwrap());
+	checksum.assert_async().await;
+}
+
+#[tokio::test]
+async fn rejects_tampered_installer() {
+	let mut server = Server::new_async().await;
+	let path = "/releases/net/neoforged/neoforge/20.6.119/neoforge-20.6.119-installer.jar";
+	server.mock("GET", path).with_body(jar(&[("install_profile.json", b"{}")])).create_async().await;
+	server
+		.mock("GET", &*format!("{path}.sha1"))
+		.with_body("0000000000000000000000000000000000000000")
+		.create_async()
+		.await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let libraries_dir = temp.path().join("libraries");
+	let java = temp.path().join("java");
+	let result = NeoForge::new(server.url())
+		.install(&reqwest::Client::new(), "20.6.119", &libraries_dir, &temp.path().join("versions"), &java)
+		.await;
+
+	assert!(matches!(result, Err(InstallerError::Download(DownloadError::HashMismatch { .. }))));
+	let installer = libraries_dir.join("net/neoforged/neoforge/20.6.119/neoforge-20.6.119-installer.jar");
+	assert!(!installer.exists());
+}
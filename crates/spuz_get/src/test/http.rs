@@ -0,0 +1,94 @@
+use std::io;
+
+use futures_lite::{io::Cursor, AsyncReadExt};
+use mockito::Server;
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+	client::{fetch_json, fetch_stream},
+	http::{Http, HttpError},
+	Body, Client, Error,
+};
+
+async fn read(mut body: Body) -> io::Result<Vec<u8>> {
+	let mut content = Vec::new();
+	body.read_to_end(&mut content).await?;
+	Ok(content)
+}
+
+#[tokio::test]
+async fn statuses_are_errors() {
+	let mut server = Server::new_async().await;
+	server.mock("GET", "/missing.json").with_status(404).with_body("<html>not found</html>").create_async().await;
+	let url: Url = format!("{}/missing.json", server.url()).parse().unwrap();
+	let client = reqwest::Client::new();
+
+	let result: Result<Value, Error> = async { Ok(fetch_json(&client, url.clone()).await?) }.await;
+	assert!(matches!(result, Err(Error::Status { url: ref it, status: 404, .. }) if *it == url));
+
+	let result: Result<Body, Error> = async { Ok(fetch_stream(&client, url.clone()).await?) }.await;
+	assert!(matches!(result, Err(Error::Status { status: 404, .. })));
+}
+
+#[tokio::test]
+async fn streams_have_content_length() {
+	let mut server = Server::new_async().await;
+	server.mock("GET", "/object").with_body("content").create_async().await;
+	let url: Url = format!("{}/object", server.url()).parse().unwrap();
+
+	let mut body = reqwest::Client::new().get_stream(url).await.unwrap();
+	assert_eq!(body.content_length(), Some(7));
+
+	let mut content = Vec::new();
+	body.read_to_end(&mut content).await.unwrap();
+	assert_eq!(content, b"content");
+	assert_eq!(body.bytes_read(), 7);
+}
+
+#[tokio::test]
+async fn body_checks_length() {
+	let body = Body::new(Cursor::new(b"con".to_vec()), Some(7));
+	assert_eq!(read(body).await.unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+
+	let body = Body::new(Cursor::new(b"content".to_vec()), Some(3));
+	assert_eq!(read(body).await.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+	let body = Body::new(Cursor::new(b"content".to_vec()), None).limit(3);
+	assert_eq!(read(body).await.unwrap_err().kind(), io::ErrorKind::FileTooLarge);
+}
+
+#[tokio::test]
+async fn limits_size() {
+	let mut server = Server::new_async().await;
+	server.mock("GET", "/sized").with_body("[1, 2, 3, 4]").create_async().await;
+	server.mock("GET", "/chunked").with_chunked_body(|it| it.write_all(b"[1, 2, 3, 4]")).create_async().await;
+	let client = Http::builder().max_size(4).build().unwrap();
+
+	let url: Url = format!("{}/sized", server.url()).parse().unwrap();
+	let result = client.get_stream(url.clone()).await;
+	assert!(matches!(result, Err(HttpError::TooLarge { limit: 4, .. })));
+	let result = client.get_json::<Value>(url).await;
+	assert!(matches!(result, Err(HttpError::TooLarge { .. })));
+
+	let url: Url = format!("{}/chunked", server.url()).parse().unwrap();
+	let body = client.get_stream(url.clone()).await.unwrap();
+	assert_eq!(read(body).await.unwrap_err().kind(), io::ErrorKind::FileTooLarge);
+	let result = client.get_json::<Value>(url).await;
+	assert!(matches!(result, Err(HttpError::TooLarge { .. })));
+}
+
+#[tokio::test]
+async fn follows_configured_redirects() {
+	let mut server = Server::new_async().await;
+	server.mock("GET", "/moved").with_status(302).with_header("Location", "/list.json").create_async().await;
+	server.mock("GET", "/list.json").with_body("[]").create_async().await;
+	let url: Url = format!("{}/moved", server.url()).parse().unwrap();
+
+	let value: Value = Http::builder().build().unwrap().get_json(url.clone()).await.unwrap();
+	assert_eq!(value, Value::Array(Vec::new()));
+
+	let client = Http::builder().redirects(0).build().unwrap();
+	let result: Result<Value, Error> = async { Ok(fetch_json(&client, url.clone()).await?) }.await;
+	assert!(matches!(result, Err(Error::Status { status: 302, .. })));
+}
@@ -0,0 +1,104 @@
+use spuz_piston::Manifest;
+use tokio::fs;
+
+use crate::{
+	json_resource::{JsonResourceParseError, JsonResourceSaveError},
+	testing::MockClient,
+	vanilla,
+};
+
+const MANIFEST: &str = include_str!("../../../../versions/1.20.6.json");
+/// sha1 of the 1.20.6 fixture
+const HASH: &str = "e549ebaa9c8111c32c7341af92e809b8a5204590";
+const PACKAGE: &str = "https://piston-meta.mojang.com/v1/packages/e549ebaa9c8111c32c7341af92e809b8a5204590/1.20.6.json";
+
+#[tokio::test]
+async fn parse_and_tee() {
+	let client = MockClient::new().with(PACKAGE, MANIFEST);
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("1.20.6.json");
+
+	let manifest: Manifest = vanilla::package(&client, HASH, "1.20.6")
+		.await
+		.unwrap()
+		.verify(HASH.to_ascii_uppercase())
+		.tee(&path)
+		.json()
+		.await
+		.unwrap();
+
+	assert_eq!(&*manifest.id, "1.20.6");
+	assert_eq!(fs::read_to_string(&path).await.unwrap(), MANIFEST);
+	assert!(!fs::try_exists(temp.path().join("1.20.6.json.part")).await.unwrap());
+}
+
+#[tokio::test]
+async fn verify_parsed() {
+	let client = MockClient::new().with(PACKAGE, MANIFEST);
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("1.20.6.json");
+
+	let result = vanilla::package::<Manifest, _>(&client, HASH, "1.20.6")
+		.await
+		.unwrap()
+		.verify("0000000000000000000000000000000000000000")
+		.tee(&path)
+		.json()
+		.await;
+
+	assert!(matches!(result, Err(JsonResourceParseError::HashMismatch { .. })));
+	assert!(!fs::try_exists(&path).await.unwrap());
+	assert!(!fs::try_exists(temp.path().join("1.20.6.json.part")).await.unwrap());
+}
+
+#[tokio::test]
+async fn truncated_stream() {
+	let client = MockClient::new().with(PACKAGE, MANIFEST).truncate(PACKAGE, 1000);
+
+	let result = vanilla::package::<Manifest, _>(&client, HASH, "1.20.6").await.unwrap().json().await;
+	assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn invalid_json() {
+	let client = MockClient::new().with(PACKAGE, format!("{MANIFEST} trailing"));
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("1.20.6.json");
+
+	let result = vanilla::package::<Manifest, _>(&client, HASH, "1.20.6").await.unwrap().json().await;
+	assert!(matches!(result, Err(JsonResourceParseError::HashMismatch { .. })));
+
+	let mut package = vanilla::package::<Manifest, _>(&client, HASH, "1.20.6").await.unwrap();
+	package.sha1 = None;
+	let result = package.tee(&path).json().await;
+	assert!(matches!(result, Err(JsonResourceParseError::Parse(_))));
+	assert!(!fs::try_exists(temp.path().join("1.20.6.json.part")).await.unwrap());
+}
+
+#[tokio::test]
+async fn verify_saved() {
+	let client = MockClient::new().with(PACKAGE, MANIFEST);
+	let temp = tempfile::tempdir().unwrap();
+	let path = temp.path().join("1.20.6.json");
+
+	let package = vanilla::package::<Manifest, _>(&client, HASH, "1.20.6").await.unwrap();
+	package.verify(HASH.to_ascii_uppercase()).save(&path).await.unwrap();
+	assert_eq!(fs::read_to_string(&path).await.unwrap(), MANIFEST);
+
+	let package = vanilla::package::<Manifest, _>(&client, HASH, "1.20.6").await.unwrap();
+	let result = package.verify("0000000000000000000000000000000000000000").save(temp.path().join("other.json")).await;
+	assert!(matches!(result, Err(JsonResourceSaveError::HashMismatch { .. })));
+}
+
+#[tokio::test]
+async fn packages_are_verified() {
+	const OTHER: &str = "https://piston-meta.mojang.com/v1/packages/0000000000000000000000000000000000000000/1.20.6.json";
+	let client = MockClient::new().with(OTHER, MANIFEST);
+
+	let result = vanilla::package::<Manifest, _>(&client, "0000000000000000000000000000000000000000", "1.20.6")
+		.await
+		.unwrap()
+		.json()
+		.await;
+	assert!(matches!(result, Err(JsonResourceParseError::HashMismatch { .. })));
+}
@@ -0,0 +1,95 @@
+use futures_lite::AsyncReadExt;
+use serde_json::json;
+use spuz_piston::Manifest;
+use tokio::fs;
+use url::Url;
+
+use crate::{
+	download,
+	fabric::Fabric,
+	local::{LocalClient, LocalError},
+	vanilla, Client, FetchError,
+};
+
+/// sha1 of the 1.20.6 fixture
+const HASH: &str = "e549ebaa9c8111c32c7341af92e809b8a5204590";
+const LOADER_JAR: &[u8] = b"fabric-loader";
+
+async fn write(path: impl AsRef<std::path::Path>, content: impl AsRef<[u8]>) {
+	fs::create_dir_all(path.as_ref().parent().unwrap()).await.unwrap();
+	fs::write(path, content).await.unwrap();
+}
+
+#[tokio::test]
+async fn serves_mirror_directory() {
+	let temp = tempfile::tempdir().unwrap();
+	let mirror = temp.path().join("mirror");
+	let meta = temp.path().join("meta");
+
+	write(meta.join(format!("v1/packages/{HASH}/1.20.6.json")), include_str!("../../../../versions/1.20.6.json")).await;
+	write(mirror.join("resources.download.minecraft.net/ab/abcdef"), "resource").await;
+
+	let client = LocalClient::mirror_of(&mirror).with("piston-meta.mojang.com", &meta);
+
+	let manifest: Manifest = vanilla::package(&client, HASH, "1.20.6").await.unwrap().json().await.unwrap();
+	assert_eq!(&*manifest.id, "1.20.6");
+
+	let mut content = String::new();
+	vanilla::resource(&client, "abcdef").await.unwrap().read_to_string(&mut content).await.unwrap();
+	assert_eq!(content, "resource");
+
+	let file = Url::from_file_path(mirror.join("resources.download.minecraft.net/ab/abcdef")).unwrap();
+	let mut content = String::new();
+	client.get_stream(file).await.unwrap().read_to_string(&mut content).await.unwrap();
+	assert_eq!(content, "resource");
+
+	let result = vanilla::list(&client).await;
+	assert!(matches!(result, Err(FetchError::Client { source: LocalError::NotFound(_), .. })));
+
+	let result = vanilla::resource(&LocalClient::new(), "abcdef").await;
+	assert!(matches!(result, Err(FetchError::Client { source: LocalError::Unmapped(_), .. })));
+}
+
+#[test]
+fn maps_decoded_segments() {
+	let client = LocalClient::mirror_of("/mirror");
+
+	let url = "https://example.com/my%20lib/1.0.jar".parse().unwrap();
+	assert_eq!(client.path(&url).unwrap(), std::path::Path::new("/mirror/example.com/my lib/1.0.jar"));
+
+	let url = "https://example.com/..%2F..%2Fsecret".parse().unwrap();
+	assert_eq!(client.path(&url), None);
+	let url = "https://example.com/a/%2E%2E%5Csecret".parse().unwrap();
+	assert_eq!(client.path(&url), None);
+}
+
+#[tokio::test]
+async fn installs_loader() {
+	let temp = tempfile::tempdir().unwrap();
+	let mirror = temp.path().join("mirror");
+
+	let profile = json!({
+		"id": "fabric-loader-0.15.11-1.20.6",
+		"inheritsFrom": "1.20.6",
+		"mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+		"libraries": [{
+			"name": "net.fabricmc:fabric-loader:0.15.11",
+			"url": "https://maven.fabricmc.net/",
+			"sha1": download::sha1(LOADER_JAR).await.unwrap()
+		}]
+	});
+	write(mirror.join("meta.fabricmc.net/v2/versions/loader/1.20.6/0.15.11/profile/json"), profile.to_string()).await;
+	write(mirror.join("maven.fabricmc.net/net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar"), LOADER_JAR)
+		.await;
+
+	let libraries_dir = temp.path().join("libraries");
+	let versions_dir = temp.path().join("versions");
+	let manifest = Fabric::default()
+		.install(&LocalClient::mirror_of(&mirror), "1.20.6", "0.15.11", &libraries_dir, &versions_dir)
+		.await
+		.unwrap();
+
+	assert_eq!(&*manifest.id, "fabric-loader-0.15.11-1.20.6");
+	let jar = libraries_dir.join("net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar");
+	assert_eq!(fs::read(jar).await.unwrap(), LOADER_JAR);
+}
@@ -0,0 +1,71 @@
+use futures_lite::AsyncReadExt;
+use url::Url;
+
+use crate::{
+	mirror::{HostMap, Mirror, Mirrored},
+	testing::MockClient,
+	Client,
+};
+
+const RESOURCE: &str = "https://resources.download.minecraft.net/ab/abcdef";
+
+async fn read<C: Client>(client: &C, url: &str) -> Result<String, C::Error> {
+	let mut content = String::new();
+	client.get_stream(url.parse().unwrap()).await?.read_to_string(&mut content).await.unwrap();
+	Ok(content)
+}
+
+#[test]
+fn rewrite_hosts() {
+	let mirror = HostMap::bmclapi();
+
+	let url = mirror.rewrite(&RESOURCE.parse().unwrap()).unwrap();
+	assert_eq!(url.as_str(), "https://bmclapi2.bangbang93.com/assets/ab/abcdef");
+
+	let url = mirror.rewrite(&"https://libraries.minecraft.net/a/b/1/b-1.jar?x=1".parse().unwrap()).unwrap();
+	assert_eq!(url.as_str(), "https://bmclapi2.bangbang93.com/maven/a/b/1/b-1.jar?x=1");
+
+	assert!(mirror.rewrite(&"https://example.com/a".parse().unwrap()).is_none());
+}
+
+#[tokio::test]
+async fn fallback_in_order() {
+	let client = MockClient::new().with("https://second.mirror/assets/ab/abcdef", "second").with(RESOURCE, "origin");
+
+	let mirrored = Mirrored::new(client)
+		.with(HostMap::mirror_of("https://first.mirror/"))
+		.with(HostMap::mirror_of("https://second.mirror"));
+	assert_eq!(read(&mirrored, RESOURCE).await.unwrap(), "second");
+
+	let mirrored = Mirrored { mirrors: vec![Box::new(HostMap::mirror_of("https://first.mirror"))], ..mirrored };
+	assert_eq!(read(&mirrored, RESOURCE).await.unwrap(), "origin");
+}
+
+#[tokio::test]
+async fn without_origin() {
+	let client = MockClient::new().with(RESOURCE, "origin").with("https://example.com/a", "unmapped");
+	let mirrored = Mirrored::new(client).with(HostMap::mirror_of("https://first.mirror")).without_origin();
+
+	assert!(read(&mirrored, RESOURCE).await.is_err());
+	assert_eq!(read(&mirrored, "https://example.com/a").await.unwrap(), "unmapped");
+}
+
+#[tokio::test]
+async fn closure_mirror() {
+	let client = MockClient::new().with("https://cache.local/ab/abcdef", "cached");
+	let mirrored = Mirrored::new(client).with(|url: &Url| Url::parse("https://cache.local").ok()?.join(url.path()).ok());
+
+	assert_eq!(read(&mirrored, RESOURCE).await.unwrap(), "cached");
+}
+
+#[test]
+fn candidates_are_unique() {
+	let mirrored = Mirrored::new(MockClient::new())
+		.with(HostMap::mirror_of("https://first.mirror"))
+		.with(|url: &Url| Some(url.clone()))
+		.with(HostMap::mirror_of("https://first.mirror"));
+
+	let candidates = mirrored.candidates(&RESOURCE.parse().unwrap());
+	let candidates: Vec<_> = candidates.iter().map(Url::as_str).collect();
+	assert_eq!(candidates, ["https://first.mirror/assets/ab/abcdef", RESOURCE]);
+}
@@ -0,0 +1,47 @@
+#![allow(clippy::unwrap_used)]
+
+#[cfg(all(feature = "blocking", feature = "ureq", feature = "vanilla"))]
+mod blocking;
+#[cfg(all(feature = "reqwest", feature = "testing"))]
+mod cache;
+#[cfg(all(feature = "curseforge", feature = "reqwest"))]
+mod curseforge;
+#[cfg(all(feature = "tokio", feature = "vanilla"))]
+mod err;
+#[cfg(feature = "fabric")]
+mod fabric;
+#[cfg(all(feature = "forge", feature = "neoforge"))]
+mod forge;
+#[cfg(feature = "reqwest")]
+mod http;
+#[cfg(all(feature = "tokio", feature = "vanilla"))]
+mod json_resource;
+#[cfg(all(feature = "vanilla", feature = "fabric"))]
+mod local;
+mod mirror;
+#[cfg(any(feature = "modrinth", feature = "curseforge"))]
+mod modpack;
+#[cfg(all(feature = "modrinth", feature = "reqwest"))]
+mod modrinth;
+#[cfg(feature = "modrinth")]
+mod mrpack;
+#[cfg(all(feature = "tokio", feature = "vanilla"))]
+mod offline;
+#[cfg(feature = "quilt")]
+mod quilt;
+#[cfg(all(feature = "tokio", feature = "vanilla"))]
+mod repair;
+#[cfg(all(feature = "tokio", feature = "vanilla"))]
+mod runtime;
+#[cfg(all(feature = "skins", feature = "reqwest"))]
+mod skins;
+#[cfg(feature = "tokio")]
+mod testing;
+
+/// Folder settled in a fresh temporary directory
+#[cfg(any(feature = "modrinth", feature = "curseforge"))]
+async fn folder() -> (tempfile::TempDir, std::sync::Arc<spuz_folder::Folder>) {
+	let temp = tempfile::tempdir().unwrap();
+	let folder = spuz_folder::Folder::settle(temp.path()).await.unwrap();
+	(temp, folder)
+}
@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+use spuz_piston::rule::RuleCompilance;
+
+use super::folder;
+use crate::{
+	download,
+	modpack::{install_version, LoaderKind},
+	testing::MockClient,
+	vanilla::{self, RESOURCES},
+};
+
+const MANIFEST: &str = include_str!("../../../../versions/1.20.6.json");
+const CLIENT: &[u8] = b"client";
+const LIBRARY: &[u8] = b"library";
+const LOG_CONFIG: &[u8] = b"<Configuration/>";
+const SOUND: &[u8] = b"sound";
+const LOADER: &[u8] = b"fabric-loader";
+
+const CLIENT_URL: &str = "https://piston-data.mojang.com/client.jar";
+const LIBRARY_URL: &str = "https://libraries.minecraft.net/com/example/library/1.0/library-1.0.jar";
+const LOG_CONFIG_URL: &str = "https://piston-data.mojang.com/client-1.12.xml";
+const INDEX_URL: &str = "https://piston-meta.mojang.com/index/16.json";
+const PROFILE_URL: &str = "https://meta.fabricmc.net/v2/versions/loader/1.20.6/0.15.11/profile/json";
+const LOADER_URL: &str = "https://maven.fabricmc.net/net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar";
+
+async fn sha1(content: &[u8]) -> String {
+	download::sha1(content).await.unwrap()
+}
+
+/// Serves the files of a trimmed down 1.20.6 and fabric 0.15.11 for it
+pub(super) async fn game(client: MockClient) -> MockClient {
+	let sound = sha1(SOUND).await;
+	let index =
+		json!({ "objects": { "minecraft/sounds/ambient.ogg": { "hash": sound, "size": SOUND.len() } } }).to_string();
+
+	let mut manifest: Value = serde_json::from_str(MANIFEST).unwrap();
+	manifest["downloads"]["client"] = json!({ "sha1": sha1(CLIENT).await, "size": CLIENT.len(), "url": CLIENT_URL });
+	manifest["logging"]["client"]["file"] =
+		json!({ "id": "client-1.12.xml", "sha1": sha1(LOG_CONFIG).await, "size": LOG_CONFIG.len(), "url": LOG_CONFIG_URL });
+	manifest["assetIndex"] = json!({ "id": "16", "sha1": sha1(index.as_bytes()).await, "size": index.len(), "totalSize": SOUND.len(), "url": INDEX_URL });
+	manifest["libraries"] = json!([{
+		"name": "com.example:library:1.0",
+		"downloads": {
+			"artifact": { "path": "com/example/library/1.0/library-1.0.jar", "sha1": sha1(LIBRARY).await, "size": LIBRARY.len(), "url": LIBRARY_URL }
+		}
+	}]);
+	let manifest = manifest.to_string();
+	let hash = sha1(manifest.as_bytes()).await;
+
+	let list = json!({
+		"latest": { "release": "1.20.6", "snapshot": "1.20.6" },
+		"versions": [{ "type": "release", "id": "1.20.6", "sha1": hash, "url": vanilla::package_url(&hash, "1.20.6").unwrap().as_str() }]
+	});
+	let profile = json!({
+		"id": "fabric-loader-0.15.11-1.20.6",
+		"inheritsFrom": "1.20.6",
+		"releaseTime": "2024-05-09T10:52:01+0000",
+		"time": "2024-05-09T10:52:01+0000",
+		"type": "release",
+		"mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+		"arguments": { "game": [], "jvm": [] },
+		"libraries": [
+			{ "name": "net.fabricmc:fabric-loader:0.15.11", "url": "https://maven.fabricmc.net/", "sha1": sha1(LOADER).await, "size": LOADER.len() }
+		]
+	});
+
+	client
+		.with_json(vanilla::list_url().unwrap().as_str(), &list)
+		.with(vanilla::package_url(&hash, "1.20.6").unwrap().as_str(), manifest)
+		.with(CLIENT_URL, CLIENT)
+		.with(LIBRARY_URL, LIBRARY)
+		.with(LOG_CONFIG_URL, LOG_CONFIG)
+		.with(INDEX_URL, index)
+		.with(&format!("{RESOURCES}/{}/{sound}", &sound[..2]), SOUND)
+		.with_json(PROFILE_URL, &profile)
+		.with(LOADER_URL, LOADER)
+}
+
+#[tokio::test]
+async fn installs_game_and_loader() {
+	let (_temp, folder) = folder().await;
+	let client = game(MockClient::new()).await;
+
+	let loader = Some((LoaderKind::Fabric, "0.15.11"));
+	let id = install_version(&client, &folder, "1.20.6", loader, "java".as_ref()).await.unwrap();
+	assert_eq!(&*id, "fabric-loader-0.15.11-1.20.6");
+
+	let version = folder.versions.get("1.20.6");
+	let manifest = version.manifest().await.unwrap().unwrap();
+	assert!(folder
+		.verify(&manifest, version.client_path(), &RuleCompilance::new(HashSet::new()))
+		.await
+		.unwrap()
+		.is_complete());
+	assert!(folder.versions.get(&id).manifest_path().exists());
+	assert_eq!(client.requested(LOADER_URL), 1);
+
+	// Installed files are not requested again
+	install_version(&client, &folder, "1.20.6", loader, "java".as_ref()).await.unwrap();
+	assert_eq!(client.requested(CLIENT_URL), 1);
+	assert_eq!(client.requested(vanilla::list_url().unwrap().as_str()), 1);
+}
+
+#[tokio::test]
+async fn rejects_unknown_versions() {
+	let (_temp, folder) = folder().await;
+	let client = game(MockClient::new()).await;
+
+	let err = install_version(&client, &folder, "1.0", None, "java".as_ref()).await.unwrap_err();
+	assert_eq!(err.to_string(), "Version 1.0 is not in the version list");
+}
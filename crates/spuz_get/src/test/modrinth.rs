@@ -0,0 +1,213 @@
+use mockito::{Matcher, Server, ServerGuard};
+use serde_json::{json, Value};
+
+use crate::{
+	download,
+	modrinth::{DependencyType, Facet, HashAlgorithm, Modrinth, ModrinthError, SearchQuery, SortIndex, VersionQuery},
+};
+
+const JAR: &[u8] = b"sodium";
+
+fn version(id: &str, project: &str, dependencies: &Value, url: &str, sha1: &str) -> Value {
+	json!({
+		"id": id,
+		"project_id": project,
+		"name": format!("{project} {id}"),
+		"version_number": id,
+		"version_type": "release",
+		"game_versions": ["1.20.6"],
+		"loaders": ["fabric"],
+		"featured": true,
+		"dependencies": dependencies,
+		"files": [
+			{ "url": format!("{url}/extra.jar"), "filename": "extra.jar", "primary": false, "size": 1, "hashes": { "sha1": "00" } },
+			{ "url": format!("{url}/{id}.jar"), "filename": format!("{id}.jar"), "primary": true, "size": 6, "hashes": { "sha1": sha1, "sha512": "ff" } }
+		]
+	})
+}
+
+fn modrinth(server: &ServerGuard) -> Modrinth {
+	Modrinth::new(format!("{}/v2", server.url()))
+}
+
+#[tokio::test]
+async fn search_with_facets() {
+	let mut server = Server::new_async().await;
+	let search = server
+		.mock("GET", "/v2/search")
+		.match_query(Matcher::AllOf(vec![
+			Matcher::UrlEncoded("query".into(), "sodium".into()),
+			Matcher::UrlEncoded(
+				"facets".into(),
+				r#"[["versions:1.20.6"],["categories:fabric","categories:quilt"],["project_type:mod"]]"#.into(),
+			),
+			Matcher::UrlEncoded("index".into(), "downloads".into()),
+			Matcher::UrlEncoded("limit".into(), "5".into()),
+		]))
+		.with_body(
+			json!({
+				"hits": [{
+					"project_id": "AANobbMI",
+					"slug": "sodium",
+					"project_type": "mod",
+					"title": "Sodium",
+					"description": "Rendering engine",
+					"categories": ["fabric", "optimization"],
+					"versions": ["1.20.6"],
+					"downloads": 100
+				}],
+				"offset": 0,
+				"limit": 5,
+				"total_hits": 1
+			})
+			.to_string(),
+		)
+		.create_async()
+		.await;
+
+	let query = SearchQuery::new("sodium")
+		.facet(Facet::GameVersion("1.20.6".into()))
+		.any_of([Facet::Loader("fabric".into()), Facet::Loader("quilt".into())])
+		.facet(Facet::ProjectType("mod".into()))
+		.index(SortIndex::Downloads)
+		.limit(5);
+	let results = modrinth(&server).search(&reqwest::Client::new(), &query).await.unwrap();
+
+	assert_eq!(results.total_hits, 1);
+	assert_eq!(&*results.hits[0].project_id, "AANobbMI");
+	assert_eq!(results.hits[0].downloads, Some(100));
+	search.assert_async().await;
+}
+
+#[tokio::test]
+async fn versions_and_files() {
+	let mut server = Server::new_async().await;
+	let sha1 = download::sha1(JAR).await.unwrap();
+	let body = json!([version("abc", "sodium", &json!([]), &server.url(), &sha1)]).to_string();
+	let versions = server
+		.mock("GET", "/v2/project/sodium/version")
+		.match_query(Matcher::AllOf(vec![
+			Matcher::UrlEncoded("loaders".into(), r#"["fabric"]"#.into()),
+			Matcher::UrlEncoded("game_versions".into(), r#"["1.20.6"]"#.into()),
+		]))
+		.with_body(body)
+		.create_async()
+		.await;
+	let by_hash = server
+		.mock("GET", format!("/v2/version_file/{sha1}").as_str())
+		.match_query(Matcher::UrlEncoded("algorithm".into(), "sha1".into()))
+		.with_body(version("abc", "sodium", &json!([]), &server.url(), &sha1).to_string())
+		.create_async()
+		.await;
+	server.mock("GET", "/abc.jar").with_body(JAR).create_async().await;
+
+	let client = reqwest::Client::new();
+	let modrinth = modrinth(&server);
+	let query = VersionQuery::new().loader("fabric").game_version("1.20.6");
+
+	let versions_list = modrinth.versions(&client, "sodium", &query).await.unwrap();
+	let file = versions_list[0].primary_file().unwrap();
+	assert_eq!(&*file.filename, "abc.jar");
+	assert_eq!(file.hashes.sha1, sha1.clone().into());
+
+	let found = modrinth.version_from_hash(&client, &sha1, HashAlgorithm::Sha1).await.unwrap();
+	assert_eq!(found, versions_list[0]);
+
+	let temp = tempfile::tempdir().unwrap();
+	let (path, checksum) = modrinth.download(&client, file, temp.path()).await.unwrap();
+	assert_eq!(path, temp.path().join("abc.jar"));
+	assert_eq!(checksum.size, 6);
+	assert_eq!(tokio::fs::read(path).await.unwrap(), JAR);
+
+	versions.assert_async().await;
+	by_hash.assert_async().await;
+}
+
+#[tokio::test]
+async fn download_checks_hash() {
+	let mut server = Server::new_async().await;
+	server.mock("GET", "/abc.jar").with_body(JAR).create_async().await;
+	let version: crate::modrinth::Version =
+		serde_json::from_value(version("abc", "sodium", &json!([]), &server.url(), "bad")).unwrap();
+
+	let temp = tempfile::tempdir().unwrap();
+	let result = modrinth(&server).download(&reqwest::Client::new(), version.primary_file().unwrap(), temp.path()).await;
+	assert!(matches!(result, Err(ModrinthError::Download(_))));
+	assert!(!temp.path().join("abc.jar").exists());
+}
+
+#[tokio::test]
+async fn resolves_required_dependencies() {
+	let mut server = Server::new_async().await;
+	let url = server.url();
+	let root = version(
+		"root",
+		"iris",
+		&json!([
+			{ "project_id": "sodium", "dependency_type": "required" },
+			{ "project_id": "modmenu", "dependency_type": "optional" },
+			{ "version_id": "api-1", "project_id": "fabric-api", "dependency_type": "required" }
+		]),
+		&url,
+		"00",
+	);
+	let sodium = version(
+		"sodium-2",
+		"sodium",
+		&json!([{ "project_id": "fabric-api", "dependency_type": "required" }, { "project_id": "iris", "dependency_type": "required" }]),
+		&url,
+		"00",
+	);
+	server
+		.mock("GET", "/v2/project/sodium/version")
+		.match_query(Matcher::Any)
+		.with_body(json!([sodium, version("sodium-1", "sodium", &json!([]), &url, "00")]).to_string())
+		.create_async()
+		.await;
+	let pinned = server
+		.mock("GET", "/v2/version/api-1")
+		.with_body(version("api-1", "fabric-api", &json!([]), &url, "00").to_string())
+		.expect(1)
+		.create_async()
+		.await;
+	let unpinned =
+		server.mock("GET", "/v2/project/fabric-api/version").match_query(Matcher::Any).expect(0).create_async().await;
+
+	let root: crate::modrinth::Version = serde_json::from_value(root).unwrap();
+	assert_eq!(root.dependencies[1].dependency_type, DependencyType::Optional);
+	let query = VersionQuery::new().loader("fabric").game_version("1.20.6");
+	let resolved = modrinth(&server).resolve_dependencies(&reqwest::Client::new(), &root, &query).await.unwrap();
+
+	let ids: Vec<_> = resolved.iter().map(|it| &*it.id).collect();
+	assert_eq!(ids, ["sodium-2", "api-1"]);
+	pinned.assert_async().await;
+	unpinned.assert_async().await;
+}
+
+#[tokio::test]
+async fn unresolved_dependency() {
+	let mut server = Server::new_async().await;
+	server.mock("GET", "/v2/project/sodium/version").match_query(Matcher::Any).with_body("[]").create_async().await;
+	let root: crate::modrinth::Version = serde_json::from_value(version(
+		"root",
+		"iris",
+		&json!([{ "project_id": "sodium", "dependency_type": "required" }]),
+		&server.url(),
+		"00",
+	))
+	.unwrap();
+
+	let result = modrinth(&server).resolve_dependencies(&reqwest::Client::new(), &root, &VersionQuery::new()).await;
+	assert!(matches!(result, Err(ModrinthError::Unresolved(project)) if &*project == "sodium"));
+}
+
+#[tokio::test]
+async fn ids_are_encoded() {
+	let mut server = Server::new_async().await;
+	let project = server.mock("GET", "/v2/project/a%2F..%3Fb").with_status(404).expect(1).create_async().await;
+
+	let result = modrinth(&server).project(&reqwest::Client::new(), "a/..?b").await;
+	assert!(result.is_err());
+
+	project.assert_async().await;
+}
@@ -0,0 +1,172 @@
+use std::io::{Cursor, Write};
+
+use serde_json::{json, Value};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+use super::{folder, modpack::game};
+use crate::{
+	download,
+	modrinth::mrpack::{LoaderKind, Mrpack, MrpackError, OpenMrpackError, Side},
+	testing::MockClient,
+	FetchError,
+};
+
+const SODIUM: &[u8] = b"sodium";
+const SERVER_ONLY: &[u8] = b"server";
+const OPTIONAL: &[u8] = b"optional";
+
+const CDN: &str = "https://cdn.modrinth.com/data";
+
+fn pack(index: &Value, entries: &[(&str, &[u8])]) -> Vec<u8> {
+	let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+	writer.start_file("modrinth.index.json", SimpleFileOptions::default()).unwrap();
+	writer.write_all(index.to_string().as_bytes()).unwrap();
+	for (name, content) in entries {
+		writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+		writer.write_all(content).unwrap();
+	}
+	writer.finish().unwrap().into_inner()
+}
+
+async fn file(path: &str, content: &[u8], downloads: &[String], env: Option<(&str, &str)>) -> Value {
+	let mut file = json!({
+		"path": path,
+		"hashes": { "sha1": download::sha1(content).await.unwrap(), "sha512": "ff" },
+		"downloads": downloads,
+		"fileSize": content.len()
+	});
+	if let Some((client, server)) = env {
+		file["env"] = json!({ "client": client, "server": server });
+	}
+	file
+}
+
+fn index(files: &[Value]) -> Value {
+	json!({
+		"formatVersion": 1,
+		"game": "minecraft",
+		"versionId": "1.0.0",
+		"name": "Pack",
+		"files": files,
+		"dependencies": { "minecraft": "1.20.6", "fabric-loader": "0.15.11" }
+	})
+}
+
+#[tokio::test]
+async fn installs_client_side() {
+	let client = MockClient::new()
+		.with_error(&format!("{CDN}/missing/sodium.jar"), "not found")
+		.with(&format!("{CDN}/sodium.jar"), SODIUM)
+		.with(&format!("{CDN}/optional.jar"), OPTIONAL)
+		.with(&format!("{CDN}/server.jar"), SERVER_ONLY);
+	let client = game(client).await;
+
+	let files = [
+		file("mods/sodium.jar", SODIUM, &[format!("{CDN}/missing/sodium.jar"), format!("{CDN}/sodium.jar")], None).await,
+		file("mods/server.jar", SERVER_ONLY, &[format!("{CDN}/server.jar")], Some(("unsupported", "required"))).await,
+		file("mods/optional.jar", OPTIONAL, &[format!("{CDN}/optional.jar")], Some(("optional", "optional"))).await,
+	];
+	let archive = pack(
+		&index(&files),
+		&[
+			("overrides/config/a.txt", b"common"),
+			("overrides/config/b.txt", b"common"),
+			("client-overrides/config/b.txt", b"client"),
+			("server-overrides/config/c.txt", b"server"),
+		],
+	);
+
+	let (_temp, folder) = folder().await;
+	let pack = Mrpack::from_bytes(archive).unwrap();
+	assert_eq!(pack.loader(), Some((LoaderKind::Fabric, "0.15.11")));
+	assert_eq!(pack.files().count(), 2);
+
+	let instance = pack.install(&client, &folder, "pack").await.unwrap();
+	assert_eq!(&*instance.config.version, "1.20.6");
+	assert_eq!(instance.config.version_id(), "fabric-loader-0.15.11-1.20.6");
+	assert_eq!(folder.instances.get("pack").await.unwrap(), Some(instance.clone()));
+	assert!(folder.versions.get("1.20.6").client_path().exists());
+	assert!(folder.versions.get(&instance.config.version_id()).manifest_path().exists());
+
+	let game_dir = instance.game_dir();
+
+	assert_eq!(std::fs::read(game_dir.join("mods/sodium.jar")).unwrap(), SODIUM);
+	assert_eq!(std::fs::read(game_dir.join("mods/optional.jar")).unwrap(), OPTIONAL);
+	assert!(!game_dir.join("mods/server.jar").exists());
+	assert_eq!(std::fs::read(game_dir.join("config/a.txt")).unwrap(), b"common");
+	assert_eq!(std::fs::read(game_dir.join("config/b.txt")).unwrap(), b"client");
+	assert!(!game_dir.join("config/c.txt").exists());
+	assert_eq!(client.requested(&format!("{CDN}/server.jar")), 0);
+}
+
+#[tokio::test]
+async fn skips_invalid_mirrors() {
+	let client = game(MockClient::new().with(&format!("{CDN}/sodium.jar"), SODIUM)).await;
+	let files = [file("mods/sodium.jar", SODIUM, &["not a url".into(), format!("{CDN}/sodium.jar")], None).await];
+
+	let (_temp, folder) = folder().await;
+	let mrpack = Mrpack::from_bytes(pack(&index(&files), &[])).unwrap();
+	let instance = mrpack.install(&client, &folder, "pack").await.unwrap();
+	assert_eq!(std::fs::read(instance.game_dir().join("mods/sodium.jar")).unwrap(), SODIUM);
+
+	let files = [file("mods/sodium.jar", SODIUM, &["not a url".into()], None).await];
+	let result = Mrpack::from_bytes(pack(&index(&files), &[])).unwrap().install(&client, &folder, "other").await;
+	assert!(matches!(result, Err(MrpackError::Fetch(FetchError::ParseUrl(_)))));
+	assert_eq!(folder.instances.get("other").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn server_side_skips_optional() {
+	let files = [
+		file("mods/client.jar", SODIUM, &[], Some(("required", "unsupported"))).await,
+		file("mods/optional.jar", OPTIONAL, &[], Some(("optional", "optional"))).await,
+		file("mods/server.jar", SERVER_ONLY, &[], Some(("unsupported", "required"))).await,
+	];
+	let pack = Mrpack::from_bytes(pack(&index(&files), &[])).unwrap().side(Side::Server).optional(false);
+
+	let paths: Vec<_> = pack.files().map(|it| &*it.path).collect();
+	assert_eq!(paths, ["mods/server.jar"]);
+}
+
+#[tokio::test]
+async fn verifies_hashes() {
+	let client = MockClient::new().with(&format!("{CDN}/sodium.jar"), "tampered");
+	let files = [file("mods/sodium.jar", SODIUM, &[format!("{CDN}/sodium.jar")], None).await];
+
+	let (_temp, folder) = folder().await;
+	let result = Mrpack::from_bytes(pack(&index(&files), &[])).unwrap().install(&client, &folder, "pack").await;
+	assert!(matches!(result, Err(MrpackError::Download(_))));
+	assert!(!folder.instances.path.join("pack").exists());
+}
+
+#[tokio::test]
+async fn rejects_unsafe_paths() {
+	let files = [file("../escape.jar", SODIUM, &["https://example.com/escape.jar".into()], None).await];
+
+	let (_temp, folder) = folder().await;
+	let result =
+		Mrpack::from_bytes(pack(&index(&files), &[])).unwrap().install(&MockClient::new(), &folder, "pack").await;
+	assert!(matches!(result, Err(MrpackError::UnsafePath(path)) if &*path == "../escape.jar"));
+	assert!(!folder.instances.path.join("pack").exists());
+}
+
+#[tokio::test]
+async fn keeps_existing_instances() {
+	let (_temp, folder) = folder().await;
+	folder.instances.create("pack", spuz_folder::InstanceConfig::new("1.20.6")).await.unwrap();
+	let result = Mrpack::from_bytes(pack(&index(&[]), &[])).unwrap().install(&MockClient::new(), &folder, "pack").await;
+	assert!(matches!(result, Err(MrpackError::Folder(spuz_folder::Error::InstanceExists(_)))));
+	assert!(folder.instances.get("pack").await.unwrap().is_some());
+}
+
+#[test]
+fn rejects_invalid_packs() {
+	let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+	writer.start_file("overrides/a.txt", SimpleFileOptions::default()).unwrap();
+	let archive = writer.finish().unwrap().into_inner();
+	assert!(matches!(Mrpack::from_bytes(archive), Err(OpenMrpackError::MissingIndex)));
+
+	let mut index = index(&[]);
+	index["formatVersion"] = json!(2);
+	assert!(matches!(Mrpack::from_bytes(pack(&index, &[])), Err(OpenMrpackError::Unsupported { format_version: 2, .. })));
+}
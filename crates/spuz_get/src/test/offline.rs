@@ -0,0 +1,90 @@
+use futures_lite::AsyncReadExt;
+use spuz_folder::Folder;
+use spuz_piston::Manifest;
+use tokio::fs;
+use url::Url;
+
+use crate::{
+	cache::{Cache, CacheEntry},
+	offline::{Offline, OfflineError},
+	vanilla, Client,
+};
+
+/// sha1 of the 1.20.6 fixture
+const HASH: &str = "e549ebaa9c8111c32c7341af92e809b8a5204590";
+const MANIFEST: &str = include_str!("../../../../versions/1.20.6.json");
+
+async fn folder() -> (tempfile::TempDir, Offline) {
+	let temp = tempfile::tempdir().unwrap();
+	let folder = Folder::settle(temp.path()).await.unwrap();
+	(temp, Offline::from_folder(&folder))
+}
+
+#[tokio::test]
+async fn serves_installed_files() {
+	let (_temp, offline) = folder().await;
+
+	fs::create_dir_all(offline.versions_dir.join("1.20.6")).await.unwrap();
+	fs::write(offline.versions_dir.join("1.20.6/1.20.6.json"), MANIFEST).await.unwrap();
+	fs::create_dir_all(offline.assets_dir.join("objects/ab")).await.unwrap();
+	fs::write(offline.assets_dir.join("objects/ab/abcdef"), "resource").await.unwrap();
+	fs::create_dir_all(offline.libraries_dir.join("org/ow2/asm/asm/9.6")).await.unwrap();
+	fs::write(offline.libraries_dir.join("org/ow2/asm/asm/9.6/asm-9.6.jar"), "asm").await.unwrap();
+
+	let manifest: Manifest = vanilla::package(&offline, HASH, "1.20.6").await.unwrap().json().await.unwrap();
+	assert_eq!(&*manifest.id, "1.20.6");
+
+	let mut content = String::new();
+	vanilla::resource(&offline, "abcdef").await.unwrap().read_to_string(&mut content).await.unwrap();
+	assert_eq!(content, "resource");
+
+	let url = "https://maven.fabricmc.net/org/ow2/asm/asm/9.6/asm-9.6.jar".parse().unwrap();
+	let mut content = String::new();
+	offline.get_stream(url).await.unwrap().read_to_string(&mut content).await.unwrap();
+	assert_eq!(content, "asm");
+}
+
+#[tokio::test]
+async fn serves_cached_responses() {
+	let (_temp, offline) = folder().await;
+
+	let url: Url = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json".parse().unwrap();
+	let list = r#"{ "latest": { "release": "1.20.6", "snapshot": "24w21b" }, "versions": [] }"#;
+	let entry = CacheEntry {
+		url: url.as_str().into(),
+		etag: None,
+		last_modified: None,
+		stored_at: 0,
+		max_age: None,
+		no_cache: false,
+	};
+	offline.cache.store(&url, &entry, list.as_bytes()).await.unwrap();
+
+	vanilla::list(&offline).await.unwrap();
+	assert_eq!(offline.cache, Cache::new(offline.versions_dir.parent().unwrap().join("cache")));
+}
+
+#[tokio::test]
+async fn reports_unavailable() {
+	let (_temp, offline) = folder().await;
+
+	let result = vanilla::object(&offline, "9d5b45173a0123720bae94afc8a35d742e559d5a", "client.jar").await;
+	assert!(matches!(result, Err(crate::FetchError::Client { source: OfflineError::NotAvailable(_), .. })));
+}
+
+#[tokio::test]
+async fn decodes_candidates() {
+	let (_temp, offline) = folder().await;
+
+	let url = "https://maven.fabricmc.net/com/example/my%20lib/1.0/my%20lib-1.0.jar".parse().unwrap();
+	assert_eq!(offline.candidates(&url)[0], offline.libraries_dir.join("com/example/my lib/1.0/my lib-1.0.jar"));
+
+	for url in [
+		"https://maven.fabricmc.net/com/%2E%2E%5C%2E%2E%5Csecret",
+		"https://maven.fabricmc.net/com/..%2F..%2Fsecret",
+		"https://piston-meta.mojang.com/v1/packages/abc/..%2F..%2Fsecret.json",
+	] {
+		let url = url.parse().unwrap();
+		assert_eq!(offline.candidates(&url), [offline.cache.body_path(&url)], "{url}");
+	}
+}
@@ -0,0 +1,81 @@
+use mockito::Server;
+use serde_json::json;
+use tokio::fs;
+
+use crate::quilt::Quilt;
+
+const LOADER_JAR: &[u8] = b"quilt-loader";
+
+#[tokio::test]
+async fn list_loaders_for_game() {
+	let mut server = Server::new_async().await;
+	let mock = server
+		.mock("GET", "/v3/versions/loader/1.20.6")
+		.with_body(
+			json!([{
+				"loader": { "separator": ".", "build": 0, "maven": "org.quiltmc:quilt-loader:0.26.0", "version": "0.26.0" },
+				"hashed": { "maven": "org.quiltmc:hashed:1.20.6", "version": "1.20.6" },
+				"intermediary": { "maven": "net.fabricmc:intermediary:1.20.6", "version": "1.20.6" }
+			}])
+			.to_string(),
+		)
+		.create_async()
+		.await;
+
+	let client = reqwest::Client::new();
+	let loaders = Quilt::new(format!("{}/v3", server.url())).loaders_for(&client, "1.20.6").await.unwrap();
+
+	assert_eq!(loaders.len(), 1);
+	assert_eq!(&*loaders[0].version, "0.26.0");
+	assert!(!loaders[0].stable);
+
+	mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn install_profile() {
+	let mut server = Server::new_async().await;
+	let maven = format!("{}/repository/release/", server.url());
+
+	let profile = json!({
+		"id": "quilt-loader-0.26.0-1.20.6",
+		"inheritsFrom": "1.20.6",
+		"type": "release",
+		"mainClass": "org.quiltmc.loader.impl.launch.knot.KnotClient",
+		"arguments": { "game": [] },
+		"libraries": [{ "name": "org.quiltmc:quilt-loader:0.26.0", "url": maven }]
+	});
+
+	server
+		.mock("GET", "/v3/versions/loader/1.20.6/0.26.0/profile/json")
+		.with_body(profile.to_string())
+		.create_async()
+		.await;
+	let loader = server
+		.mock("GET", "/repository/release/org/quiltmc/quilt-loader/0.26.0/quilt-loader-0.26.0.jar")
+		.with_body(LOADER_JAR)
+		.create_async()
+		.await;
+
+	let temp = tempfile::tempdir().unwrap();
+	let libraries_dir = temp.path().join("libraries");
+	let versions_dir = temp.path().join("versions");
+
+	let client = reqwest::Client::new();
+	let manifest = Quilt::new(format!("{}/v3", server.url()))
+		.install(&client, "1.20.6", "0.26.0", &libraries_dir, &versions_dir)
+		.await
+		.unwrap();
+
+	assert_eq!(&*manifest.id, "quilt-loader-0.26.0-1.20.6");
+	assert_eq!(manifest.main_class.as_deref(), Some("org.quiltmc.loader.impl.launch.knot.KnotClient"));
+	assert!(manifest.arguments.as_ref().unwrap().jvm.is_empty());
+
+	let artifact = manifest.libraries[0].downloads.artifact.as_ref().unwrap();
+	assert_eq!(fs::read(libraries_dir.join(&artifact.path)).await.unwrap(), LOADER_JAR);
+	assert!(fs::try_exists(versions_dir.join("quilt-loader-0.26.0-1.20.6/quilt-loader-0.26.0-1.20.6.json"))
+		.await
+		.unwrap());
+
+	loader.assert_async().await;
+}
@@ -0,0 +1,51 @@
+use spuz_folder::{FileKind, Report, RequiredFile};
+use tempfile::TempDir;
+
+use crate::{
+	download::{self, DownloadError},
+	testing::MockClient,
+	vanilla::repair::{repair, RepairError},
+};
+
+const LIBRARY_URL: &str = "https://libraries.minecraft.net/com/example/library-1.0.jar";
+const SOUND_URL: &str = "https://resources.download.minecraft.net/ab/sound";
+
+async fn file(kind: FileKind, dir: &TempDir, name: &str, url: &str, content: &[u8]) -> RequiredFile {
+	RequiredFile {
+		kind,
+		path: dir.path().join(name),
+		url: url.into(),
+		sha1: download::sha1(content).await.unwrap().into(),
+		size: content.len() as u64,
+	}
+}
+
+#[tokio::test]
+async fn repairs_damaged_files() {
+	let dir = TempDir::new().unwrap();
+	let library = file(FileKind::Library, &dir, "libraries/library-1.0.jar", LIBRARY_URL, b"library").await;
+	let sound = file(FileKind::Asset, &dir, "objects/sound", SOUND_URL, b"sound").await;
+	std::fs::create_dir_all(dir.path().join("objects")).unwrap();
+	std::fs::write(&sound.path, b"noise").unwrap();
+
+	let client = MockClient::new().with(LIBRARY_URL, "library").with(SOUND_URL, "sound");
+	let report = Report { missing: vec![library.clone()], corrupt: vec![sound.clone()] };
+	repair(&client, &report).await.unwrap();
+
+	assert_eq!(std::fs::read(&library.path).unwrap(), b"library");
+	assert_eq!(std::fs::read(&sound.path).unwrap(), b"sound");
+	assert_eq!(client.requested(LIBRARY_URL), 1);
+}
+
+#[tokio::test]
+async fn rejects_mismatched_content() {
+	let dir = TempDir::new().unwrap();
+	let library = file(FileKind::Library, &dir, "library-1.0.jar", LIBRARY_URL, b"library").await;
+
+	let client = MockClient::new().with(LIBRARY_URL, "tampered");
+	let report = Report { missing: vec![library.clone()], corrupt: Vec::new() };
+	let err = repair(&client, &report).await.unwrap_err();
+
+	assert!(matches!(err, RepairError::Download(DownloadError::HashMismatch { .. })));
+	assert!(!library.path.exists());
+}
@@ -0,0 +1,146 @@
+use async_compression::futures::bufread::LzmaEncoder;
+use futures_lite::{io::Cursor, AsyncReadExt};
+use serde_json::json;
+use spuz_folder::Folder;
+use spuz_piston::runtime::TARGET_RUNTIME;
+use tokio::fs;
+
+use crate::{
+	download::{self, DownloadError},
+	testing::MockClient,
+	vanilla::runtime::{install, install_manifest, InstallRuntimeError},
+};
+
+const ALL_URL: &str =
+	"https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+const MANIFEST_URL: &str = "https://piston-meta.mojang.com/v1/packages/manifest/manifest.json";
+const JAVA: &[u8] = b"#!/bin/sh\necho java\n";
+const RELEASE: &[u8] = b"JAVA_VERSION=\"17.0.8\"\n";
+
+async fn lzma(content: &[u8]) -> Vec<u8> {
+	let mut compressed = Vec::new();
+	LzmaEncoder::new(Cursor::new(content)).read_to_end(&mut compressed).await.unwrap();
+	compressed
+}
+
+async fn client(release_sha1: &str) -> MockClient {
+	let java_sha1 = download::sha1(Cursor::new(JAVA)).await.unwrap();
+
+	let target = serde_json::to_value(TARGET_RUNTIME).unwrap();
+	let all = json!({
+		target.as_str().unwrap(): {
+			"java-runtime-gamma": [{
+				"availability": { "group": 1, "progress": 100 },
+				"manifest": { "sha1": "manifest", "size": 0, "url": MANIFEST_URL },
+				"version": { "name": "17.0.8", "released": "2023-07-18T12:00:00+00:00" }
+			}]
+		}
+	});
+
+	let manifest = json!({
+		"files": {
+			"bin": { "type": "directory" },
+			"bin/java": {
+				"type": "file",
+				"executable": true,
+				"downloads": {
+					"raw": { "sha1": java_sha1, "size": JAVA.len(), "url": "https://piston-data.mojang.com/raw/java" },
+					"lzma": { "sha1": "lzma", "size": 0, "url": "https://piston-data.mojang.com/lzma/java" }
+				}
+			},
+			"release": {
+				"type": "file",
+				"executable": false,
+				"downloads": {
+					"raw": { "sha1": release_sha1, "size": RELEASE.len(), "url": "https://piston-data.mojang.com/raw/release" }
+				}
+			},
+			"legal/java.base/LICENSE": { "type": "link", "target": "../../release" }
+		}
+	});
+
+	MockClient::new()
+		.with(ALL_URL, all.to_string())
+		.with(MANIFEST_URL, manifest.to_string())
+		.with("https://piston-data.mojang.com/lzma/java", lzma(JAVA).await)
+		.with("https://piston-data.mojang.com/raw/release", RELEASE)
+}
+
+#[tokio::test]
+async fn install_runtime() {
+	let temp = tempfile::tempdir().unwrap();
+	let folder = Folder::settle(temp.path()).await.unwrap();
+	let runtime = folder.java_runtimes.component("java-runtime-gamma");
+
+	let release_sha1 = download::sha1(Cursor::new(RELEASE)).await.unwrap();
+	let client = client(&release_sha1).await;
+	install(&client, &runtime).await.unwrap();
+
+	let dir = runtime.dir();
+	assert_eq!(fs::read(dir.join("bin/java")).await.unwrap(), JAVA);
+	assert_eq!(fs::read(dir.join("release")).await.unwrap(), RELEASE);
+	assert_eq!(fs::read(dir.join("legal/java.base/LICENSE")).await.unwrap(), RELEASE);
+	assert!(runtime.bin_exists().await.unwrap());
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+
+		let mode = fs::metadata(dir.join("bin/java")).await.unwrap().permissions().mode();
+		assert_eq!(mode & 0o111, 0o111);
+	}
+
+	// Second run must succeed without touching up to date files
+	install(&client, &runtime).await.unwrap();
+	assert_eq!(client.requested("https://piston-data.mojang.com/lzma/java"), 1);
+	assert_eq!(client.requested("https://piston-data.mojang.com/raw/release"), 1);
+}
+
+#[tokio::test]
+async fn replaces_directory_with_link() {
+	let temp = tempfile::tempdir().unwrap();
+	let folder = Folder::settle(temp.path()).await.unwrap();
+	let runtime = folder.java_runtimes.component("java-runtime-gamma");
+	fs::create_dir_all(runtime.dir().join("legal/java.base/LICENSE/stale")).await.unwrap();
+
+	let release_sha1 = download::sha1(Cursor::new(RELEASE)).await.unwrap();
+	install(&client(&release_sha1).await, &runtime).await.unwrap();
+
+	assert_eq!(fs::read(runtime.dir().join("legal/java.base/LICENSE")).await.unwrap(), RELEASE);
+}
+
+#[tokio::test]
+async fn rejects_escaping_paths() {
+	let temp = tempfile::tempdir().unwrap();
+	let escaping = temp.path().join("escaped");
+	let manifest = json!({ "files": { "../escaped": { "type": "directory" } } });
+	let manifest = serde_json::from_value(manifest).unwrap();
+
+	let result = install_manifest(&MockClient::new(), &manifest, &temp.path().join("runtime")).await;
+
+	assert!(matches!(result, Err(InstallRuntimeError::UnsafePath(_))));
+	assert!(!fs::try_exists(escaping).await.unwrap());
+}
+
+#[tokio::test]
+async fn install_runtime_hash_mismatch() {
+	let temp = tempfile::tempdir().unwrap();
+	let folder = Folder::settle(temp.path()).await.unwrap();
+	let runtime = folder.java_runtimes.component("java-runtime-gamma");
+
+	let result = install(&client("0000000000000000000000000000000000000000").await, &runtime).await;
+
+	assert!(matches!(result, Err(InstallRuntimeError::Download(DownloadError::HashMismatch { .. }))));
+	assert!(!fs::try_exists(runtime.dir().join("release")).await.unwrap());
+}
+
+#[tokio::test]
+async fn install_unknown_component() {
+	let temp = tempfile::tempdir().unwrap();
+	let folder = Folder::settle(temp.path()).await.unwrap();
+	let runtime = folder.java_runtimes.component("java-runtime-delta");
+
+	let result = install(&client("").await, &runtime).await;
+
+	assert!(matches!(result, Err(InstallRuntimeError::UnknownComponent(_))));
+}
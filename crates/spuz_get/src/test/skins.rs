@@ -0,0 +1,158 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use mockito::{Matcher, Server, ServerGuard};
+use serde_json::json;
+
+use crate::skins::{Model, SkinError, Skins, TextureCache, TextureState};
+
+const UUID: &str = "069a79f444e94726a5befca90e38aaf5";
+
+fn skins(server: &ServerGuard) -> Skins {
+	Skins::new(server.url(), server.url())
+}
+
+/// Png header of the given size, enough for the validation
+fn png(width: u32, height: u32) -> Vec<u8> {
+	let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+	png.extend(width.to_be_bytes());
+	png.extend(height.to_be_bytes());
+	png.extend([8, 6, 0, 0, 0]);
+	png
+}
+
+fn profile(server: &ServerGuard, cape: TextureState) -> String {
+	json!({
+		"id": UUID,
+		"name": "Notch",
+		"skins": [{ "id": "s", "state": "ACTIVE", "url": format!("{}/texture/abc", server.url()), "variant": "SLIM" }],
+		"capes": [{ "id": "c", "state": cape, "url": format!("{}/texture/def", server.url()), "alias": "Migrator" }]
+	})
+	.to_string()
+}
+
+#[tokio::test]
+async fn textures_of_player() {
+	let mut server = Server::new_async().await;
+	let textures = json!({
+		"timestamp": 0,
+		"profileId": UUID,
+		"profileName": "Notch",
+		"textures": {
+			"SKIN": { "url": "http://textures.minecraft.net/texture/abc", "metadata": { "model": "slim" } },
+			"CAPE": { "url": "http://textures.minecraft.net/texture/def" }
+		}
+	});
+	server
+		.mock("GET", format!("/session/minecraft/profile/{UUID}").as_str())
+		.with_body(
+			json!({ "id": UUID, "name": "Notch", "properties": [{ "name": "textures", "value": STANDARD.encode(textures.to_string()) }] })
+				.to_string(),
+		)
+		.create_async()
+		.await;
+
+	let textures = skins(&server).textures(&reqwest::Client::new(), UUID).await.unwrap();
+	let skin = textures.skin.unwrap();
+	assert_eq!(&*skin.url, "http://textures.minecraft.net/texture/abc");
+	assert_eq!(skin.model, Model::Slim);
+	assert_eq!(textures.cape.as_deref(), Some("http://textures.minecraft.net/texture/def"));
+	assert_eq!(&*textures.profile_name, "Notch");
+}
+
+#[tokio::test]
+async fn textures_are_cached() {
+	let mut server = Server::new_async().await;
+	let texture = server.mock("GET", "/texture/abc").with_body(png(64, 64)).expect(1).create_async().await;
+	let dir = tempfile::tempdir().unwrap();
+	let cache = TextureCache::new(dir.path());
+	let client = reqwest::Client::new();
+
+	let url = format!("{}/texture/abc", server.url());
+	let path = cache.texture(&client, &url).await.unwrap();
+	assert_eq!(path, dir.path().join("abc.png"));
+	assert_eq!(cache.texture(&client, &url).await.unwrap(), path);
+
+	texture.assert_async().await;
+	assert_eq!(std::fs::read(path).unwrap(), png(64, 64));
+}
+
+#[tokio::test]
+async fn set_and_upload_skin() {
+	let mut server = Server::new_async().await;
+	let set = server
+		.mock("POST", "/minecraft/profile/skins")
+		.match_header("authorization", "Bearer token")
+		.match_body(Matcher::Json(json!({ "variant": "slim", "url": "https://example.com/skin.png" })))
+		.with_body(profile(&server, TextureState::Inactive))
+		.create_async()
+		.await;
+	let skins = skins(&server);
+	let client = reqwest::Client::new();
+
+	let updated = skins.set_skin(&client, "token", "https://example.com/skin.png", Model::Slim).await.unwrap();
+	assert_eq!(updated.active_skin().unwrap().variant, Model::Slim);
+	set.assert_async().await;
+
+	// Json matching of the set mock would choke on the binary body
+	set.remove_async().await;
+	let upload = server
+		.mock("POST", "/minecraft/profile/skins")
+		.match_header("content-type", Matcher::Regex("multipart/form-data".into()))
+		.with_body(profile(&server, TextureState::Inactive))
+		.create_async()
+		.await;
+
+	skins.upload_skin(&client, "token", png(64, 32), Model::Classic).await.unwrap();
+	upload.assert_async().await;
+
+	let err = skins.upload_skin(&client, "token", png(128, 128), Model::Classic).await.unwrap_err();
+	assert!(matches!(err, SkinError::InvalidSkin(_)), "{err}");
+	let err = skins.upload_skin(&client, "token", b"GIF89a".to_vec(), Model::Classic).await.unwrap_err();
+	assert!(matches!(err, SkinError::InvalidSkin(_)), "{err}");
+}
+
+#[tokio::test]
+async fn reset_skin_and_capes() {
+	let mut server = Server::new_async().await;
+	let reset = server
+		.mock("DELETE", "/minecraft/profile/skins/active")
+		.with_body(profile(&server, TextureState::Inactive))
+		.create_async()
+		.await;
+	let select = server
+		.mock("PUT", "/minecraft/profile/capes/active")
+		.match_body(Matcher::Json(json!({ "capeId": "c" })))
+		.with_body(profile(&server, TextureState::Active))
+		.create_async()
+		.await;
+	let hide = server
+		.mock("DELETE", "/minecraft/profile/capes/active")
+		.with_body(profile(&server, TextureState::Inactive))
+		.create_async()
+		.await;
+	server.mock("GET", "/minecraft/profile").with_body(profile(&server, TextureState::Inactive)).create_async().await;
+	let skins = skins(&server);
+	let client = reqwest::Client::new();
+
+	skins.reset_skin(&client, "token").await.unwrap();
+	reset.assert_async().await;
+
+	let capes = skins.profile(&client, "token").await.unwrap().capes;
+	assert_eq!(capes.len(), 1);
+
+	let profile = skins.select_cape(&client, "token", &capes[0].id).await.unwrap();
+	assert_eq!(profile.active_cape().map(|it| &*it.id), Some("c"));
+	select.assert_async().await;
+
+	let profile = skins.hide_cape(&client, "token").await.unwrap();
+	assert_eq!(profile.active_cape(), None);
+	hide.assert_async().await;
+}
+
+#[tokio::test]
+async fn unauthorized() {
+	let mut server = Server::new_async().await;
+	server.mock("GET", "/minecraft/profile").with_status(401).create_async().await;
+
+	let err = skins(&server).profile(&reqwest::Client::new(), "expired").await.unwrap_err();
+	assert!(matches!(err, SkinError::Status { status: 401, .. }), "{err}");
+}
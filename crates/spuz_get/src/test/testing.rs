@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use futures_lite::AsyncReadExt;
+use spuz_piston::Manifest;
+
+use crate::{
+	testing::{MockClient, MockError, Request, RequestKind},
+	vanilla, Client, FetchError,
+};
+
+/// sha1 of the 1.20.6 fixture
+const HASH: &str = "e549ebaa9c8111c32c7341af92e809b8a5204590";
+const PACKAGE: &str = "https://piston-meta.mojang.com/v1/packages/e549ebaa9c8111c32c7341af92e809b8a5204590/1.20.6.json";
+const RESOURCE: &str = "https://resources.download.minecraft.net/ab/abcdef";
+
+#[tokio::test]
+async fn serves_fixtures_and_records_requests() {
+	let client =
+		MockClient::new().with_fixture(PACKAGE, "../../versions/1.20.6.json").unwrap().with(RESOURCE, "resource");
+
+	let manifest: Manifest = vanilla::package(&client, HASH, "1.20.6").await.unwrap().json().await.unwrap();
+	assert_eq!(&*manifest.id, "1.20.6");
+	let _: Manifest = client.get_json(PACKAGE.parse().unwrap()).await.unwrap();
+	assert!(vanilla::resource(&client, "ffffff").await.is_err());
+
+	assert_eq!(client.requested(PACKAGE), 2);
+	assert_eq!(client.requests()[0], Request { kind: RequestKind::Stream, url: PACKAGE.parse().unwrap() });
+	assert_eq!(client.requests()[1].kind, RequestKind::Json);
+	assert_eq!(client.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn injects_errors() {
+	let client = MockClient::new().with_error(RESOURCE, "connection reset");
+
+	let result = vanilla::resource(&client, "abcdef").await;
+	assert!(matches!(result, Err(FetchError::Client { source: MockError::Injected { .. }, .. })));
+
+	let result = vanilla::resource(&client, "ffffff").await;
+	assert!(matches!(result, Err(FetchError::Client { source: MockError::NotFound(_), .. })));
+}
+
+#[tokio::test]
+async fn injects_latency() {
+	let client = MockClient::new()
+		.with(RESOURCE, "resource")
+		.with("https://example.com/", "slow")
+		.with_latency(Duration::from_millis(50))
+		.delay("https://example.com", Duration::from_millis(150));
+
+	let start = Instant::now();
+	vanilla::resource(&client, "abcdef").await.unwrap();
+	assert!(start.elapsed() >= Duration::from_millis(50));
+
+	let start = Instant::now();
+	client.get_stream("https://example.com".parse().unwrap()).await.unwrap();
+	assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn truncates_streams() {
+	let client = MockClient::new().with(RESOURCE, "resource").truncate(RESOURCE, 3);
+
+	let mut stream = vanilla::resource(&client, "abcdef").await.unwrap();
+	let mut content = Vec::new();
+	let err = stream.read_to_end(&mut content).await.unwrap_err();
+
+	assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+	assert_eq!(content, b"res");
+}
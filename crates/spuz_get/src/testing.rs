@@ -0,0 +1,210 @@
+//! [`MockClient`] to test code built on top of the crate without network
+//!
+//! # Example
+//! ```
+//! use serde_json::json;
+//! use spuz_get::{testing::MockClient, vanilla};
+//!
+//! const LIST: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+//!
+//! let client = MockClient::new()
+//!     .with_json(LIST, &json!({ "latest": { "release": "1.21", "snapshot": "1.21" }, "versions": [] }));
+//!
+//! pollster::block_on(vanilla::list(&client)).unwrap();
+//! assert_eq!(client.requested(LIST), 1);
+//! ```
+
+use std::{
+	collections::HashMap,
+	io,
+	path::Path,
+	pin::Pin,
+	sync::{Mutex, PoisonError},
+	task::{Context, Poll},
+	time::Duration,
+};
+
+use async_trait::async_trait;
+use futures_lite::{io::Cursor, AsyncRead};
+use serde::{de::DeserializeOwned, Serialize};
+use spuz_piston::shared::Str;
+use thiserror::Error;
+use url::Url;
+
+use crate::{client::Body, Client};
+
+/// Method of the [`Client`] the request was made with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+	Json,
+	Stream,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+	pub kind: RequestKind,
+	pub url: Url,
+}
+
+#[derive(Debug, Clone)]
+struct Route {
+	response: Result<Vec<u8>, Str>,
+	latency: Option<Duration>,
+	truncate_at: Option<usize>,
+}
+
+/// Client that serves canned responses keyed by url and records every
+/// request. Unknown urls fail with [`MockError::NotFound`]
+#[derive(Debug, Default)]
+pub struct MockClient {
+	routes: HashMap<Box<str>, Route>,
+	latency: Option<Duration>,
+	requests: Mutex<Vec<Request>>,
+}
+
+/// Urls are normalized, so `https://host` and `https://host/` are the same
+fn key(url: &str) -> Box<str> {
+	Url::parse(url).map_or_else(|_| url.into(), |it| it.as_str().into())
+}
+
+impl MockClient {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn route(mut self, url: &str, response: Result<Vec<u8>, Str>) -> Self {
+		self.routes.insert(key(url), Route { response, latency: None, truncate_at: None });
+		self
+	}
+
+	/// Serves `content` at the `url`
+	#[must_use]
+	pub fn with(self, url: &str, content: impl Into<Vec<u8>>) -> Self {
+		self.route(url, Ok(content.into()))
+	}
+
+	/// Serves the `value` serialized to json at the `url`
+	#[must_use]
+	pub fn with_json(self, url: &str, value: &impl Serialize) -> Self {
+		let response = serde_json::to_vec(value).map_err(|err| err.to_string().into());
+		self.route(url, response)
+	}
+
+	/// Serves the content of the fixture file at the `url`
+	pub fn with_fixture(self, url: &str, path: impl AsRef<Path>) -> io::Result<Self> {
+		Ok(self.with(url, std::fs::read(path)?))
+	}
+
+	/// Fails requests to the `url` with [`MockError::Injected`]
+	#[must_use]
+	pub fn with_error(self, url: &str, message: impl Into<Str>) -> Self {
+		self.route(url, Err(message.into()))
+	}
+
+	/// Delays every response by `latency`, unless the route has its own
+	#[must_use]
+	pub fn with_latency(mut self, latency: Duration) -> Self {
+		self.latency = Some(latency);
+		self
+	}
+
+	/// Delays responses of the already added `url`
+	#[must_use]
+	pub fn delay(mut self, url: &str, latency: Duration) -> Self {
+		if let Some(route) = self.routes.get_mut(&key(url)) {
+			route.latency = Some(latency);
+		}
+		self
+	}
+
+	/// Cuts the stream of the already added `url` after `len` bytes, the next
+	/// read fails with [`io::ErrorKind::UnexpectedEof`]
+	#[must_use]
+	pub fn truncate(mut self, url: &str, len: usize) -> Self {
+		if let Some(route) = self.routes.get_mut(&key(url)) {
+			route.truncate_at = Some(len);
+		}
+		self
+	}
+
+	/// All requests made so far, in order
+	pub fn requests(&self) -> Vec<Request> {
+		self.requests.lock().unwrap_or_else(PoisonError::into_inner).clone()
+	}
+
+	/// Number of requests made to the `url`
+	pub fn requested(&self, url: &str) -> usize {
+		let url = key(url);
+		self.requests().iter().filter(|it| it.url.as_str() == &*url).count()
+	}
+
+	async fn respond(&self, kind: RequestKind, url: Url) -> Result<&Route, MockError> {
+		self.requests.lock().unwrap_or_else(PoisonError::into_inner).push(Request { kind, url: url.clone() });
+
+		let route = self.routes.get(url.as_str());
+		if let Some(latency) = route.and_then(|it| it.latency).or(self.latency) {
+			tokio::time::sleep(latency).await;
+		}
+
+		let route = route.ok_or_else(|| MockError::NotFound(url.clone()))?;
+		match &route.response {
+			Ok(_) => Ok(route),
+			Err(message) => Err(MockError::Injected { url, message: message.clone() }),
+		}
+	}
+}
+
+#[async_trait]
+impl Client for MockClient {
+	type Error = MockError;
+
+	async fn get_json<T>(&self, url: Url) -> Result<T, Self::Error>
+	where
+		T: DeserializeOwned,
+	{
+		let route = self.respond(RequestKind::Json, url).await?;
+		let content = route.response.as_deref().unwrap_or_default();
+		let content = &content[..route.truncate_at.unwrap_or(content.len()).min(content.len())];
+		Ok(serde_json::from_slice(content)?)
+	}
+
+	async fn get_stream(&self, url: Url) -> Result<Body, Self::Error> {
+		let route = self.respond(RequestKind::Stream, url).await?;
+		let content = route.response.clone().unwrap_or_default();
+		// The declared length is the full one, like a connection dropped midway
+		let len = Some(content.len() as u64);
+
+		Ok(match route.truncate_at {
+			Some(at) => Body::new(Truncated(Cursor::new(content[..at.min(content.len())].to_vec())), len),
+			None => Body::new(Cursor::new(content), len),
+		})
+	}
+}
+
+/// Stream that fails instead of reporting the end
+struct Truncated(Cursor<Vec<u8>>);
+
+impl AsyncRead for Truncated {
+	fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+		match Pin::new(&mut self.0).poll_read(cx, buf) {
+			Poll::Ready(Ok(0)) if !buf.is_empty() => {
+				Poll::Ready(Err(io::Error::new(io::ErrorKind::UnexpectedEof, "stream is truncated")))
+			}
+			poll => poll,
+		}
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum MockError {
+	#[error("Not found: {0}")]
+	NotFound(Url),
+	#[error("Request to {url} failed: {message}")]
+	Injected { url: Url, message: Str },
+	#[error("Invalid json: {0}")]
+	Json(
+		#[from]
+		#[source]
+		serde_json::Error,
+	),
+}
@@ -1,40 +1,70 @@
-use std::marker::PhantomData;
+#[cfg(feature = "tokio")]
+pub mod repair;
+#[cfg(feature = "tokio")]
+pub mod runtime;
 
+#[cfg(feature = "tokio")]
 use spuz_piston::list::Versions;
 use url::Url;
 
-use crate::{client::BoxedAsyncRead, Client, FetchError, JsonResource};
+#[cfg(feature = "tokio")]
+use crate::{
+	client::{fetch_json, fetch_stream, Body},
+	Client, FetchError, JsonResource,
+};
+
+/// Host of version lists, packages and objects. Use
+/// [`Mirrored`](crate::mirror::Mirrored) to request them from elsewhere
+pub const PISTON_META: &str = "https://piston-meta.mojang.com";
+pub use spuz_piston::assets::RESOURCES;
+
+/// Url of the version list
+pub fn list_url() -> Result<Url, url::ParseError> {
+	Url::parse(&format!("{PISTON_META}/mc/game/version_manifest_v2.json"))
+}
+
+/// Url of the json package by `hash` and `id`
+pub fn package_url(hash: &str, id: &str) -> Result<Url, url::ParseError> {
+	Url::parse(&format!("{PISTON_META}/v1/packages/{hash}/{id}.json"))
+}
+
+/// Url of the binary object by `hash` and `id`
+pub fn object_url(hash: &str, id: &str) -> Result<Url, url::ParseError> {
+	Url::parse(&format!("{PISTON_META}/v1/objects/{hash}/{id}"))
+}
+
+/// Url of the game resource by `hash`
+pub fn resource_url(hash: &str) -> Result<Url, url::ParseError> {
+	Url::parse(&format!("{RESOURCES}/{h2}/{hash}", h2 = &hash[..2]))
+}
 
 /// Lists all versions of minecraft over time
+#[cfg(feature = "tokio")]
 pub async fn list<C: Client>(client: &C) -> Result<Versions, FetchError<C>> {
-	let url = Url::parse("https://piston-meta.mojang.com/mc/game/version_manifest_v2.json")?;
-	client.get_json(url).await.map_err(FetchError::Client)
+	fetch_json(client, list_url()?).await
 }
 
-/// Requests json package from `https://piston-meta.mojang.com/v1/packages` by `hash` and `id`
-pub async fn package<P, C: Client>(
-	client: &C,
-	hash: &str,
-	id: &str,
-) -> Result<JsonResource<BoxedAsyncRead, P>, FetchError<C>> {
-	let url: Url = format!("https://piston-meta.mojang.com/v1/packages/{hash}/{id}.json").parse()?;
-	let stream = client.get_stream(url).await.map_err(FetchError::Client)?;
+/// Requests json package from `https://piston-meta.mojang.com/v1/packages` by `hash` and `id`,
+/// the content is [verified](JsonResource::verify) against `hash`
+#[cfg(feature = "tokio")]
+pub async fn package<P, C: Client>(client: &C, hash: &str, id: &str) -> Result<JsonResource<Body, P>, FetchError<C>> {
+	let stream = fetch_stream(client, package_url(hash, id)?).await?;
 
-	Ok(JsonResource { stream, json: PhantomData })
+	Ok(JsonResource::new(stream).verify(hash))
 }
 
 /// Requests binary object from `https://piston-meta.mojang.com/v1/objects` by `hash` and `id`
-pub async fn object<C: Client>(client: &C, hash: &str, id: &str) -> Result<BoxedAsyncRead, FetchError<C>> {
-	let url: Url = format!("https://piston-meta.mojang.com/v1/objects/{hash}/{id}").parse()?;
-	let stream = client.get_stream(url).await.map_err(FetchError::Client)?;
+#[cfg(feature = "tokio")]
+pub async fn object<C: Client>(client: &C, hash: &str, id: &str) -> Result<Body, FetchError<C>> {
+	let stream = fetch_stream(client, object_url(hash, id)?).await?;
 
 	Ok(stream)
 }
 
 /// Requests game resource (aka assets) from `https://resources.download.minecraft.net/`
-pub async fn resource<C: Client>(client: &C, hash: &str) -> Result<BoxedAsyncRead, FetchError<C>> {
-	let url: Url = format!("https://resources.download.minecraft.net/{h2}/{hash}", h2 = &hash[..2]).parse()?;
-	let stream = client.get_stream(url).await.map_err(FetchError::Client)?;
+#[cfg(feature = "tokio")]
+pub async fn resource<C: Client>(client: &C, hash: &str) -> Result<Body, FetchError<C>> {
+	let stream = fetch_stream(client, resource_url(hash)?).await?;
 
 	Ok(stream)
 }
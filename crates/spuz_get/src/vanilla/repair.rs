@@ -0,0 +1,39 @@
+//! Repair of an installed version from the [`Report`] of
+//! [`Folder::verify`](spuz_folder::Folder::verify)
+
+use spuz_folder::Report;
+use thiserror::Error;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+	client::fetch_stream,
+	download::{self, DownloadError},
+	Client, FetchError,
+};
+
+/// Downloads every missing or corrupt file of the `report` into its place
+pub async fn repair<C: Client>(client: &C, report: &Report) -> Result<(), RepairError<C>> {
+	for file in report.damaged() {
+		let stream = fetch_stream(client, Url::parse(&file.url)?).await?;
+		download::save(stream, &file.path, Some(&file.sha1)).await?;
+	}
+
+	debug!("Repaired {} missing and {} corrupt files", report.missing.len(), report.corrupt.len());
+
+	Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum RepairError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+}
+
+impl<C: Client> From<url::ParseError> for RepairError<C> {
+	fn from(value: url::ParseError) -> Self {
+		Self::Fetch(value.into())
+	}
+}
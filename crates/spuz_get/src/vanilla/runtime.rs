@@ -0,0 +1,162 @@
+use std::{io, path::Path, sync::Arc};
+
+use async_compression::futures::bufread::LzmaDecoder;
+use cfg_if::cfg_if;
+use futures_lite::io::BufReader;
+use spuz_folder::JavaRuntime;
+use spuz_piston::{
+	runtime::{ComponentInfo, RuntimeSource},
+	shared::BoxPath,
+	RuntimeComponents, RuntimeManifest,
+};
+use thiserror::Error;
+use tokio::fs;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+	client::{fetch_json, fetch_stream},
+	download::{self, DownloadError},
+	Client, FetchError,
+};
+
+/// Lists java runtime components available for every platform
+pub async fn components<C: Client>(client: &C) -> Result<RuntimeComponents, FetchError<C>> {
+	let url = Url::parse(
+		"https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json",
+	)?;
+	fetch_json(client, url).await
+}
+
+/// Requests the manifest of files the component consists of
+pub async fn manifest<C: Client>(client: &C, info: &ComponentInfo) -> Result<RuntimeManifest, FetchError<C>> {
+	let url = Url::parse(&info.manifest.url)?;
+	fetch_json(client, url).await
+}
+
+/// Installs java runtime component matching
+/// [`TARGET_RUNTIME`](spuz_piston::runtime::TARGET_RUNTIME) into the
+/// [`JavaRuntime::dir`]. Files that are already present and have the right
+/// hash are not downloaded again, so it also can be used to repair the
+/// installation
+pub async fn install<C: Client>(client: &C, runtime: &JavaRuntime) -> Result<(), InstallRuntimeError<C>> {
+	let components = components(client).await?;
+	let info = components
+		.component(&runtime.component)
+		.ok_or_else(|| InstallRuntimeError::UnknownComponent(runtime.component.clone()))?;
+	let manifest = manifest(client, info).await?;
+
+	install_manifest(client, &manifest, runtime.dir()).await
+}
+
+/// Installs all files of the runtime manifest into the `dir`
+pub async fn install_manifest<C: Client>(
+	client: &C,
+	manifest: &RuntimeManifest,
+	dir: &Path,
+) -> Result<(), InstallRuntimeError<C>> {
+	fs::create_dir_all(dir).await?;
+
+	// Directories go first, links last, so that their targets already exist
+	let mut entries: Vec<_> = manifest.iter().collect();
+	entries.sort_by_key(|(path, source)| {
+		let order = match source {
+			RuntimeSource::Directory => 0,
+			RuntimeSource::File { .. } => 1,
+			RuntimeSource::Link { .. } => 2,
+		};
+		(order, path.components().count())
+	});
+
+	for (path, source) in entries {
+		let relative = download::relative_path(path).ok_or_else(|| InstallRuntimeError::UnsafePath(path.clone()))?;
+		let path = dir.join(relative);
+
+		match source {
+			RuntimeSource::Directory => {
+				fs::create_dir_all(&path).await?;
+			}
+			RuntimeSource::File { downloads, executable } => {
+				if download::is_valid(&path, &downloads.raw.sha1).await? {
+					debug!("{path:?} is up to date, skipping");
+				} else if let Some(lzma) = &downloads.lzma {
+					let stream = fetch_stream(client, Url::parse(&lzma.url)?).await?;
+					let decoder = LzmaDecoder::new(BufReader::new(stream));
+					download::save(decoder, &path, Some(&downloads.raw.sha1)).await?;
+				} else {
+					let stream = fetch_stream(client, Url::parse(&downloads.raw.url)?).await?;
+					download::save(stream, &path, Some(&downloads.raw.sha1)).await?;
+				}
+
+				if *executable {
+					set_executable(&path).await?;
+				}
+			}
+			RuntimeSource::Link { target } => {
+				match fs::symlink_metadata(&path).await {
+					Ok(meta) if meta.is_dir() => fs::remove_dir_all(&path).await?,
+					Ok(_) => fs::remove_file(&path).await?,
+					Err(_) => {}
+				}
+				symlink(target, &path).await?;
+			}
+		}
+	}
+
+	debug!("Java runtime installed into {dir:?}");
+
+	Ok(())
+}
+
+async fn set_executable(path: &Path) -> io::Result<()> {
+	cfg_if! {
+		if #[cfg(unix)] {
+			use std::os::unix::fs::PermissionsExt;
+
+			let mut permissions = fs::metadata(path).await?.permissions();
+			permissions.set_mode(permissions.mode() | 0o755);
+			fs::set_permissions(path, permissions).await
+		} else {
+			let _ = path;
+			Ok(())
+		}
+	}
+}
+
+async fn symlink(target: &Path, path: &Path) -> io::Result<()> {
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent).await?;
+	}
+
+	cfg_if! {
+		if #[cfg(unix)] {
+			fs::symlink(target, path).await
+		} else {
+			fs::symlink_file(target, path).await
+		}
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum InstallRuntimeError<C: Client> {
+	#[error(transparent)]
+	Fetch(#[from] FetchError<C>),
+	#[error("Component `{0}` is not available for the target runtime")]
+	UnknownComponent(Arc<str>),
+	#[error("Path {0:?} of the runtime manifest escapes the runtime directory")]
+	UnsafePath(BoxPath),
+	#[error(transparent)]
+	Download(#[from] DownloadError),
+	#[error("Io error: {0}")]
+	Io(
+		#[from]
+		#[source]
+		io::Error,
+	),
+}
+
+impl<C: Client> From<url::ParseError> for InstallRuntimeError<C> {
+	fn from(value: url::ParseError) -> Self {
+		Self::Fetch(value.into())
+	}
+}
@@ -8,6 +8,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::{Size, Str};
 
+/// Host asset objects are downloaded from, by the first two characters of
+/// the hash and the hash
+pub const RESOURCES: &str = "https://resources.download.minecraft.net";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AssetObject {
 	pub hash: Str,
@@ -29,6 +33,6 @@ impl FromStr for AssetIndex {
 
 impl Display for AssetIndex {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		serde_json::to_string(self).map_err(|_| std::fmt::Error).fmt(f)
+		f.write_str(&serde_json::to_string(self).map_err(|_| std::fmt::Error)?)
 	}
 }
@@ -0,0 +1,79 @@
+use std::{
+	collections::HashSet,
+	fmt::{Debug, Display, Formatter},
+	str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	manifest::{Arguments, Library},
+	maven::Coordinate,
+	Arr, Manifest, Str,
+};
+
+/// Version manifest that only describes changes on top of another version,
+/// referenced by [`inherits_from`](Self::inherits_from). Mod loaders, such as
+/// fabric, quilt or forge, are installed this way
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InheritedManifest {
+	pub id: Str,
+	pub inherits_from: Str,
+	pub r#type: Option<Str>,
+	pub time: Option<Str>,
+	pub release_time: Option<Str>,
+	pub main_class: Option<Str>,
+	#[serde(default)]
+	pub libraries: Arr<Library>,
+	pub arguments: Option<Arguments>,
+}
+
+impl InheritedManifest {
+	/// Merges the changes into the `parent` manifest, so the result can be
+	/// launched as a regular version. Libraries of the child replace libraries
+	/// of the parent with the same maven group and artifact
+	pub fn inherit(self, parent: Manifest) -> Manifest {
+		let coordinates: Vec<Coordinate> = self.libraries.iter().filter_map(|it| it.name.parse().ok()).collect();
+		let overridden: HashSet<_> = coordinates.iter().map(Coordinate::unversioned).collect();
+
+		let parent_libraries = parent.libraries.into_vec().into_iter().filter(|lib| match lib.name.parse::<Coordinate>() {
+			Ok(coordinate) => !overridden.contains(&coordinate.unversioned()),
+			Err(_) => true,
+		});
+		let libraries = self.libraries.into_vec().into_iter().chain(parent_libraries).collect();
+
+		let arguments = match self.arguments {
+			Some(child) => Arguments {
+				game: parent.arguments.game.into_vec().into_iter().chain(child.game.into_vec()).collect(),
+				jvm: parent.arguments.jvm.into_vec().into_iter().chain(child.jvm.into_vec()).collect(),
+			},
+			None => parent.arguments,
+		};
+
+		Manifest {
+			r#type: self.r#type.unwrap_or(parent.r#type),
+			id: self.id,
+			time: self.time.unwrap_or(parent.time),
+			release_time: self.release_time.unwrap_or(parent.release_time),
+			main_class: self.main_class.unwrap_or(parent.main_class),
+			libraries,
+			arguments,
+			..parent
+		}
+	}
+}
+
+impl FromStr for InheritedManifest {
+	type Err = serde_json::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		serde_json::from_str(s)
+	}
+}
+
+impl Display for InheritedManifest {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&serde_json::to_string(self).map_err(|_| std::fmt::Error)?)
+	}
+}
@@ -1,6 +1,8 @@
 pub mod assets;
+pub mod inherit;
 pub mod list;
 pub mod manifest;
+pub mod maven;
 pub mod platform;
 pub mod profiles;
 pub mod rule;
@@ -10,6 +12,7 @@ pub mod shared;
 mod test;
 
 pub use assets::AssetIndex;
+pub use inherit::InheritedManifest;
 pub use manifest::Manifest;
 pub use profiles::LauncherProfiles;
 pub use runtime::{RuntimeComponents, RuntimeManifest};
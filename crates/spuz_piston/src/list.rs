@@ -18,6 +18,6 @@ pub struct VersionRef {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Versions {
-	latest: Latest,
-	versions: Arr<VersionRef>,
+	pub latest: Latest,
+	pub versions: Arr<VersionRef>,
 }
@@ -24,6 +24,8 @@ pub enum ListOrValue<T> {
 #[serde(rename_all = "camelCase")]
 pub struct AssetIndexRef {
 	pub id: Str,
+	pub sha1: Str,
+	pub size: Size,
 	pub url: Str,
 	pub total_size: Size,
 }
@@ -45,7 +47,9 @@ pub enum Argument {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Arguments {
+	#[serde(default)]
 	pub game: Arr<Argument>,
+	#[serde(default)]
 	pub jvm: Arr<Argument>,
 }
 
@@ -54,6 +58,15 @@ pub struct Library {
 	pub name: Str,
 	pub downloads: Specifiers,
 	pub rules: Option<Arr<Rule>>,
+	/// How the native classifier is unpacked into the natives directory
+	pub extract: Option<Extract>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Extract {
+	/// Prefixes of the entries left out, e.g. `META-INF/`
+	#[serde(default)]
+	pub exclude: Arr<Str>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -92,6 +105,14 @@ pub struct ClientLogging {
 	pub r#type: Str,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JavaVersion {
+	/// Runtime component, e.g. `java-runtime-delta`
+	pub component: Str,
+	pub major_version: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Logging {
 	pub client: ClientLogging,
@@ -119,6 +140,9 @@ pub struct Manifest {
 	pub arguments: Arguments,
 	pub downloads: Downloads,
 	pub logging: Logging,
+	/// Java the version is made for, missing in manifests of old versions
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub java_version: Option<JavaVersion>,
 }
 
 impl FromStr for Manifest {
@@ -131,6 +155,6 @@ impl FromStr for Manifest {
 
 impl Display for Manifest {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		serde_json::to_string(self).map_err(|_| std::fmt::Error).fmt(f)
+		f.write_str(&serde_json::to_string(self).map_err(|_| std::fmt::Error)?)
 	}
 }
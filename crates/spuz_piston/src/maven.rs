@@ -0,0 +1,101 @@
+use std::{
+	fmt::{Display, Formatter},
+	path::PathBuf,
+	str::FromStr,
+};
+
+/// Maven artifact coordinate in the
+/// `group:artifact:version[:classifier][@extension]` form, as used in library
+/// names
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Coordinate {
+	pub group: Box<str>,
+	pub artifact: Box<str>,
+	pub version: Box<str>,
+	pub classifier: Option<Box<str>>,
+	pub extension: Box<str>,
+}
+
+impl Coordinate {
+	/// File name of the artifact, e.g. `fabric-loader-0.15.11.jar`
+	pub fn file_name(&self) -> String {
+		let Self { artifact, version, extension, .. } = self;
+		match &self.classifier {
+			Some(classifier) => format!("{artifact}-{version}-{classifier}.{extension}"),
+			None => format!("{artifact}-{version}.{extension}"),
+		}
+	}
+
+	/// Relative path of the artifact in the maven repository, e.g.
+	/// `net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar`
+	pub fn path(&self) -> PathBuf {
+		let mut path: PathBuf = self.group.split('.').collect();
+		path.push(&*self.artifact);
+		path.push(&*self.version);
+		path.push(self.file_name());
+		path
+	}
+
+	/// Same as [`path`](Self::path), but always `/` separated to be joined to the
+	/// repository url
+	pub fn url_path(&self) -> String {
+		format!("{}/{}/{}/{}", self.group.replace('.', "/"), self.artifact, self.version, self.file_name())
+	}
+
+	/// Key to identify the same library regardless of its version
+	pub fn unversioned(&self) -> (&str, &str, Option<&str>) {
+		(&self.group, &self.artifact, self.classifier.as_deref())
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCoordinateError(pub Box<str>);
+
+impl Display for ParseCoordinateError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Invalid maven coordinate: `{}`", self.0)
+	}
+}
+
+impl std::error::Error for ParseCoordinateError {}
+
+impl FromStr for Coordinate {
+	type Err = ParseCoordinateError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (coordinate, extension) = s.split_once('@').unwrap_or((s, "jar"));
+		let mut parts = coordinate.split(':');
+
+		let mut next = || parts.next().filter(|it| !it.is_empty()).map(Box::<str>::from);
+		let (Some(group), Some(artifact), Some(version)) = (next(), next(), next()) else {
+			return Err(ParseCoordinateError(s.into()));
+		};
+		let classifier = next();
+
+		// Coordinates are joined to the libraries directory, so no part may
+		// introduce a separator or a parent directory
+		let valid = group.split('.').chain([&*artifact, &*version, extension]).chain(classifier.as_deref()).all(is_segment);
+		if parts.next().is_some() || !valid {
+			return Err(ParseCoordinateError(s.into()));
+		}
+
+		Ok(Self { group, artifact, version, classifier, extension: extension.into() })
+	}
+}
+
+fn is_segment(it: &str) -> bool {
+	!it.is_empty() && it != "." && it != ".." && !it.contains(['/', '\\'])
+}
+
+impl Display for Coordinate {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}:{}", self.group, self.artifact, self.version)?;
+		if let Some(classifier) = &self.classifier {
+			write!(f, ":{classifier}")?;
+		}
+		if &*self.extension != "jar" {
+			write!(f, "@{}", self.extension)?;
+		}
+		Ok(())
+	}
+}
@@ -233,6 +233,6 @@ impl FromStr for RuntimeManifest {
 
 impl Display for RuntimeManifest {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		serde_json::to_string(self).map_err(|_| std::fmt::Error).fmt(f)
+		f.write_str(&serde_json::to_string(self).map_err(|_| std::fmt::Error)?)
 	}
 }
@@ -0,0 +1,76 @@
+use std::str::FromStr;
+
+use crate::{InheritedManifest, Manifest};
+
+const FABRIC: &str = r#"{
+	"id": "fabric-loader-0.15.11-1.20.6",
+	"inheritsFrom": "1.20.6",
+	"releaseTime": "2024-05-09T10:52:01+0000",
+	"time": "2024-05-09T10:52:01+0000",
+	"type": "release",
+	"mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+	"arguments": {
+		"game": [],
+		"jvm": ["-DFabricMcEmu= net.minecraft.client.main.Main "]
+	},
+	"libraries": [
+		{
+			"name": "org.ow2.asm:asm:9.7",
+			"downloads": {
+				"artifact": {
+					"path": "org/ow2/asm/asm/9.7/asm-9.7.jar",
+					"sha1": "073d7b3086e14beb604ced229c302feff6449723",
+					"size": 125428,
+					"url": "https://maven.fabricmc.net/org/ow2/asm/asm/9.7/asm-9.7.jar"
+				}
+			}
+		}
+	]
+}"#;
+
+#[test]
+fn parse_inherited_manifest() {
+	let parsed = InheritedManifest::from_str(FABRIC);
+	if let Err(err) = &parsed {
+		eprintln!("{err:?}");
+	}
+	assert!(parsed.is_ok());
+}
+
+#[test]
+fn inherit_manifest() {
+	let parent = Manifest::from_str(include_str!("../../../../versions/1.20.6.json")).unwrap();
+	let child = InheritedManifest::from_str(FABRIC).unwrap();
+
+	let merged = child.inherit(parent.clone());
+
+	assert_eq!(&*merged.id, "fabric-loader-0.15.11-1.20.6");
+	assert_eq!(&*merged.main_class, "net.fabricmc.loader.impl.launch.knot.KnotClient");
+	assert_eq!(merged.asset_index, parent.asset_index);
+	assert_eq!(merged.downloads, parent.downloads);
+	assert_eq!(&*merged.libraries[0].name, "org.ow2.asm:asm:9.7");
+	assert_eq!(merged.libraries.len(), parent.libraries.len() + 1);
+	assert_eq!(merged.arguments.game, parent.arguments.game);
+	assert_eq!(merged.arguments.jvm.len(), parent.arguments.jvm.len() + 1);
+}
+
+#[test]
+fn inherit_replaces_libraries() {
+	let parent = Manifest::from_str(include_str!("../../../../versions/1.20.6.json")).unwrap();
+	let child = FABRIC.replace("org.ow2.asm:asm:9.7", "com.google.code.gson:gson:2.99");
+	let child = InheritedManifest::from_str(&child).unwrap();
+
+	let merged = child.inherit(parent.clone());
+
+	assert_eq!(merged.libraries.len(), parent.libraries.len());
+	assert_eq!(merged.libraries.iter().filter(|it| it.name.starts_with("com.google.code.gson:gson:")).count(), 1);
+}
+
+#[test]
+fn display_round_trip() {
+	let parent = Manifest::from_str(include_str!("../../../../versions/1.20.6.json")).unwrap();
+	let child = InheritedManifest::from_str(FABRIC).unwrap();
+
+	assert_eq!(Manifest::from_str(&parent.to_string()).unwrap(), parent);
+	assert_eq!(InheritedManifest::from_str(&child.to_string()).unwrap(), child);
+}
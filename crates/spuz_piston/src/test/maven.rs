@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use crate::maven::Coordinate;
+
+#[test]
+fn parse_coordinate() {
+	let coordinate: Coordinate = "net.fabricmc:fabric-loader:0.15.11".parse().unwrap();
+	assert_eq!(&*coordinate.group, "net.fabricmc");
+	assert_eq!(&*coordinate.artifact, "fabric-loader");
+	assert_eq!(&*coordinate.version, "0.15.11");
+	assert_eq!(coordinate.classifier, None);
+	assert_eq!(&*coordinate.extension, "jar");
+	assert_eq!(
+		coordinate.path(),
+		PathBuf::from_iter(["net", "fabricmc", "fabric-loader", "0.15.11", "fabric-loader-0.15.11.jar"])
+	);
+	assert_eq!(coordinate.url_path(), "net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar");
+	assert_eq!(coordinate.to_string(), "net.fabricmc:fabric-loader:0.15.11");
+}
+
+#[test]
+fn parse_coordinate_with_classifier_and_extension() {
+	let coordinate: Coordinate = "de.oceanlabs.mcp:mcp_config:1.20.6-20240429.135109:mappings@txt".parse().unwrap();
+	assert_eq!(coordinate.classifier.as_deref(), Some("mappings"));
+	assert_eq!(&*coordinate.extension, "txt");
+	assert_eq!(
+		coordinate.url_path(),
+		"de/oceanlabs/mcp/mcp_config/1.20.6-20240429.135109/mcp_config-1.20.6-20240429.135109-mappings.txt"
+	);
+	assert_eq!(coordinate.to_string(), "de.oceanlabs.mcp:mcp_config:1.20.6-20240429.135109:mappings@txt");
+}
+
+#[test]
+fn parse_invalid_coordinate() {
+	assert!("net.fabricmc:fabric-loader".parse::<Coordinate>().is_err());
+	assert!("net.fabricmc::0.15.11".parse::<Coordinate>().is_err());
+	assert!("a:b:c:d:e".parse::<Coordinate>().is_err());
+	assert!("a:b:c@".parse::<Coordinate>().is_err());
+}
+
+#[test]
+fn parse_escaping_coordinate() {
+	assert!("net.fabricmc:..:0.15.11".parse::<Coordinate>().is_err());
+	assert!("net.fabricmc:fabric-loader:../../..".parse::<Coordinate>().is_err());
+	assert!("net/../..:fabric-loader:0.15.11".parse::<Coordinate>().is_err());
+	assert!("net..fabricmc:fabric-loader:0.15.11".parse::<Coordinate>().is_err());
+	assert!("net.fabricmc:fabric-loader:0.15.11:..\\x".parse::<Coordinate>().is_err());
+	assert!("net.fabricmc:fabric-loader:0.15.11@/jar".parse::<Coordinate>().is_err());
+}
@@ -1,2 +1,6 @@
+#![allow(clippy::unwrap_used)]
+
+mod inherit;
+mod maven;
 mod runtime;
 mod versions;
@@ -21,6 +21,7 @@ pub struct CommandBuilder {
 	main_class: String,
 	java_args: Vec<String>,
 	app_args: Vec<String>,
+	env: Vec<(String, String)>,
 }
 
 impl CommandBuilder {
@@ -38,10 +39,16 @@ impl CommandBuilder {
 		});
 	}
 
+	/// Sets the environment variable of the game process
+	pub fn env(&mut self, key: impl Into<String>, value: impl Into<String>) {
+		self.env.push((key.into(), value.into()));
+	}
+
 	pub fn build(&self) -> LaunchCommand {
 		let mut cmd = Command::new(&self.bin);
 		cmd
 			.current_dir(&self.current_dir)
+			.envs(self.env.iter().map(|(key, value)| (key, value)))
 			.args(&self.java_args) // Jvm args
 			.arg(&self.main_class) // Main class
 			.args(&self.app_args) // App args (minecraft args)
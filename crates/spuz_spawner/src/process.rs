@@ -1,10 +1,9 @@
-use async_channel::Receiver;
-use thiserror::Error;
 use tokio::process::Command;
 #[cfg(feature = "process-handle")]
 use {
-	async_channel::unbounded,
+	async_channel::{unbounded, Receiver},
 	std::sync::Arc,
+	thiserror::Error,
 	tokio::{io::AsyncReadExt, sync::Notify},
 };
 
@@ -96,6 +95,7 @@ pub struct ProcessHandle {
 	pub logs: Receiver<String>,
 }
 
+#[cfg(feature = "process-handle")]
 #[derive(Debug, Error)]
 #[error("Failed to spawn process: {source}")]
 pub struct SpawnError {
@@ -55,6 +55,20 @@ impl<'a> Layer for NativesDir<'a> {
 	}
 }
 
+#[derive(Debug)]
+pub struct LibrariesDir<'a>(pub &'a Path);
+
+impl Layer for LibrariesDir<'_> {
+	fn apply(self, launch_mod: &mut LaunchMod) {
+		let str_path = self.0.to_string_lossy();
+		set_vars!(launch_mod.java_args, {
+			"library_directory" => &str_path,
+			"classpath_separator" => SEP,
+		});
+		debug!("Libraries directory set to {:?}", self.0);
+	}
+}
+
 #[derive(Debug)]
 pub struct VersionInfo<'a> {
 	pub id: &'a str,
@@ -67,9 +81,9 @@ impl Layer for VersionInfo<'_> {
 		set_vars!(launch_mod.app_args, {
 			"version_name" => self.id,
 			"version_type" => self.version_type,
-			"user_type" => "msa",
 			"assets_index_name" => self.asset_index_id,
 		});
+		set_vars!(launch_mod.java_args, "version_name", self.id);
 	}
 }
 
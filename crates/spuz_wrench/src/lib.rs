@@ -2,8 +2,13 @@ mod internal;
 pub mod macros;
 mod mandep;
 mod opts;
+#[cfg(test)]
+mod test;
 
-use std::{collections::HashSet, path::Path};
+use std::{
+	collections::HashSet,
+	path::{Path, PathBuf},
+};
 
 use spuz_piston::{
 	rule::{Feature, RuleCompilance},
@@ -12,7 +17,10 @@ use spuz_piston::{
 use spuz_spawner::{LaunchMod, Layer};
 use typed_builder::TypedBuilder;
 
-pub use crate::opts::{Fullscreen, LauncherInfo, Player, WindowSize};
+pub use crate::opts::{
+	offline_uuid, validate_username, AuthlibInjector, Fullscreen, LauncherInfo, Player, UserType, UsernameError,
+	WindowSize,
+};
 use crate::{
 	internal::{AssersDir, GameDir, NativesDir},
 	mandep::ManifestLayer,
@@ -35,6 +43,15 @@ pub struct LauncherWrench<'a> {
 	pub features: HashSet<Feature>,
 }
 
+impl LauncherWrench<'_> {
+	/// Libraries the game is launched with, to check that they are present
+	pub fn libraries(&self) -> Vec<PathBuf> {
+		let rule_compilance = RuleCompilance::new(self.features.clone());
+		let libraries_dir = self.libraries_dir.unwrap_or(Path::new("libraries"));
+		mandep::libraries(&rule_compilance, self.manifest, libraries_dir).collect()
+	}
+}
+
 impl<'a> Layer for LauncherWrench<'a> {
 	fn apply(self, launch_mod: &mut LaunchMod) {
 		let rule_compilance = RuleCompilance::new(self.features);
@@ -1,4 +1,7 @@
-use std::{iter, path::Path};
+use std::{
+	iter,
+	path::{Path, PathBuf},
+};
 
 use spuz_piston::{
 	manifest::{Argument, Arguments, ListOrValue},
@@ -8,7 +11,7 @@ use spuz_piston::{
 };
 use spuz_spawner::{LaunchMod, Layer};
 
-use crate::internal::{Classpath, VersionInfo};
+use crate::internal::{Classpath, LibrariesDir, VersionInfo};
 
 #[derive(Debug)]
 pub struct ManifestLayer<'a> {
@@ -24,33 +27,13 @@ impl<'a> Layer for ManifestLayer<'a> {
 
 		push_modern_arguments(self.rulecomp, &self.manifest.arguments, launch_mod);
 
-		let check_rule = |rule: &Rule| self.rulecomp.is_met(rule);
-
-		let iter = self
-			.manifest
-			.libraries
-			.iter()
-			.filter(|lib| match &lib.rules {
-				Some(rules) => rules.iter().all(check_rule),
-				None => true,
-			})
-			.flat_map(|lib| {
-				let main_lib_path = lib.downloads.artifact.as_ref().map(|it| self.libraries_dir.join(&it.path));
-
-				let target_classifier = NativeClassifier::from(TARGET_OS);
-				if let Some(classifier) = lib.downloads.classifiers.as_ref().and_then(|it| it.get(&target_classifier)) {
-					let native_lib_path = self.libraries_dir.join(&classifier.path);
-					[main_lib_path, Some(native_lib_path)]
-				} else {
-					[main_lib_path, None]
-				}
-			})
-			.flatten();
+		let iter = libraries(self.rulecomp, self.manifest, self.libraries_dir);
 
 		let classpath = iter.chain(iter::once(self.client_jar.to_owned()));
 
 		let layers = (
 			Classpath(classpath),
+			LibrariesDir(self.libraries_dir),
 			VersionInfo { id: &self.manifest.id, version_type: &self.manifest.r#type, asset_index_id: &self.manifest.assets },
 		);
 
@@ -58,6 +41,36 @@ impl<'a> Layer for ManifestLayer<'a> {
 	}
 }
 
+/// Paths of the libraries of the manifest that apply to the current platform,
+/// natives included
+pub(crate) fn libraries<'a>(
+	rulecomp: &'a RuleCompilance,
+	manifest: &'a Manifest,
+	libraries_dir: &'a Path,
+) -> impl Iterator<Item = PathBuf> + 'a {
+	let check_rule = |rule: &Rule| rulecomp.is_met(rule);
+
+	manifest
+		.libraries
+		.iter()
+		.filter(move |lib| match &lib.rules {
+			Some(rules) => rules.iter().all(check_rule),
+			None => true,
+		})
+		.flat_map(move |lib| {
+			let main_lib_path = lib.downloads.artifact.as_ref().map(|it| libraries_dir.join(&it.path));
+
+			let target_classifier = NativeClassifier::from(TARGET_OS);
+			if let Some(classifier) = lib.downloads.classifiers.as_ref().and_then(|it| it.get(&target_classifier)) {
+				let native_lib_path = libraries_dir.join(&classifier.path);
+				[main_lib_path, Some(native_lib_path)]
+			} else {
+				[main_lib_path, None]
+			}
+		})
+		.flatten()
+}
+
 fn push_modern_arguments(rulcomp: &RuleCompilance, args: &Arguments, launch_mod: &mut LaunchMod) {
 	for arg in &args.jvm {
 		push_modern_arg(rulcomp, launch_mod.java_args, arg);
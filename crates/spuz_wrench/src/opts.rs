@@ -1,6 +1,35 @@
+use std::{fmt::Write, path::PathBuf};
+
+use md5::{Digest, Md5};
 use spuz_spawner::{LaunchMod, Layer};
+use thiserror::Error;
 use tracing::debug;
 
+/// Access token passed to the game for players without one
+const NO_ACCESS_TOKEN: &str = "0";
+
+/// How the player was authenticated, the `${user_type}` argument
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UserType {
+	/// Microsoft account
+	#[default]
+	Msa,
+	/// Offline player, no authentication at all
+	Legacy,
+	/// Account of a yggdrasil server, e.g. one used with [`AuthlibInjector`]
+	Mojang,
+}
+
+impl UserType {
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Msa => "msa",
+			Self::Legacy => "legacy",
+			Self::Mojang => "mojang",
+		}
+	}
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Player {
 	pub username: String,
@@ -8,12 +37,56 @@ pub struct Player {
 	pub access_token: Option<String>,
 	pub client_id: Option<String>,
 	pub xuid: Option<String>,
+	pub user_type: UserType,
 }
 
 impl Player {
 	pub fn new(username: impl Into<String>, uuid: impl Into<String>) -> Self {
 		Self { username: username.into(), uuid: uuid.into(), ..Default::default() }
 	}
+
+	/// Offline player with the uuid the server derives for it in offline mode
+	pub fn offline(username: impl Into<String>) -> Result<Self, UsernameError> {
+		let username = username.into();
+		validate_username(&username)?;
+
+		let uuid = offline_uuid(&username);
+		Ok(Self { username, uuid, user_type: UserType::Legacy, ..Default::default() })
+	}
+}
+
+/// Usernames are 3 to 16 ascii letters, digits or underscores
+pub fn validate_username(username: &str) -> Result<(), UsernameError> {
+	let len = username.chars().count();
+	if !(3..=16).contains(&len) {
+		return Err(UsernameError::Length(len));
+	}
+	match username.chars().find(|it| !it.is_ascii_alphanumeric() && *it != '_') {
+		Some(char) => Err(UsernameError::Char(char)),
+		None => Ok(()),
+	}
+}
+
+/// Name based (v3) uuid of `OfflinePlayer:<username>`, as java's
+/// `UUID.nameUUIDFromBytes` computes it. Written without dashes, like the ids
+/// of online profiles
+pub fn offline_uuid(username: &str) -> String {
+	let mut hash: [u8; 16] = Md5::digest(format!("OfflinePlayer:{username}")).into();
+	hash[6] = hash[6] & 0x0f | 0x30;
+	hash[8] = hash[8] & 0x3f | 0x80;
+
+	hash.iter().fold(String::with_capacity(32), |mut uuid, byte| {
+		let _ = write!(uuid, "{byte:02x}");
+		uuid
+	})
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum UsernameError {
+	#[error("Username must be 3 to 16 characters long, got {0}")]
+	Length(usize),
+	#[error("Username can not contain `{0}`, only letters, digits and underscores")]
+	Char(char),
 }
 
 impl Layer for Player {
@@ -21,15 +94,16 @@ impl Layer for Player {
 		for arg in &mut *launch_mod.app_args {
 			*arg = arg.replace("${auth_player_name}", &self.username);
 			*arg = arg.replace("${auth_uuid}", &self.uuid);
+			*arg = arg.replace("${user_type}", self.user_type.as_str());
 			if let Some(xuid) = &self.xuid {
 				*arg = arg.replace("${auth_xuid}", xuid);
 			}
 			if let Some(client_id) = &self.client_id {
 				*arg = arg.replace("${clientid}", client_id);
 			}
-			if let Some(access_token) = &self.access_token {
-				*arg = arg.replace("${auth_access_token}", access_token);
-			}
+			// The game does not start without a token, offline players get a
+			// placeholder
+			*arg = arg.replace("${auth_access_token}", self.access_token.as_deref().unwrap_or(NO_ACCESS_TOKEN));
 		}
 
 		debug!("Player set to: {}:{}", self.username, self.uuid);
@@ -59,6 +133,43 @@ impl Layer for LauncherInfo {
 	}
 }
 
+/// Routes authentication and skins of the game to a yggdrasil server with the
+/// [authlib-injector](https://github.com/yushijinhun/authlib-injector) agent
+#[derive(Debug, Clone)]
+pub struct AuthlibInjector {
+	/// Path of `authlib-injector.jar`
+	pub agent: PathBuf,
+	/// Api root of the yggdrasil server
+	pub api_root: String,
+	/// Base64 of the api root response. Saves the agent from requesting it
+	/// on every launch
+	pub prefetched: Option<String>,
+}
+
+impl AuthlibInjector {
+	pub fn new(agent: impl Into<PathBuf>, api_root: impl Into<String>) -> Self {
+		Self { agent: agent.into(), api_root: api_root.into(), prefetched: None }
+	}
+
+	#[must_use]
+	pub fn prefetched(mut self, prefetched: impl Into<String>) -> Self {
+		self.prefetched = Some(prefetched.into());
+		self
+	}
+}
+
+impl Layer for AuthlibInjector {
+	fn apply(self, launch_mod: &mut LaunchMod) {
+		let agent = self.agent.to_string_lossy();
+		launch_mod.java_args.push(format!("-javaagent:{agent}={}", self.api_root));
+		if let Some(prefetched) = &self.prefetched {
+			launch_mod.java_args.push(format!("-Dauthlibinjector.yggdrasil.prefetched={prefetched}"));
+		}
+
+		debug!("Authlib injector set to {}", self.api_root);
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct WindowSize {
 	pub width: u32,
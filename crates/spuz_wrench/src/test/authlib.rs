@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use spuz_spawner::{LaunchMod, Layer};
+
+use crate::AuthlibInjector;
+
+fn java_args(layer: AuthlibInjector) -> Vec<String> {
+	let mut main_class = String::new();
+	let mut current_dir = PathBuf::new();
+	let mut java_args = Vec::new();
+	let mut app_args = Vec::new();
+	layer.apply(&mut LaunchMod {
+		main_class: &mut main_class,
+		current_dir: &mut current_dir,
+		java_args: &mut java_args,
+		app_args: &mut app_args,
+	});
+	java_args
+}
+
+#[test]
+fn agent_argument() {
+	let args = java_args(AuthlibInjector::new("authlib-injector.jar", "https://skins.example.com/api/yggdrasil"));
+	assert_eq!(args, ["-javaagent:authlib-injector.jar=https://skins.example.com/api/yggdrasil"]);
+}
+
+#[test]
+fn prefetched_metadata() {
+	let args = java_args(AuthlibInjector::new("authlib-injector.jar", "https://skins.example.com").prefetched("e30="));
+	assert_eq!(
+		args,
+		["-javaagent:authlib-injector.jar=https://skins.example.com", "-Dauthlibinjector.yggdrasil.prefetched=e30="]
+	);
+}
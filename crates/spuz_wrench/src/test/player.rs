@@ -0,0 +1,42 @@
+use spuz_spawner::{LaunchMod, Layer};
+
+use crate::{offline_uuid, Player, UserType, UsernameError};
+
+#[test]
+fn offline_uuid_matches_java() {
+	assert_eq!(offline_uuid("Notch"), "b50ad385829d3141a2167e7d7539ba7f");
+}
+
+#[test]
+fn offline_player() {
+	let player = Player::offline("Notch").unwrap();
+	assert_eq!(player.uuid, "b50ad385829d3141a2167e7d7539ba7f");
+	assert_eq!(player.user_type, UserType::Legacy);
+	assert_eq!(player.access_token, None);
+}
+
+#[test]
+fn invalid_usernames() {
+	assert_eq!(Player::offline("ab").unwrap_err(), UsernameError::Length(2));
+	assert_eq!(Player::offline("a".repeat(17)).unwrap_err(), UsernameError::Length(17));
+	assert_eq!(Player::offline("not a name").unwrap_err(), UsernameError::Char(' '));
+	assert_eq!(Player::offline("имя_игрока").unwrap_err(), UsernameError::Char('и'));
+	assert!(Player::offline("Player_123").is_ok());
+}
+
+#[test]
+fn user_type_argument() {
+	let mut main_class = String::new();
+	let mut current_dir = std::path::PathBuf::new();
+	let mut java_args = Vec::new();
+	let mut app_args = ["--userType", "${user_type}", "--accessToken", "${auth_access_token}"].map(String::from).to_vec();
+	let mut launch_mod = LaunchMod {
+		main_class: &mut main_class,
+		current_dir: &mut current_dir,
+		java_args: &mut java_args,
+		app_args: &mut app_args,
+	};
+
+	Player::offline("Notch").unwrap().apply(&mut launch_mod);
+	assert_eq!(app_args, ["--userType", "legacy", "--accessToken", "0"]);
+}